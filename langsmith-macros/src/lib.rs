@@ -0,0 +1,136 @@
+//! Procedural macros for `langsmith-rust`. Use them through the re-exports in
+//! that crate (`langsmith_rust::trace`).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, FnArg, ItemFn, LitStr, Pat, ReturnType, Type};
+
+/// Traces an `async fn` as a LangSmith run, like wrapping its body in `trace_node`.
+///
+/// ```ignore
+/// #[trace(run_type = "llm", name = "call_model")]
+/// async fn call_model(prompt: String, temperature: f64) -> langsmith_rust::Result<String> {
+///     // ...
+/// }
+/// ```
+///
+/// - `name` defaults to the function name and `run_type` to `"chain"`;
+/// - named arguments (except `self`) are serialized into the run's inputs,
+///   keyed by argument name, so they must implement `Serialize` (one that fails
+///   to serialize is reported and recorded as `"<unserializable>"`);
+/// - the function must return `langsmith_rust::Result<T>` with `T: Serialize`;
+///   the `Ok` value becomes the run's outputs and an `Err` its error.
+///
+/// When tracing is disabled the arguments aren't serialized and the body runs as is.
+#[proc_macro_attribute]
+pub fn trace(args: TokenStream, item: TokenStream) -> TokenStream {
+    let function = parse_macro_input!(item as ItemFn);
+    let mut name: Option<LitStr> = None;
+    let mut run_type: Option<LitStr> = None;
+
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("run_type") {
+            run_type = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `name` or `run_type`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    expand(function, name, run_type)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(
+    function: ItemFn,
+    name: Option<LitStr>,
+    run_type: Option<LitStr>,
+) -> syn::Result<TokenStream2> {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = function;
+
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            sig.fn_token,
+            "#[trace] can only be used on async functions",
+        ));
+    }
+    if !returns_result(&sig.output) {
+        return Err(syn::Error::new_spanned(
+            &sig.output,
+            "#[trace] functions must return langsmith_rust::Result<T>",
+        ));
+    }
+
+    let name = name.unwrap_or_else(|| LitStr::new(&sig.ident.to_string(), sig.ident.span()));
+    let run_type =
+        run_type.unwrap_or_else(|| LitStr::new("chain", proc_macro2::Span::call_site()));
+
+    let inputs = sig.inputs.iter().filter_map(|arg| match arg {
+        FnArg::Typed(arg) => match &*arg.pat {
+            Pat::Ident(pat) => Some(&pat.ident),
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
+    });
+    let keys = inputs.clone().map(|ident| ident.to_string());
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            let __langsmith_inputs = if ::langsmith_rust::Config::is_tracing_enabled() {
+                let mut __langsmith_inputs_map =
+                    ::langsmith_rust::__private::serde_json::Map::new();
+                #(
+                    __langsmith_inputs_map.insert(
+                        #keys.to_string(),
+                        ::langsmith_rust::__private::input_value(&#inputs),
+                    );
+                )*
+                Some(::langsmith_rust::__private::serde_json::Value::Object(
+                    __langsmith_inputs_map,
+                ))
+            } else {
+                None
+            };
+            let __langsmith_body = move || async move #block;
+            match __langsmith_inputs {
+                Some(__langsmith_inputs) => {
+                    ::langsmith_rust::tracing::decorator::trace_node(
+                        #name,
+                        ::langsmith_rust::RunType::from(#run_type),
+                        __langsmith_inputs,
+                        |_| __langsmith_body(),
+                    )
+                    .await
+                }
+                None => __langsmith_body().await,
+            }
+        }
+    })
+}
+
+/// Whether the declared return type is some `Result<..>`
+fn returns_result(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    match &**ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Result"),
+        _ => false,
+    }
+}
@@ -0,0 +1,288 @@
+use crate::client::http::{
+    deadline_exceeded, encode_json_body, is_retryable_status, request_error, retry_backoff,
+    validated_run, DeliveryBudgets, JsonBody, ProjectCreate, TlsSettings,
+};
+use crate::config::Config;
+use crate::error::{report_trace_error, LangSmithError, Result};
+use crate::models::attachment::Attachment;
+use crate::models::run::{Run, RunUpdate};
+use reqwest::blocking::multipart::{Form, Part};
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use std::io::Cursor;
+use std::time::Instant;
+use uuid::Uuid;
+
+/// Synchronous counterpart of `LangSmithClient` for CLI tools and services
+/// without an async runtime, built on `reqwest::blocking`.
+///
+/// Like any `reqwest::blocking` client it must not be used from inside an
+/// async context; use `LangSmithClient` there.
+pub struct BlockingLangSmithClient {
+    client: Client,
+    config: Config,
+    budgets: DeliveryBudgets,
+}
+
+impl BlockingLangSmithClient {
+    pub fn new() -> Result<Self> {
+        let config = Config::get()?;
+        Self::try_with_config(config)
+    }
+
+    /// Falls back to a client without the TLS settings in `config` when they
+    /// can't be loaded, after reporting the error; use `try_with_config` to
+    /// handle it instead.
+    pub fn with_config(config: Config) -> Self {
+        Self::try_with_config(config.clone()).unwrap_or_else(|e| {
+            report_trace_error(Some("client"), &e);
+            let client = Client::builder().timeout(config.timeout).build().unwrap_or_default();
+            Self::with_http_client(config, client)
+        })
+    }
+
+    /// Like `with_config`, returning `LangSmithError::Config` when the TLS
+    /// settings in `config` can't be loaded.
+    pub fn try_with_config(config: Config) -> Result<Self> {
+        let mut builder = Client::builder().timeout(config.timeout);
+
+        let tls = TlsSettings::from_config(&config)?;
+        if let Some(cert) = tls.root_certificate {
+            builder = builder.add_root_certificate(cert);
+        }
+        if let Some(identity) = tls.identity {
+            builder = builder.identity(identity);
+        }
+
+        Ok(Self::with_http_client(config, builder.build()?))
+    }
+
+    /// Uses a pre-built `reqwest::blocking::Client`. The TLS and timeout
+    /// settings in `config` are not applied to it.
+    pub fn with_http_client(config: Config, client: Client) -> Self {
+        Self {
+            client,
+            config,
+            budgets: DeliveryBudgets::default(),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Creates the run, validating it first like `LangSmithClient::post_run`.
+    /// With `Config::ensure_project`, a missing project is created and the post
+    /// retried.
+    pub fn post_run(&self, run: &Run) -> Result<()> {
+        if !self.config.tracing_enabled {
+            return Err(LangSmithError::TracingDisabled);
+        }
+        let run = validated_run(&self.config, run)?;
+        let run = run.as_ref();
+        if let Some(sink) = &self.config.sink {
+            sink.post_run(run);
+            return Ok(());
+        }
+
+        let url = format!("{}/runs", self.config.endpoint);
+        let delivery = self.budgets.start(&self.config, run.id, false);
+        let deadline = delivery.deadline;
+        let result = self.send_json(Method::POST, &url, run, deadline);
+        let response = match (result, &self.config.truncation) {
+            (Err(LangSmithError::PayloadTooLarge { .. }), Some(strategy)) => {
+                self.send_json(Method::POST, &url, &strategy.truncate_run(run), deadline)?
+            }
+            (result, _) => result?,
+        };
+
+        let project = run.session_name.as_deref().or(self.config.project.as_deref());
+        let response = match project {
+            Some(project)
+                if self.config.ensure_project && response.status() == StatusCode::NOT_FOUND =>
+            {
+                self.create_project(project, None)?;
+                self.send_json(Method::POST, &url, run, deadline)?
+            }
+            _ => response,
+        };
+        if self.config.upsert_posts && response.status() == StatusCode::CONFLICT {
+            return Ok(());
+        }
+        error_for_status(response)?;
+        Ok(())
+    }
+
+    /// Blocking version of `LangSmithClient::post_run_multipart`
+    pub fn post_run_multipart(&self, run: &Run, attachments: &[Attachment]) -> Result<()> {
+        if !self.config.tracing_enabled {
+            return Err(LangSmithError::TracingDisabled);
+        }
+        let run = validated_run(&self.config, run)?;
+        let run = run.as_ref();
+        // Sinks only record the run itself
+        if let Some(sink) = &self.config.sink {
+            sink.post_run(run);
+            return Ok(());
+        }
+
+        let url = format!("{}/runs/multipart", self.config.endpoint);
+        let run_json = serde_json::to_vec(run)?;
+        let build_form = || -> Result<Form> {
+            let run_part = Part::bytes(run_json.clone()).mime_str("application/json")?;
+            let mut form = Form::new().part(format!("post.{}", run.id), run_part);
+            for attachment in attachments {
+                let data = Cursor::new(attachment.data.clone());
+                let part = Part::reader_with_length(data, attachment.data.len() as u64)
+                    .file_name(attachment.name.clone())
+                    .mime_str(&attachment.mime_type)?;
+                form = form.part(format!("attachment.{}.{}", run.id, attachment.name), part);
+            }
+            Ok(form)
+        };
+        // Surface an invalid mime type before sending
+        build_form()?;
+
+        let delivery = self.budgets.start(&self.config, run.id, false);
+        let response = self.send_with_retry(
+            || {
+                let request = self.request(Method::POST, &url);
+                match build_form() {
+                    Ok(form) => request.multipart(form),
+                    Err(_) => request,
+                }
+            },
+            delivery.deadline,
+        )?;
+        if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            let size = run_json.len() + attachments.iter().map(|a| a.data.len()).sum::<usize>();
+            return Err(LangSmithError::PayloadTooLarge { size });
+        }
+        if self.config.upsert_posts && response.status() == StatusCode::CONFLICT {
+            return Ok(());
+        }
+        error_for_status(response)?;
+        Ok(())
+    }
+
+    pub fn patch_run(&self, run_id: Uuid, updates: &RunUpdate) -> Result<()> {
+        if !self.config.tracing_enabled {
+            return Err(LangSmithError::TracingDisabled);
+        }
+        if let Some(sink) = &self.config.sink {
+            sink.patch_run(run_id, updates);
+            return Ok(());
+        }
+
+        let url = format!("{}/runs/{}", self.config.endpoint, run_id);
+        let delivery = self.budgets.start(&self.config, run_id, updates.end_time.is_some());
+        let deadline = delivery.deadline;
+        let result = self.send_json(Method::PATCH, &url, updates, deadline);
+        let response = match (result, &self.config.truncation) {
+            (Err(LangSmithError::PayloadTooLarge { .. }), Some(strategy)) => {
+                let truncated = strategy.truncate_update(updates);
+                self.send_json(Method::PATCH, &url, &truncated, deadline)?
+            }
+            (result, _) => result?,
+        };
+        error_for_status(response)?;
+        Ok(())
+    }
+
+    /// Creates a project; one that already exists is not an error
+    pub fn create_project(&self, name: &str, description: Option<&str>) -> Result<()> {
+        let url = format!("{}/sessions", self.config.endpoint);
+        let body = ProjectCreate { name, description };
+        let deadline = self.config.per_run_deadline.map(|budget| Instant::now() + budget);
+        let response = self.send_json(Method::POST, &url, &body, deadline)?;
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(());
+        }
+        error_for_status(response)?;
+        Ok(())
+    }
+
+    fn send_json<T: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        value: &T,
+        deadline: Option<Instant>,
+    ) -> Result<Response> {
+        let body = encode_json_body(&self.config, value)?;
+        let response =
+            self.send_with_retry(|| self.json_request(method.clone(), url, &body), deadline)?;
+
+        if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            return Err(LangSmithError::PayloadTooLarge { size: body.size });
+        }
+        Ok(response)
+    }
+
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .request(method, url)
+            .header("x-api-key", &self.config.api_key);
+        match &self.config.tenant_id {
+            Some(tenant_id) => request.header("x-tenant-id", tenant_id),
+            None => request,
+        }
+    }
+
+    fn json_request(&self, method: Method, url: &str, body: &JsonBody) -> RequestBuilder {
+        let mut request = self
+            .request(method, url)
+            .header(CONTENT_TYPE, "application/json");
+        if body.gzipped {
+            request = request.header(CONTENT_ENCODING, "gzip");
+        }
+        request.body(body.bytes.clone())
+    }
+
+    /// Blocking version of `LangSmithClient::send_with_retry`; attempts are
+    /// cut at the deadline with a request timeout
+    fn send_with_retry<F>(&self, build: F, deadline: Option<Instant>) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+
+        loop {
+            let request = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if remaining.is_zero() => return Err(deadline_exceeded()),
+                    Some(remaining) if remaining < self.config.timeout => {
+                        build().timeout(remaining)
+                    }
+                    Some(_) => build(),
+                    None => return Err(deadline_exceeded()),
+                },
+                None => build(),
+            };
+            let result = request.send();
+            let retryable = match &result {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+
+            match retry_backoff(&self.config, attempt, deadline) {
+                Some(backoff) if retryable => std::thread::sleep(backoff),
+                _ => return result.map_err(request_error),
+            }
+            attempt += 1;
+        }
+    }
+}
+
+fn error_for_status(response: Response) -> Result<Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status = response.status().as_u16();
+    let body = response.text().unwrap_or_default();
+    Err(LangSmithError::Api { status, body })
+}
@@ -1,55 +1,190 @@
+use crate::client::sink::InMemorySink;
 use crate::config::Config;
-use crate::error::{LangSmithError, Result};
+use crate::error::{report_trace_error, LangSmithError, Result};
+use crate::models::attachment::Attachment;
+use crate::models::dataset::{Dataset, Example};
+use crate::models::feedback::Feedback;
+use crate::models::query::{QueryRunsRequest, QueryRunsResponse, RunsPage};
 use crate::models::run::{Run, RunUpdate};
-use reqwest::Client;
+use crate::utils::validation::{normalize_run, validate_run};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_MATCH};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Certificate, Client, Identity, Method, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// Bodies at least this large are gzipped when compression is enabled
+const COMPRESSION_THRESHOLD: usize = 1024;
+
+/// A run fetched together with its server-side version
+#[derive(Debug, Clone)]
+pub struct VersionedRun {
+    pub run: Run,
+    /// Opaque version (ETag), `None` if the server didn't send one
+    pub version: Option<String>,
+}
+
 pub struct LangSmithClient {
     client: Client,
     config: Config,
+    budgets: DeliveryBudgets,
 }
 
 impl LangSmithClient {
     pub fn new() -> Result<Self> {
         let config = Config::get()?;
-        let client = Client::new();
-        Ok(Self { client, config })
+        Self::try_with_config(config)
     }
 
+    /// Falls back to a client without the TLS settings in `config` (CA
+    /// certificate, client identity) when they can't be loaded, after
+    /// reporting the error; use `try_with_config` to handle it instead.
     pub fn with_config(config: Config) -> Self {
-        let client = Client::new();
-        Self { client, config }
+        Self::try_with_config(config.clone()).unwrap_or_else(|e| {
+            report_trace_error(Some("client"), &e);
+            let client = Client::builder().timeout(config.timeout).build().unwrap_or_default();
+            Self::with_http_client(config, client)
+        })
+    }
+
+    /// Like `with_config`, returning `LangSmithError::Config` when the TLS
+    /// settings in `config` can't be loaded.
+    pub fn try_with_config(config: Config) -> Result<Self> {
+        let client = build_http_client(&config)?;
+        Ok(Self::with_http_client(config, client))
+    }
+
+    /// Uses a pre-built `reqwest::Client` (proxies, root certificates, user-agent, ...).
+    /// The TLS and timeout settings in `config` are not applied to it.
+    pub fn with_http_client(config: Config, client: Client) -> Self {
+        Self {
+            client,
+            config,
+            budgets: DeliveryBudgets::default(),
+        }
+    }
+
+    /// A client with tracing enabled that records created and updated runs in
+    /// `sink` instead of sending them. Other requests (queries, feedback,
+    /// datasets) still go to the default endpoint.
+    pub fn in_memory(sink: InMemorySink) -> Self {
+        let config = Config::builder()
+            .tracing_enabled(true)
+            .sink(Arc::new(sink))
+            .build()
+            .expect("the default endpoint is valid");
+        Self::with_http_client(config, Client::new())
     }
 
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Creates the run in LangSmith. Unless `Config::validate_runs` is off, the
+    /// run is checked with `validate_run` first and an invalid run fails with
+    /// `LangSmithError::Config` without sending anything. With
+    /// `Config::upsert_posts`, a run that already exists is not an error.
     pub async fn post_run(&self, run: &Run) -> Result<()> {
         if !self.config.tracing_enabled {
             return Err(LangSmithError::TracingDisabled);
         }
+        let run = validated_run(&self.config, run)?;
+        let run = run.as_ref();
+        if let Some(sink) = &self.config.sink {
+            sink.post_run(run);
+            return Ok(());
+        }
 
         let url = format!("{}/runs", self.config.endpoint);
-        
-        let mut request = self
-            .client
-            .post(&url)
-            .header("x-api-key", &self.config.api_key)
-            .json(run);
+        let delivery = self.budgets.start(&self.config, run.id, false);
+        let deadline = delivery.deadline;
+        let result = self.send_json(Method::POST, &url, run, None, deadline).await;
+        let response = match (result, &self.config.truncation) {
+            (Err(LangSmithError::PayloadTooLarge { .. }), Some(strategy)) => {
+                let truncated = strategy.truncate_run(run);
+                self.send_json(Method::POST, &url, &truncated, None, deadline).await?
+            }
+            (result, _) => result?,
+        };
 
-        if let Some(tenant_id) = &self.config.tenant_id {
-            request = request.header("x-tenant-id", tenant_id);
+        let project = run.session_name.as_deref().or(self.config.project.as_deref());
+        let response = match project {
+            Some(project)
+                if self.config.ensure_project && response.status() == StatusCode::NOT_FOUND =>
+            {
+                self.create_project(project, None).await?;
+                self.send_json(Method::POST, &url, run, None, deadline).await?
+            }
+            _ => response,
+        };
+        if self.config.upsert_posts && response.status() == StatusCode::CONFLICT {
+            return Ok(());
         }
+        error_for_status(response).await?;
+        Ok(())
+    }
 
-        let response = request.send().await?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(LangSmithError::Other(format!(
-                "HTTP {}: {}",
-                status.as_u16(),
-                text
-            )));
+    /// Creates a run together with its attachments in one multipart request
+    /// (`POST /runs/multipart`): a `post.{run_id}` part holding the run JSON
+    /// and an `attachment.{run_id}.{name}` part per attachment.
+    pub async fn post_run_multipart(&self, run: &Run, attachments: &[Attachment]) -> Result<()> {
+        if !self.config.tracing_enabled {
+            return Err(LangSmithError::TracingDisabled);
+        }
+        let run = validated_run(&self.config, run)?;
+        let run = run.as_ref();
+        // Sinks only record the run itself
+        if let Some(sink) = &self.config.sink {
+            sink.post_run(run);
+            return Ok(());
         }
 
+        let url = format!("{}/runs/multipart", self.config.endpoint);
+        let run_json = serde_json::to_vec(run)?;
+        let build_form = || -> Result<Form> {
+            let run_part = Part::bytes(run_json.clone()).mime_str("application/json")?;
+            let mut form = Form::new().part(format!("post.{}", run.id), run_part);
+            for attachment in attachments {
+                let part = Part::stream(attachment.data.clone())
+                    .file_name(attachment.name.clone())
+                    .mime_str(&attachment.mime_type)?;
+                form = form.part(format!("attachment.{}.{}", run.id, attachment.name), part);
+            }
+            Ok(form)
+        };
+        // Surface an invalid mime type before sending
+        build_form()?;
+
+        let delivery = self.budgets.start(&self.config, run.id, false);
+        let response = self
+            .send_with_retry(
+                || {
+                    let request = self.request(Method::POST, &url);
+                    match build_form() {
+                        Ok(form) => request.multipart(form),
+                        Err(_) => request,
+                    }
+                },
+                delivery.deadline,
+            )
+            .await?;
+        if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            let size = run_json.len() + attachments.iter().map(|a| a.data.len()).sum::<usize>();
+            return Err(LangSmithError::PayloadTooLarge { size });
+        }
+        if self.config.upsert_posts && response.status() == StatusCode::CONFLICT {
+            return Ok(());
+        }
+        error_for_status(response).await?;
         Ok(())
     }
 
@@ -58,31 +193,580 @@ impl LangSmithClient {
             return Err(LangSmithError::TracingDisabled);
         }
 
+        self.send_patch(run_id, updates, None).await
+    }
+
+    /// Patches a run only if it is still at `version` (as returned by
+    /// `get_run_versioned`). If the run changed server-side in the meantime the
+    /// patch is rejected with `LangSmithError::Conflict`.
+    pub async fn patch_run_if_match(
+        &self,
+        run_id: Uuid,
+        updates: &RunUpdate,
+        version: &str,
+    ) -> Result<()> {
+        if !self.config.tracing_enabled {
+            return Err(LangSmithError::TracingDisabled);
+        }
+
+        self.send_patch(run_id, updates, Some(version)).await
+    }
+
+    async fn send_patch(
+        &self,
+        run_id: Uuid,
+        updates: &RunUpdate,
+        if_match: Option<&str>,
+    ) -> Result<()> {
+        if let Some(sink) = &self.config.sink {
+            sink.patch_run(run_id, updates);
+            return Ok(());
+        }
+
+        let url = format!("{}/runs/{}", self.config.endpoint, run_id);
+        // The patch ending the run is its last request
+        let delivery = self.budgets.start(&self.config, run_id, updates.end_time.is_some());
+        let deadline = delivery.deadline;
+        let result = self.send_json(Method::PATCH, &url, updates, if_match, deadline).await;
+        let response = match (result, &self.config.truncation) {
+            (Err(LangSmithError::PayloadTooLarge { .. }), Some(strategy)) => {
+                let truncated = strategy.truncate_update(updates);
+                self.send_json(Method::PATCH, &url, &truncated, if_match, deadline).await?
+            }
+            (result, _) => result?,
+        };
+
+        if matches!(response.status(), StatusCode::CONFLICT | StatusCode::PRECONDITION_FAILED) {
+            return Err(LangSmithError::Conflict(format!("run {}", run_id)));
+        }
+
+        error_for_status(response).await?;
+        Ok(())
+    }
+
+    /// Sends several runs in one request. Runs in `post` are created (and may
+    /// already be complete); runs in `patch` update existing runs. Every run is
+    /// checked like in `post_run`, and nothing is sent if one is invalid.
+    pub async fn batch_ingest(&self, post: &[Run], patch: &[Run]) -> Result<()> {
+        if !self.config.tracing_enabled {
+            return Err(LangSmithError::TracingDisabled);
+        }
+        let post = validated_runs(&self.config, post)?;
+        let patch = validated_runs(&self.config, patch)?;
+        let (post, patch) = (post.as_ref(), patch.as_ref());
+        if let Some(sink) = &self.config.sink {
+            post.iter().for_each(|run| sink.post_run(run));
+            patch.iter().for_each(|run| sink.patch_run(run.id, &RunUpdate::from(run)));
+            return Ok(());
+        }
+
+        let url = format!("{}/runs/batch", self.config.endpoint);
+        let response = self
+            .send_json(
+                Method::POST,
+                &url,
+                &BatchRequest { post, patch },
+                None,
+                self.request_deadline(),
+            )
+            .await?;
+        error_for_status(response).await?;
+        Ok(())
+    }
+
+    /// Fetches a run by id. A missing run surfaces as `LangSmithError::NotFound`.
+    pub async fn get_run(&self, run_id: Uuid) -> Result<Run> {
+        Ok(self.get_run_versioned(run_id).await?.run)
+    }
+
+    /// Fetches a run along with its server-side version (the `ETag` header),
+    /// for use with `patch_run_if_match`.
+    pub async fn get_run_versioned(&self, run_id: Uuid) -> Result<VersionedRun> {
         let url = format!("{}/runs/{}", self.config.endpoint, run_id);
-        
+        let response = self
+            .send_with_retry(|| self.request(Method::GET, &url), self.request_deadline())
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(LangSmithError::NotFound(format!("run {}", run_id)));
+        }
+
+        let response = error_for_status(response).await?;
+        let version = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let run = response.json::<Run>().await?;
+        Ok(VersionedRun { run, version })
+    }
+
+    /// Fetches the first page of runs matching `query`. Use `query_runs_page`
+    /// to get the next cursor, or `query_runs_all` to follow it.
+    pub async fn query_runs(&self, query: QueryRunsRequest) -> Result<Vec<Run>> {
+        Ok(self.query_runs_page(&query).await?.runs)
+    }
+
+    /// Fetches one page of runs matching `query` (starting at `query.cursor`).
+    pub async fn query_runs_page(&self, query: &QueryRunsRequest) -> Result<RunsPage> {
+        let url = format!("{}/runs/query", self.config.endpoint);
+        let response = self
+            .send_json(Method::POST, &url, query, None, self.request_deadline())
+            .await?;
+        let response = error_for_status(response).await?;
+        Ok(response.json::<QueryRunsResponse>().await?.into())
+    }
+
+    /// Fetches every page of runs matching `query`, following the cursors.
+    pub async fn query_runs_all(&self, mut query: QueryRunsRequest) -> Result<Vec<Run>> {
+        let mut runs = Vec::new();
+        loop {
+            let page = self.query_runs_page(&query).await?;
+            runs.extend(page.runs);
+            match page.next_cursor {
+                Some(cursor) if query.cursor.as_ref() != Some(&cursor) => {
+                    query.cursor = Some(cursor);
+                }
+                _ => return Ok(runs),
+            }
+        }
+    }
+
+    /// Attaches evaluation feedback to an existing run.
+    pub async fn create_feedback(&self, feedback: &Feedback) -> Result<()> {
+        let url = format!("{}/feedback", self.config.endpoint);
+        let response = self
+            .send_with_retry(
+                || self.request(Method::POST, &url).json(feedback),
+                self.request_deadline(),
+            )
+            .await?;
+        error_for_status(response).await?;
+        Ok(())
+    }
+
+    /// Creates a project (tracing session). A project that already exists
+    /// (409) counts as success.
+    pub async fn create_project(&self, name: &str, description: Option<&str>) -> Result<()> {
+        let url = format!("{}/sessions", self.config.endpoint);
+        let body = ProjectCreate { name, description };
+        let response = self
+            .send_json(Method::POST, &url, &body, None, self.request_deadline())
+            .await?;
+        if response.status() == StatusCode::CONFLICT {
+            return Ok(());
+        }
+        error_for_status(response).await?;
+        Ok(())
+    }
+
+    /// Creates a dataset to collect evaluation examples in.
+    pub async fn create_dataset(&self, name: &str, description: Option<&str>) -> Result<Dataset> {
+        let url = format!("{}/datasets", self.config.endpoint);
+        let body = DatasetCreate { name, description };
+        let response = self
+            .send_json(Method::POST, &url, &body, None, self.request_deadline())
+            .await?;
+        Ok(error_for_status(response).await?.json::<Dataset>().await?)
+    }
+
+    /// Adds an input/output example to a dataset.
+    pub async fn create_example(
+        &self,
+        dataset_id: Uuid,
+        inputs: &Value,
+        outputs: &Value,
+    ) -> Result<Example> {
+        let url = format!("{}/examples", self.config.endpoint);
+        let body = ExampleCreate {
+            dataset_id,
+            inputs,
+            outputs,
+        };
+        let response = self
+            .send_json(Method::POST, &url, &body, None, self.request_deadline())
+            .await?;
+        Ok(error_for_status(response).await?.json::<Example>().await?)
+    }
+
+    /// Creates a request with the authentication headers applied.
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
         let mut request = self
             .client
-            .patch(&url)
-            .header("x-api-key", &self.config.api_key)
-            .json(updates);
+            .request(method, url)
+            .header("x-api-key", &self.config.api_key);
 
         if let Some(tenant_id) = &self.config.tenant_id {
             request = request.header("x-tenant-id", tenant_id);
         }
 
-        let response = request.send().await?;
+        request
+    }
+
+    /// Encodes and sends a JSON body (with retries), mapping a 413 response
+    /// to `LangSmithError::PayloadTooLarge`.
+    async fn send_json<T: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        value: &T,
+        if_match: Option<&str>,
+        deadline: Option<Instant>,
+    ) -> Result<Response> {
+        let body = self.encode_body(value)?;
+        let response = self
+            .send_with_retry(
+                || {
+                    let request = self.json_request(method.clone(), url, &body);
+                    match if_match {
+                        Some(version) => request.header(IF_MATCH, version),
+                        None => request,
+                    }
+                },
+                deadline,
+            )
+            .await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(LangSmithError::Other(format!(
-                "HTTP {}: {}",
-                status.as_u16(),
-                text
-            )));
+        if response.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            return Err(LangSmithError::PayloadTooLarge { size: body.size });
         }
+        Ok(response)
+    }
 
-        Ok(())
+    /// Creates a request carrying a pre-encoded JSON body.
+    fn json_request(&self, method: Method, url: &str, body: &JsonBody) -> RequestBuilder {
+        let mut request = self
+            .request(method, url)
+            .header(CONTENT_TYPE, "application/json");
+        if body.gzipped {
+            request = request.header(CONTENT_ENCODING, "gzip");
+        }
+        request.body(body.bytes.clone())
+    }
+
+    fn encode_body<T: Serialize>(&self, value: &T) -> Result<JsonBody> {
+        encode_json_body(&self.config, value)
+    }
+
+    /// Deadline for a request that isn't part of a run's delivery
+    fn request_deadline(&self) -> Option<Instant> {
+        self.config.per_run_deadline.map(|budget| Instant::now() + budget)
+    }
+
+    /// Sends a request, retrying connection failures, 429 and 5xx responses
+    /// up to `max_retries` times with exponential backoff. When `deadline` is
+    /// set, attempts still running at the deadline are cancelled and no retry
+    /// starts after it.
+    ///
+    /// Returns the last response received, whatever its status.
+    async fn send_with_retry<F>(&self, build: F, deadline: Option<Instant>) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+
+        loop {
+            let result = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline
+                        .checked_duration_since(Instant::now())
+                        .filter(|remaining| !remaining.is_zero())
+                        .ok_or_else(deadline_exceeded)?;
+                    tokio::time::timeout(remaining, build().send())
+                        .await
+                        .map_err(|_| deadline_exceeded())?
+                }
+                None => build().send().await,
+            };
+            let retryable = match &result {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+
+            let backoff = retry_backoff(&self.config, attempt, deadline);
+            match backoff {
+                Some(backoff) if retryable => tokio::time::sleep(backoff).await,
+                _ => return result.map_err(request_error),
+            }
+            attempt += 1;
+        }
+    }
+}
+
+/// Error for a request cut short by `per_run_deadline`
+pub(super) fn deadline_exceeded() -> LangSmithError {
+    LangSmithError::Timeout("per_run_deadline exceeded".to_string())
+}
+
+/// Maps a transport error, surfacing timeouts as `LangSmithError::Timeout`.
+pub(super) fn request_error(error: reqwest::Error) -> LangSmithError {
+    if error.is_timeout() {
+        LangSmithError::Timeout(error.to_string())
+    } else {
+        LangSmithError::Http(error)
+    }
+}
+
+/// Body of `POST /runs/batch`
+#[derive(Serialize)]
+struct BatchRequest<'a> {
+    post: &'a [Run],
+    patch: &'a [Run],
+}
+
+/// Body of `POST /sessions`
+#[derive(Serialize)]
+pub(super) struct ProjectCreate<'a> {
+    pub(super) name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) description: Option<&'a str>,
+}
+
+/// Body of `POST /datasets`
+#[derive(Serialize)]
+struct DatasetCreate<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+}
+
+/// Body of `POST /examples`
+#[derive(Serialize)]
+struct ExampleCreate<'a> {
+    dataset_id: Uuid,
+    inputs: &'a Value,
+    outputs: &'a Value,
+}
+
+/// Request body serialized ahead of sending
+pub(super) struct JsonBody {
+    pub(super) bytes: Vec<u8>,
+    pub(super) gzipped: bool,
+    /// Length of the uncompressed JSON
+    pub(super) size: usize,
+}
+
+/// Serializes a request body once so retries can resend it, gzipping it
+/// when compression is enabled and the body is large.
+pub(super) fn encode_json_body<T: Serialize>(config: &Config, value: &T) -> Result<JsonBody> {
+    let bytes = serde_json::to_vec(value)?;
+    let size = bytes.len();
+    if !config.compression_enabled || size < COMPRESSION_THRESHOLD {
+        return Ok(JsonBody {
+            bytes,
+            gzipped: false,
+            size,
+        });
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&bytes)
+        .and_then(|_| encoder.finish())
+        .map(|bytes| JsonBody {
+            bytes,
+            gzipped: true,
+            size,
+        })
+        .map_err(|e| LangSmithError::Other(format!("Failed to compress request body: {}", e)))
+}
+
+/// The run as it should be posted: checked by `validate_run` and normalized
+/// by `normalize_run` when `validate_runs` is enabled, otherwise unchanged.
+/// The run is only copied when its inputs need wrapping.
+pub(super) fn validated_run<'a>(config: &Config, run: &'a Run) -> Result<Cow<'a, Run>> {
+    if !config.validate_runs {
+        return Ok(Cow::Borrowed(run));
+    }
+    validate_run(run)?;
+    if run.inputs.is_object() {
+        return Ok(Cow::Borrowed(run));
+    }
+    let mut run = run.clone();
+    normalize_run(&mut run);
+    Ok(Cow::Owned(run))
+}
+
+/// `validated_run` for each run of a batch, copying the batch only when one
+/// of its runs needs normalizing
+pub(super) fn validated_runs<'a>(config: &Config, runs: &'a [Run]) -> Result<Cow<'a, [Run]>> {
+    let mut normalized: Option<Vec<Run>> = None;
+    for (index, run) in runs.iter().enumerate() {
+        if let Cow::Owned(run) = validated_run(config, run)? {
+            normalized.get_or_insert_with(|| runs.to_vec())[index] = run;
+        }
     }
+    Ok(normalized.map_or(Cow::Borrowed(runs), Cow::Owned))
+}
+
+/// 429 and 5xx responses are worth retrying
+pub(super) fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
 }
 
+/// Delay before retry number `attempt + 1`, or `None` once `max_retries` is
+/// used up or waiting would overrun the `deadline`.
+pub(super) fn retry_backoff(
+    config: &Config,
+    attempt: u32,
+    deadline: Option<Instant>,
+) -> Option<Duration> {
+    if attempt >= config.max_retries {
+        return None;
+    }
+
+    let backoff = config.retry_backoff * 2u32.saturating_pow(attempt);
+    match deadline {
+        Some(deadline) if Instant::now() + backoff >= deadline => None,
+        _ => Some(backoff),
+    }
+}
+
+/// Runs that sent no request for this long are forgotten by `DeliveryBudgets`,
+/// so runs that are never ended don't accumulate
+const DELIVERY_IDLE_EXPIRY: Duration = Duration::from_secs(3600);
+
+/// Time spent delivering each run, so that a run's post and patches share one
+/// `per_run_deadline` budget instead of getting one each
+#[derive(Default)]
+pub(super) struct DeliveryBudgets {
+    spent: Mutex<HashMap<Uuid, SpentTime>>,
+}
+
+struct SpentTime {
+    spent: Duration,
+    last_used: Instant,
+}
+
+impl DeliveryBudgets {
+    /// Starts a request for `run_id`; its deadline is what remains of the run's
+    /// budget. `last` marks the run's final request, after which the run is
+    /// forgotten.
+    pub(super) fn start(&self, config: &Config, run_id: Uuid, last: bool) -> Delivery<'_> {
+        let started = Instant::now();
+        let deadline = config.per_run_deadline.map(|budget| {
+            let mut spent = self.spent.lock().unwrap();
+            spent.retain(|_, s| started.duration_since(s.last_used) < DELIVERY_IDLE_EXPIRY);
+            let used = spent.get(&run_id).map_or(Duration::ZERO, |s| s.spent);
+            started + budget.saturating_sub(used)
+        });
+        Delivery {
+            budgets: self,
+            run_id,
+            last,
+            started,
+            deadline,
+        }
+    }
+}
+
+/// A request being delivered for a run; its time is charged to the run's
+/// budget when dropped
+pub(super) struct Delivery<'a> {
+    budgets: &'a DeliveryBudgets,
+    run_id: Uuid,
+    last: bool,
+    started: Instant,
+    pub(super) deadline: Option<Instant>,
+}
+
+impl Drop for Delivery<'_> {
+    fn drop(&mut self) {
+        if self.deadline.is_none() {
+            return;
+        }
+        let mut spent = self.budgets.spent.lock().unwrap();
+        if self.last {
+            spent.remove(&self.run_id);
+            return;
+        }
+        let now = Instant::now();
+        let entry = spent.entry(self.run_id).or_insert(SpentTime {
+            spent: Duration::ZERO,
+            last_used: now,
+        });
+        entry.spent += now - self.started;
+        entry.last_used = now;
+    }
+}
+
+/// Converts a non-success response into an error carrying the status and body.
+async fn error_for_status(response: Response) -> Result<Response> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    Err(LangSmithError::Api { status, body })
+}
+
+/// Builds the underlying HTTP client, applying custom root certificates and
+/// client identity (mTLS) from the config.
+fn build_http_client(config: &Config) -> Result<Client> {
+    let mut builder = Client::builder().timeout(config.timeout);
+
+    let tls = TlsSettings::from_config(config)?;
+    if let Some(cert) = tls.root_certificate {
+        builder = builder.add_root_certificate(cert);
+    }
+    if let Some(identity) = tls.identity {
+        builder = builder.identity(identity);
+    }
+
+    Ok(builder.build()?)
+}
+
+/// Root certificate and client identity (mTLS) read from the config
+pub(super) struct TlsSettings {
+    pub(super) root_certificate: Option<Certificate>,
+    pub(super) identity: Option<Identity>,
+}
+
+impl TlsSettings {
+    pub(super) fn from_config(config: &Config) -> Result<Self> {
+        let root_certificate = match &config.ca_cert_path {
+            Some(path) => {
+                let pem = read_pem(path, "CA certificate")?;
+                let cert = Certificate::from_pem(&pem).map_err(|e| {
+                    LangSmithError::Config(format!(
+                        "Invalid CA certificate {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                Some(cert)
+            }
+            None => None,
+        };
+
+        let identity = match (&config.client_cert_path, &config.client_key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert = read_pem(cert_path, "client certificate")?;
+                let key = read_pem(key_path, "client key")?;
+                let identity = Identity::from_pkcs8_pem(&cert, &key).map_err(|e| {
+                    LangSmithError::Config(format!("Invalid client identity: {}", e))
+                })?;
+                Some(identity)
+            }
+            (None, None) => None,
+            _ => {
+                return Err(LangSmithError::Config(
+                    "LANGSMITH_CLIENT_CERT and LANGSMITH_CLIENT_KEY must be set together"
+                        .to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            root_certificate,
+            identity,
+        })
+    }
+}
+
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| {
+        LangSmithError::Config(format!("Failed to read {} {}: {}", what, path.display(), e))
+    })
+}
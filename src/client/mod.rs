@@ -1,4 +1,9 @@
 pub mod http;
+pub mod sink;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 
-pub use http::LangSmithClient;
-
+pub use http::{LangSmithClient, VersionedRun};
+pub use sink::{InMemorySink, RunSink};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingLangSmithClient;
@@ -0,0 +1,53 @@
+use crate::models::run::{Run, RunUpdate};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Destination that receives runs in place of the LangSmith API.
+///
+/// When `Config::sink` is set, `LangSmithClient` hands every created and
+/// updated run to the sink and sends nothing over HTTP.
+pub trait RunSink: fmt::Debug + Send + Sync {
+    fn post_run(&self, run: &Run);
+
+    fn patch_run(&self, run_id: Uuid, update: &RunUpdate);
+}
+
+/// Sink that keeps runs in memory, so tests can assert on exactly what an
+/// application traced. Clones share the same recordings.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySink {
+    runs: Arc<Mutex<Vec<Run>>>,
+    patches: Arc<Mutex<Vec<(Uuid, RunUpdate)>>>,
+}
+
+impl InMemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs created so far, in the order they were posted
+    pub fn recorded_runs(&self) -> Vec<Run> {
+        self.runs.lock().unwrap().clone()
+    }
+
+    /// Updates sent so far with the id of the run each one applies to
+    pub fn recorded_patches(&self) -> Vec<(Uuid, RunUpdate)> {
+        self.patches.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.runs.lock().unwrap().clear();
+        self.patches.lock().unwrap().clear();
+    }
+}
+
+impl RunSink for InMemorySink {
+    fn post_run(&self, run: &Run) {
+        self.runs.lock().unwrap().push(run.clone());
+    }
+
+    fn patch_run(&self, run_id: Uuid, update: &RunUpdate) {
+        self.patches.lock().unwrap().push((run_id, update.clone()));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::client::sink::RunSink;
+use crate::config::env::validate_endpoint;
+use crate::config::Config;
+use crate::error::{LangSmithError, Result};
+use crate::models::run::DottedOrderPrecision;
+use crate::utils::truncation::TruncationStrategy;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Builds a `Config` in code instead of from environment variables.
+/// Unset fields get the same defaults as `Config::from_env`.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl Config {
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: Config {
+                tracing_enabled: false,
+                endpoint: "https://api.smith.langchain.com".to_string(),
+                api_key: String::new(),
+                project: None,
+                project_id: None,
+                environment: None,
+                env_projects: HashMap::new(),
+                tenant_id: None,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+                max_retries: 0,
+                retry_backoff: Duration::from_millis(100),
+                per_run_deadline: None,
+                timeout: Duration::from_millis(10_000),
+                sample_rate: 1.0,
+                compression_enabled: true,
+                truncation: None,
+                max_string_bytes: None,
+                ensure_project: false,
+                upsert_posts: false,
+                dotted_order_precision: DottedOrderPrecision::Micros,
+                validate_runs: true,
+                sink: None,
+            },
+        }
+    }
+
+    pub fn tracing_enabled(mut self, enabled: bool) -> Self {
+        self.config.tracing_enabled = enabled;
+        self
+    }
+
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.config.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.config.api_key = api_key.into();
+        self
+    }
+
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.config.project = Some(project.into());
+        self
+    }
+
+    /// Project UUID sent as each run's `session_id`
+    pub fn project_id(mut self, project_id: Uuid) -> Self {
+        self.config.project_id = Some(project_id);
+        self
+    }
+
+    pub fn tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.config.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    pub fn ca_cert_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.ca_cert_path = Some(path.into());
+        self
+    }
+
+    /// Client certificate and PKCS#8 key used for mutual TLS
+    pub fn client_identity(
+        mut self,
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> Self {
+        self.config.client_cert_path = Some(cert_path.into());
+        self.config.client_key_path = Some(key_path.into());
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.config.max_retries = max_retries;
+        self
+    }
+
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.config.retry_backoff = backoff;
+        self
+    }
+
+    pub fn per_run_deadline(mut self, deadline: Duration) -> Self {
+        self.config.per_run_deadline = Some(deadline);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Fraction of traces recorded, clamped to 0.0..=1.0. NaN is rejected by
+    /// `build`.
+    pub fn sample_rate(mut self, sample_rate: f64) -> Self {
+        self.config.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn compression_enabled(mut self, enabled: bool) -> Self {
+        self.config.compression_enabled = enabled;
+        self
+    }
+
+    /// Truncates and retries runs rejected as too large
+    pub fn truncation(mut self, strategy: TruncationStrategy) -> Self {
+        self.config.truncation = Some(strategy);
+        self
+    }
+
+    /// Truncates long strings in inputs/outputs before runs are sent
+    pub fn max_string_bytes(mut self, max_bytes: usize) -> Self {
+        self.config.max_string_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Creates the run's project on demand when a post fails with 404
+    pub fn ensure_project(mut self, enabled: bool) -> Self {
+        self.config.ensure_project = enabled;
+        self
+    }
+
+    /// Treats a post rejected with 409 as success, since a run with that id
+    /// already exists
+    pub fn upsert_posts(mut self, enabled: bool) -> Self {
+        self.config.upsert_posts = enabled;
+        self
+    }
+
+    /// Fractional-second digits in `dotted_order` time stamps
+    pub fn dotted_order_precision(mut self, precision: DottedOrderPrecision) -> Self {
+        self.config.dotted_order_precision = precision;
+        self
+    }
+
+    /// Validates runs before posting them (on by default); turn it off to send
+    /// runs exactly as given
+    pub fn validate_runs(mut self, enabled: bool) -> Self {
+        self.config.validate_runs = enabled;
+        self
+    }
+
+    /// Hands runs to `sink` instead of sending them to LangSmith
+    pub fn sink(mut self, sink: Arc<dyn RunSink>) -> Self {
+        self.config.sink = Some(sink);
+        self
+    }
+
+    /// Builds the config. Fails if the endpoint isn't an http(s) URL, or if no
+    /// API key was set and runs would be sent to LangSmith (i.e. without a sink).
+    pub fn build(mut self) -> Result<Config> {
+        if self.config.api_key.is_empty() && self.config.sink.is_none() {
+            return Err(LangSmithError::Config("API key not set".to_string()));
+        }
+        if self.config.sample_rate.is_nan() {
+            return Err(LangSmithError::Config("sample_rate must be a number".to_string()));
+        }
+        self.config.endpoint = validate_endpoint(&self.config.endpoint)?;
+        Ok(self.config)
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
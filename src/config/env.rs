@@ -1,43 +1,207 @@
+use crate::client::sink::RunSink;
+use crate::config::file::{ConfigFile, FileValues};
 use crate::error::{LangSmithError, Result};
+use crate::models::run::DottedOrderPrecision;
+use crate::utils::truncation::TruncationStrategy;
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub tracing_enabled: bool,
     pub endpoint: String,
     pub api_key: String,
+    /// Project runs are sent to: the `env_projects` entry for `environment`
+    /// when there is one, otherwise `LANGSMITH_PROJECT`
     pub project: Option<String>,
+    /// UUID of the project (`LANGSMITH_PROJECT_ID`), sent as the runs'
+    /// `session_id` for API paths that expect the id rather than the name
+    pub project_id: Option<Uuid>,
+    /// Deployment environment name (`LANGSMITH_ENV`), e.g. "staging"
+    pub environment: Option<String>,
+    /// Project per environment, from `LANGSMITH_ENV_PROJECTS`
+    /// ("dev=app-dev,staging=app-staging")
+    pub env_projects: HashMap<String, String>,
     pub tenant_id: Option<String>,
+    /// PEM-encoded CA certificate added to the HTTP client's root store
+    pub ca_cert_path: Option<PathBuf>,
+    /// PEM-encoded client certificate used for mutual TLS
+    pub client_cert_path: Option<PathBuf>,
+    /// PEM-encoded (PKCS#8) private key matching `client_cert_path`
+    pub client_key_path: Option<PathBuf>,
+    /// Number of times a failed request is retried (0 disables retries)
+    pub max_retries: u32,
+    /// Initial delay between retries, doubled after each attempt
+    pub retry_backoff: Duration,
+    /// Upper bound on the total time spent delivering a run, shared by its
+    /// post and patches and including all retries; other requests get a
+    /// budget of their own
+    pub per_run_deadline: Option<Duration>,
+    /// Timeout applied to each HTTP request
+    pub timeout: Duration,
+    /// Fraction of traces that are recorded, between 0.0 and 1.0
+    pub sample_rate: f64,
+    /// Gzip large request bodies
+    pub compression_enabled: bool,
+    /// When set, runs rejected as too large are truncated and retried once
+    pub truncation: Option<TruncationStrategy>,
+    /// When set, string values in inputs/outputs longer than this many bytes
+    /// are truncated before runs are sent (see `TruncationStrategy::bytes`)
+    pub max_string_bytes: Option<usize>,
+    /// Create the run's project when posting a run fails with 404
+    pub ensure_project: bool,
+    /// Treat a 409 from posting a run as success: the run's client-chosen id
+    /// already exists, e.g. because a retried post had reached the server
+    pub upsert_posts: bool,
+    /// Fractional-second digits in `dotted_order` time stamps
+    pub dotted_order_precision: DottedOrderPrecision,
+    /// Check runs with `validate_run` before posting them, so invalid runs
+    /// fail locally instead of with an opaque 422 from the server
+    pub validate_runs: bool,
+    /// When set, runs are handed to this sink instead of sent to LangSmith
+    /// (see `InMemorySink` for tests)
+    pub sink: Option<Arc<dyn RunSink>>,
 }
 
 static CONFIG: Lazy<Mutex<Option<Config>>> = Lazy::new(|| Mutex::new(None));
 
 impl Config {
+    /// Loads the config from environment variables, first loading a `.env` file
+    /// unless `LANGSMITH_DISABLE_DOTENV` is set.
     pub fn from_env() -> Result<Self> {
-        // Try to load .env file (ignore errors if it doesn't exist)
-        let _ = dotenvy::dotenv();
+        Self::from_env_internal(dotenv_enabled(), &FileValues::default())
+    }
+
+    /// Loads the config from environment variables only, never reading `.env`.
+    /// Use this in production where the environment is injected.
+    pub fn from_env_strict() -> Result<Self> {
+        Self::from_env_internal(false, &FileValues::default())
+    }
+
+    /// Loads the config from a `langsmith.toml` (or `.json`, by extension) file
+    /// whose keys mirror the `LANGSMITH_*` variables, e.g. `api_key`, `timeout_ms`
+    /// or an `[env_projects]` table. Environment variables (and `.env`, unless
+    /// disabled) override values from the file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let file = ConfigFile::load(path.as_ref())?.into_values();
+        Self::from_env_internal(dotenv_enabled(), &file)
+    }
+
+    fn from_env_internal(load_dotenv: bool, file: &FileValues) -> Result<Self> {
+        if load_dotenv {
+            // Try to load .env file (ignore errors if it doesn't exist)
+            let _ = dotenvy::dotenv();
+        }
+
+        // Environment variables take precedence over values from a config file
+        let var = |name: &str| std::env::var(name).ok().or_else(|| file.vars.get(name).cloned());
+
+        let tracing_enabled = var("LANGSMITH_TRACING")
+            .and_then(|v| parse_flag("LANGSMITH_TRACING", &v))
+            .unwrap_or(false);
+
+        let endpoint = var("LANGSMITH_ENDPOINT")
+            .unwrap_or_else(|| "https://api.smith.langchain.com".to_string());
+        let endpoint = validate_endpoint(&endpoint)?;
 
-        let tracing_enabled = std::env::var("LANGSMITH_TRACING")
-            .unwrap_or_else(|_| "false".to_string())
-            .parse::<bool>()
+        let api_key = var("LANGSMITH_API_KEY")
+            .ok_or_else(|| LangSmithError::Config("LANGSMITH_API_KEY not set".to_string()))?;
+
+        let environment = var("LANGSMITH_ENV");
+        let env_projects = match std::env::var("LANGSMITH_ENV_PROJECTS") {
+            Ok(v) => parse_env_projects(&v),
+            Err(_) => file.env_projects.clone(),
+        };
+        let project = environment
+            .as_ref()
+            .and_then(|env| env_projects.get(env).cloned())
+            .or_else(|| var("LANGSMITH_PROJECT"));
+        let project_id = var("LANGSMITH_PROJECT_ID").and_then(|v| parse_project_id(&v));
+        let tenant_id = var("LANGSMITH_TENANT_ID");
+        let ca_cert_path = var("LANGSMITH_CA_CERT").map(PathBuf::from);
+        let client_cert_path = var("LANGSMITH_CLIENT_CERT").map(PathBuf::from);
+        let client_key_path = var("LANGSMITH_CLIENT_KEY").map(PathBuf::from);
+
+        let max_retries = var("LANGSMITH_MAX_RETRIES")
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        let retry_backoff = var("LANGSMITH_RETRY_BACKOFF_MS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_millis(100));
+
+        let per_run_deadline = var("LANGSMITH_PER_RUN_DEADLINE_MS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_millis);
+
+        let timeout = var("LANGSMITH_TIMEOUT_MS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(Duration::from_millis(10_000));
+
+        let sample_rate = var("LANGSMITH_SAMPLE_RATE")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|rate| rate.is_finite())
+            .map(|rate| rate.clamp(0.0, 1.0))
+            .unwrap_or(1.0);
+
+        let compression_enabled = var("LANGSMITH_COMPRESSION")
+            .and_then(|v| parse_flag("LANGSMITH_COMPRESSION", &v))
+            .unwrap_or(true);
+
+        let truncation = var("LANGSMITH_TRUNCATE_MAX_CHARS")
+            .and_then(|v| v.parse::<usize>().ok())
+            .map(TruncationStrategy::new);
+
+        let max_string_bytes = var("LANGSMITH_MAX_STRING_BYTES")
+            .and_then(|v| v.parse::<usize>().ok());
+
+        let ensure_project = var("LANGSMITH_ENSURE_PROJECT")
+            .and_then(|v| parse_flag("LANGSMITH_ENSURE_PROJECT", &v))
             .unwrap_or(false);
 
-        let endpoint = std::env::var("LANGSMITH_ENDPOINT")
-            .unwrap_or_else(|_| "https://api.smith.langchain.com".to_string());
+        let upsert_posts = var("LANGSMITH_UPSERT_POSTS")
+            .and_then(|v| parse_flag("LANGSMITH_UPSERT_POSTS", &v))
+            .unwrap_or(false);
 
-        let api_key = std::env::var("LANGSMITH_API_KEY")
-            .map_err(|_| LangSmithError::Config("LANGSMITH_API_KEY not set".to_string()))?;
+        let dotted_order_precision = var("LANGSMITH_DOTTED_ORDER_PRECISION")
+            .and_then(|v| parse_precision(&v))
+            .unwrap_or_default();
 
-        let project = std::env::var("LANGSMITH_PROJECT").ok();
-        let tenant_id = std::env::var("LANGSMITH_TENANT_ID").ok();
+        let validate_runs = var("LANGSMITH_VALIDATE_RUNS")
+            .and_then(|v| parse_flag("LANGSMITH_VALIDATE_RUNS", &v))
+            .unwrap_or(true);
 
         Ok(Config {
             tracing_enabled,
             endpoint,
             api_key,
             project,
+            project_id,
+            environment,
+            env_projects,
             tenant_id,
+            ca_cert_path,
+            client_cert_path,
+            client_key_path,
+            max_retries,
+            retry_backoff,
+            per_run_deadline,
+            timeout,
+            sample_rate,
+            compression_enabled,
+            truncation,
+            max_string_bytes,
+            ensure_project,
+            upsert_posts,
+            dotted_order_precision,
+            validate_runs,
+            sink: None,
         })
     }
 
@@ -49,6 +213,12 @@ impl Config {
         Ok(config.as_ref().unwrap().clone())
     }
 
+    /// Installs `config` as the process-wide config returned by `get`,
+    /// replacing any config loaded from the environment.
+    pub fn set(config: Config) {
+        *CONFIG.lock().unwrap() = Some(config);
+    }
+
     pub fn is_tracing_enabled() -> bool {
         Self::get()
             .map(|c| c.tracing_enabled)
@@ -56,3 +226,88 @@ impl Config {
     }
 }
 
+/// Whether `.env` files may be loaded, i.e. `LANGSMITH_DISABLE_DOTENV` isn't set to a
+/// true value (see `parse_flag`)
+pub(crate) fn dotenv_enabled() -> bool {
+    !std::env::var("LANGSMITH_DISABLE_DOTENV")
+        .ok()
+        .and_then(|v| parse_flag("LANGSMITH_DISABLE_DOTENV", &v))
+        .unwrap_or(false)
+}
+
+/// Parses a boolean variable leniently: `true/false`, `1/0`, `yes/no` and
+/// `on/off`, in any case. Anything else is logged and treated as unset.
+fn parse_flag(name: &str, value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => {
+            log::warn!(
+                target: "langsmith",
+                "Ignoring unrecognized value '{}' for {} \
+                 (expected true/false, 1/0, yes/no or on/off)",
+                value,
+                name
+            );
+            None
+        }
+    }
+}
+
+/// Parses `LANGSMITH_PROJECT_ID`, logging values that aren't UUIDs
+fn parse_project_id(value: &str) -> Option<Uuid> {
+    let project_id = Uuid::parse_str(value.trim()).ok();
+    if project_id.is_none() {
+        log::warn!(
+            target: "langsmith",
+            "Ignoring invalid value '{}' for LANGSMITH_PROJECT_ID (expected a UUID)",
+            value
+        );
+    }
+    project_id
+}
+
+/// Parses `LANGSMITH_DOTTED_ORDER_PRECISION`, logging unrecognized values
+fn parse_precision(value: &str) -> Option<DottedOrderPrecision> {
+    let precision = DottedOrderPrecision::parse(value);
+    if precision.is_none() {
+        log::warn!(
+            target: "langsmith",
+            "Ignoring unrecognized value '{}' for LANGSMITH_DOTTED_ORDER_PRECISION \
+             (expected micros or nanos)",
+            value
+        );
+    }
+    precision
+}
+
+/// Parses `env=project` pairs separated by commas, skipping malformed entries
+fn parse_env_projects(value: &str) -> HashMap<String, String> {
+    value
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(env, project)| (env.trim().to_string(), project.trim().to_string()))
+        .filter(|(env, project)| !env.is_empty() && !project.is_empty())
+        .collect()
+}
+
+/// Checks that `endpoint` is an absolute http(s) URL and strips any trailing
+/// slash, since request paths are appended to it.
+pub(crate) fn validate_endpoint(endpoint: &str) -> Result<String> {
+    let invalid = |reason: &str| {
+        LangSmithError::Config(format!(
+            "Invalid LangSmith endpoint '{}': {} (expected e.g. https://api.smith.langchain.com)",
+            endpoint, reason
+        ))
+    };
+
+    let url = url::Url::parse(endpoint).map_err(|e| invalid(&e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("scheme must be http or https"));
+    }
+    if url.host().is_none() {
+        return Err(invalid("missing host"));
+    }
+
+    Ok(endpoint.trim_end_matches('/').to_string())
+}
@@ -0,0 +1,106 @@
+use crate::error::{LangSmithError, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Settings read from a `langsmith.toml` or `langsmith.json` file.
+///
+/// Every field mirrors one `LANGSMITH_*` environment variable, so a file value
+/// is used exactly as if that variable had been set.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConfigFile {
+    tracing_enabled: Option<bool>,
+    endpoint: Option<String>,
+    api_key: Option<String>,
+    project: Option<String>,
+    project_id: Option<String>,
+    environment: Option<String>,
+    env_projects: Option<HashMap<String, String>>,
+    tenant_id: Option<String>,
+    ca_cert: Option<PathBuf>,
+    client_cert: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+    max_retries: Option<u32>,
+    retry_backoff_ms: Option<u64>,
+    per_run_deadline_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+    sample_rate: Option<f64>,
+    compression: Option<bool>,
+    truncate_max_chars: Option<usize>,
+    max_string_bytes: Option<usize>,
+    ensure_project: Option<bool>,
+    upsert_posts: Option<bool>,
+    dotted_order_precision: Option<String>,
+    validate_runs: Option<bool>,
+}
+
+impl ConfigFile {
+    /// Reads `path` as JSON when it has a `.json` extension, otherwise as TOML
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let invalid = |reason: String| {
+            LangSmithError::Config(format!(
+                "Invalid config file '{}': {}",
+                path.display(),
+                reason
+            ))
+        };
+
+        let contents = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()))
+        } else {
+            toml::from_str(&contents).map_err(|e| invalid(e.to_string()))
+        }
+    }
+
+    /// The file's values, ready to be used beneath the environment
+    pub(crate) fn into_values(self) -> FileValues {
+        let path = |p: PathBuf| p.to_string_lossy().into_owned();
+
+        let vars = [
+            ("LANGSMITH_TRACING", self.tracing_enabled.map(|v| v.to_string())),
+            ("LANGSMITH_ENDPOINT", self.endpoint),
+            ("LANGSMITH_API_KEY", self.api_key),
+            ("LANGSMITH_PROJECT", self.project),
+            ("LANGSMITH_PROJECT_ID", self.project_id),
+            ("LANGSMITH_ENV", self.environment),
+            ("LANGSMITH_TENANT_ID", self.tenant_id),
+            ("LANGSMITH_CA_CERT", self.ca_cert.map(path)),
+            ("LANGSMITH_CLIENT_CERT", self.client_cert.map(path)),
+            ("LANGSMITH_CLIENT_KEY", self.client_key.map(path)),
+            ("LANGSMITH_MAX_RETRIES", self.max_retries.map(|v| v.to_string())),
+            ("LANGSMITH_RETRY_BACKOFF_MS", self.retry_backoff_ms.map(|v| v.to_string())),
+            ("LANGSMITH_PER_RUN_DEADLINE_MS", self.per_run_deadline_ms.map(|v| v.to_string())),
+            ("LANGSMITH_TIMEOUT_MS", self.timeout_ms.map(|v| v.to_string())),
+            ("LANGSMITH_SAMPLE_RATE", self.sample_rate.map(|v| v.to_string())),
+            ("LANGSMITH_COMPRESSION", self.compression.map(|v| v.to_string())),
+            ("LANGSMITH_TRUNCATE_MAX_CHARS", self.truncate_max_chars.map(|v| v.to_string())),
+            ("LANGSMITH_MAX_STRING_BYTES", self.max_string_bytes.map(|v| v.to_string())),
+            ("LANGSMITH_ENSURE_PROJECT", self.ensure_project.map(|v| v.to_string())),
+            ("LANGSMITH_UPSERT_POSTS", self.upsert_posts.map(|v| v.to_string())),
+            ("LANGSMITH_DOTTED_ORDER_PRECISION", self.dotted_order_precision),
+            ("LANGSMITH_VALIDATE_RUNS", self.validate_runs.map(|v| v.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .collect();
+
+        FileValues {
+            vars,
+            env_projects: self.env_projects.unwrap_or_default(),
+        }
+    }
+}
+
+/// Values read from a config file
+#[derive(Debug, Default)]
+pub(crate) struct FileValues {
+    /// Values keyed by the environment variable each one stands in for
+    pub(crate) vars: HashMap<&'static str, String>,
+    /// The `[env_projects]` table, used unless `LANGSMITH_ENV_PROJECTS` is set
+    pub(crate) env_projects: HashMap<String, String>,
+}
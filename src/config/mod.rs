@@ -1,4 +1,7 @@
+pub mod builder;
 pub mod env;
+mod file;
 
+pub use builder::ConfigBuilder;
 pub use env::Config;
 
@@ -1,3 +1,5 @@
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,15 +13,92 @@ pub enum LangSmithError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// Non-success response from the LangSmith API
+    #[error("HTTP {status}: {body}")]
+    Api { status: u16, body: String },
+
+    #[error("Conflict: {0} was modified concurrently")]
+    Conflict(String),
+
+    #[error("Payload too large: {size} bytes")]
+    PayloadTooLarge { size: usize },
+
     #[error("Tracing is disabled")]
     TracingDisabled,
 
     #[error("Invalid UUID: {0}")]
     InvalidUuid(#[from] uuid::Error),
 
+    #[error("Invalid traceparent header: {0}")]
+    InvalidTraceparent(String),
+
     #[error("Other error: {0}")]
     Other(String),
 }
 
+impl LangSmithError {
+    /// Status and response body of an `Api` error, e.g. to read which field
+    /// a 422 rejected; `None` for any other error
+    pub fn as_api(&self) -> Option<(u16, &str)> {
+        match self {
+            LangSmithError::Api { status, body } => Some((*status, body.as_str())),
+            _ => None,
+        }
+    }
+
+    /// Name of the variant, e.g. `"Timeout"` or `"Api"`
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            LangSmithError::Config(_) => "Config",
+            LangSmithError::Http(_) => "Http",
+            LangSmithError::Serialization(_) => "Serialization",
+            LangSmithError::Timeout(_) => "Timeout",
+            LangSmithError::NotFound(_) => "NotFound",
+            LangSmithError::Api { .. } => "Api",
+            LangSmithError::Conflict(_) => "Conflict",
+            LangSmithError::PayloadTooLarge { .. } => "PayloadTooLarge",
+            LangSmithError::TracingDisabled => "TracingDisabled",
+            LangSmithError::InvalidUuid(_) => "InvalidUuid",
+            LangSmithError::InvalidTraceparent(_) => "InvalidTraceparent",
+            LangSmithError::Other(_) => "Other",
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, LangSmithError>;
 
+type TraceErrorCallback = Arc<dyn Fn(&LangSmithError) + Send + Sync>;
+
+static ON_TRACE_ERROR: Lazy<RwLock<Option<TraceErrorCallback>>> = Lazy::new(|| RwLock::new(None));
+
+/// Registers a callback invoked whenever a best-effort tracing call (post/patch)
+/// fails. Errors are still logged; the callback is meant for metrics or alerting.
+pub fn set_on_trace_error(callback: Box<dyn Fn(&LangSmithError) + Send + Sync>) {
+    *ON_TRACE_ERROR.write().unwrap() = Some(Arc::from(callback));
+}
+
+/// Removes the callback registered with `set_on_trace_error`.
+pub fn clear_on_trace_error() {
+    *ON_TRACE_ERROR.write().unwrap() = None;
+}
+
+/// Logs a swallowed tracing error and notifies the registered callback.
+pub(crate) fn report_trace_error(stage: Option<&str>, error: &LangSmithError) {
+    match stage {
+        Some(stage) => {
+            log::warn!(target: "langsmith", "LangSmith tracing error ({}): {}", stage, error)
+        }
+        None => log::warn!(target: "langsmith", "LangSmith tracing error: {}", error),
+    }
+
+    let callback = ON_TRACE_ERROR.read().unwrap().clone();
+    if let Some(callback) = callback {
+        callback(error);
+    }
+}
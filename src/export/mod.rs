@@ -0,0 +1,92 @@
+//! Offline export of runs (JSONL files, nested trees).
+//!
+//! These helpers are for dumping runs to other systems; the live API always
+//! uses RFC3339 timestamps regardless of the options here.
+
+use crate::error::{LangSmithError, Result};
+use crate::models::run::Run;
+use crate::utils::timestamp::{unix_micros, Timestamp};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use uuid::Uuid;
+
+/// How `start_time`/`end_time` are written in exported runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeFormat {
+    /// RFC3339 strings, the same as the LangSmith API
+    #[default]
+    Rfc3339,
+    /// Integer milliseconds since the Unix epoch
+    EpochMillis,
+}
+
+/// Serializes a single run for export
+pub fn run_to_json(run: &Run, format: TimeFormat) -> Result<Value> {
+    let mut value = serde_json::to_value(run)?;
+    if format == TimeFormat::EpochMillis {
+        value["start_time"] = json!(unix_millis(&run.start_time));
+        if let Some(end_time) = run.end_time {
+            value["end_time"] = json!(unix_millis(&end_time));
+        }
+    }
+    Ok(value)
+}
+
+fn unix_millis(timestamp: &Timestamp) -> i64 {
+    unix_micros(timestamp).div_euclid(1_000)
+}
+
+/// Writes one JSON object per line for each run
+pub fn write_jsonl<W: Write>(mut writer: W, runs: &[Run], format: TimeFormat) -> Result<()> {
+    for run in runs {
+        let line = serde_json::to_string(&run_to_json(run, format)?)?;
+        writeln!(writer, "{}", line)
+            .map_err(|e| LangSmithError::Other(format!("Failed to write export: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Nests runs under their parents via a `child_runs` array, returning the
+/// root runs (those whose parent isn't among `runs`) in their original order.
+pub fn to_tree_json(runs: &[Run], format: TimeFormat) -> Result<Value> {
+    let ids: Vec<Uuid> = runs.iter().map(|run| run.id).collect();
+    let mut children: HashMap<Uuid, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for (index, run) in runs.iter().enumerate() {
+        match run.parent_run_id.filter(|parent| ids.contains(parent)) {
+            Some(parent) => children.entry(parent).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+
+    let trees = roots
+        .into_iter()
+        .map(|index| build_tree(runs, index, &children, format))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Value::Array(trees))
+}
+
+fn build_tree(
+    runs: &[Run],
+    index: usize,
+    children: &HashMap<Uuid, Vec<usize>>,
+    format: TimeFormat,
+) -> Result<Value> {
+    let run = &runs[index];
+    let child_runs = children
+        .get(&run.id)
+        .map(|indices| {
+            indices
+                .iter()
+                .map(|&child| build_tree(runs, child, children, format))
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let mut value = run_to_json(run, format)?;
+    value["child_runs"] = Value::Array(child_runs);
+    Ok(value)
+}
@@ -1,4 +1,5 @@
 use crate::client::LangSmithClient;
+use crate::config::Config;
 use crate::models::run::RunType;
 use crate::tracing::tracer::Tracer;
 use crate::tracing::context::TraceContext;
@@ -25,6 +26,18 @@ impl TracerFactory {
         Tracer::new(name, run_type, inputs).with_client(client)
     }
 
+    /// Create a tracer that reports through a pre-built HTTP client
+    pub fn create_with_http_client(
+        name: impl Into<String>,
+        run_type: RunType,
+        inputs: Value,
+        config: Config,
+        http_client: reqwest::Client,
+    ) -> Tracer {
+        let client = LangSmithClient::with_http_client(config, http_client);
+        Tracer::new(name, run_type, inputs).with_client(Arc::new(client))
+    }
+
     /// Create a tracer with thread context
     pub fn create_with_thread(
         name: impl Into<String>,
@@ -57,7 +70,7 @@ impl TracerFactory {
         let trace_id = Uuid::new_v4();
         tracer.run.trace_id = Some(trace_id);
         // Generate dotted_order for root
-        tracer.run.dotted_order = Some(tracer.run.generate_dotted_order(None));
+        tracer.run.dotted_order = Some(tracer.generate_dotted_order(None));
         tracer
     }
 
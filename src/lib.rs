@@ -3,9 +3,11 @@
 //! This crate provides a Rust implementation for manual tracing to LangSmith,
 //! similar to the Python and TypeScript SDKs.
 
-// Load .env file on module initialization
+// Load .env file on module initialization, unless LANGSMITH_DISABLE_DOTENV is set
 fn init_dotenv() {
-    let _ = dotenvy::dotenv();
+    if config::env::dotenv_enabled() {
+        let _ = dotenvy::dotenv();
+    }
 }
 
 // Initialize dotenv when the module is loaded
@@ -15,26 +17,64 @@ static INIT: std::sync::Once = std::sync::Once::new();
 pub mod client;
 pub mod config;
 pub mod error;
+pub mod export;
 pub mod factories;
+#[cfg(feature = "axum")]
+pub mod middleware;
 pub mod models;
 pub mod observability;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod pricing;
+pub mod prelude;
 pub mod strategies;
+#[cfg(feature = "subscriber")]
+pub mod subscriber;
+pub mod testing;
 pub mod tracing;
 pub mod utils;
 
+#[cfg(not(any(feature = "chrono", feature = "time")))]
+compile_error!("enable either the `chrono` (default) or the `time` feature for timestamps");
+
 // Re-export main types
-pub use client::LangSmithClient;
-pub use config::Config;
-pub use error::{LangSmithError, Result};
+pub use client::{InMemorySink, LangSmithClient, RunSink};
+#[cfg(feature = "blocking")]
+pub use client::BlockingLangSmithClient;
+pub use config::{Config, ConfigBuilder};
+pub use error::{clear_on_trace_error, set_on_trace_error, LangSmithError, Result};
 pub use factories::TracerFactory;
 pub use models::{
     metrics::Metrics,
-    AIMessage, HumanMessage, Message, Run, RunType, RunUpdate, SystemMessage, ToolCall,
-    ToolMessage,
+    AIMessage, Attachment, ChatMessage, ContentPart, Dataset, DottedOrderPrecision, Example,
+    Feedback, FunctionMessage, HumanMessage, Message, MessageContent, Run, RunErrorKind, RunStatus,
+    RunType, RunUpdate, SystemMessage, ToolCall, ToolMessage,
 };
 pub use observability::{LangSmithObserver, Observable, ObservableNodeWrapper, Observer};
-pub use strategies::{SerializationStrategy, TracingStrategy};
-pub use tracing::{trace_node, trace_node_sync, GraphTrace, RunScope, TraceContext, Tracer};
+pub use strategies::{NoOpTracingStrategy, SerializationStrategy, TracingStrategy};
+pub use tracing::{
+    current_trace, trace_future, trace_llm_node, trace_map, trace_map_concurrent, trace_node,
+    trace_node_batched, trace_node_sync, trace_node_sync_with, trace_node_with,
+    with_current_trace, BatchScope, GraphTrace, GraphTraceBuilder, RunScope, RunTree, ScopeGuard,
+    SharedTracer, StreamingTracer, TraceContext, TraceOptions, TraceRegistry, Tracer, TracerGuard,
+};
+
+pub use langsmith_macros::trace;
+
+// Used by the code `#[trace]` expands to
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+
+    /// Serializes a `#[trace]` argument. A failure is reported and recorded as
+    /// a placeholder, so the function still runs.
+    pub fn input_value<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Value {
+        serde_json::to_value(value).unwrap_or_else(|e| {
+            crate::error::report_trace_error(Some("inputs"), &e.into());
+            serde_json::Value::String("<unserializable>".to_string())
+        })
+    }
+}
 
 // Initialize dotenv on first use
 pub fn init() {
@@ -46,6 +86,7 @@ pub fn init() {
 #[cfg(test)]
 mod tests {
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_module_compiles() {
         // Verify the module compiles
         assert!(true);
@@ -0,0 +1,98 @@
+use bytes::Bytes;
+use http::{HeaderMap, Uri};
+use serde_json::{json, Map, Value};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Controls which parts of an HTTP request `LangSmithLayer` records as run inputs.
+///
+/// Method and path are always recorded. Headers listed in `redacted_headers`
+/// are recorded with a `"[REDACTED]"` value if they are also selected in `headers`.
+#[derive(Debug, Clone)]
+pub struct CaptureConfig {
+    /// Record the query string as a `query` object
+    pub query_params: bool,
+    /// Header names (case-insensitive) to record
+    pub headers: Vec<String>,
+    /// Header names whose values are never recorded
+    pub redacted_headers: Vec<String>,
+    /// Record request bodies of at most this many bytes; larger bodies and
+    /// streamed bodies of unknown length are not recorded. 0 disables body capture
+    pub max_body_bytes: usize,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            query_params: true,
+            headers: vec!["user-agent".to_string(), "content-type".to_string()],
+            redacted_headers: vec![
+                "authorization".to_string(),
+                "proxy-authorization".to_string(),
+                "cookie".to_string(),
+                "x-api-key".to_string(),
+            ],
+            max_body_bytes: 0,
+        }
+    }
+}
+
+impl CaptureConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_query_params(mut self, enabled: bool) -> Self {
+        self.query_params = enabled;
+        self
+    }
+
+    pub fn with_headers(mut self, headers: Vec<String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_redacted_headers(mut self, headers: Vec<String>) -> Self {
+        self.redacted_headers = headers;
+        self
+    }
+
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    pub(crate) fn capture_headers(&self, headers: &HeaderMap) -> Value {
+        let mut captured = Map::new();
+        for name in &self.headers {
+            let name = name.to_ascii_lowercase();
+            let Some(value) = headers.get(&name).and_then(|v| v.to_str().ok()) else {
+                continue;
+            };
+            let redacted = self
+                .redacted_headers
+                .iter()
+                .any(|r| r.eq_ignore_ascii_case(&name));
+            let value = if redacted { REDACTED } else { value };
+            captured.insert(name, json!(value));
+        }
+        Value::Object(captured)
+    }
+
+    pub(crate) fn capture_query(&self, uri: &Uri) -> Option<Value> {
+        if !self.query_params {
+            return None;
+        }
+        let query = uri.query()?;
+        let params: Map<String, Value> = form_urlencoded::parse(query.as_bytes())
+            .map(|(k, v)| (k.into_owned(), json!(v)))
+            .collect();
+        Some(Value::Object(params))
+    }
+
+    /// Records the body as JSON when it parses, otherwise as a string.
+    pub(crate) fn capture_body(&self, body: &Bytes) -> Value {
+        serde_json::from_slice::<Value>(body)
+            .unwrap_or_else(|_| json!(String::from_utf8_lossy(body)))
+    }
+}
@@ -0,0 +1,207 @@
+use crate::config::Config;
+use crate::error::report_trace_error;
+use crate::middleware::capture::CaptureConfig;
+use crate::models::run::RunType;
+use crate::tracing::context::{with_current_trace, TraceContext, TRACE_ID_HEADER};
+use crate::tracing::scope::RunScope;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderValue, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Limited};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::JoinHandle;
+use tower::{Layer, Service};
+
+/// `tower::Layer` that starts a root run per HTTP request.
+///
+/// The run's inputs hold the method, path and whatever `CaptureConfig` selects
+/// (by default the query string and a few non-sensitive headers), and its
+/// outputs the response status. The handler runs with the run as the current
+/// trace (see `with_current_trace`), so `trace_node` and `#[trace]` calls nest
+/// under it; its `TraceContext` is also inserted into the request extensions,
+/// e.g. for axum's `Extension<TraceContext>`. Incoming `langsmith-*` trace
+/// headers continue an existing trace, and the trace id is echoed back on the
+/// response. The run's POST and PATCH are sent on spawned tasks, so the
+/// response doesn't wait on LangSmith.
+///
+/// When the body is captured and reading it fails, the request is answered
+/// with `400 Bad Request` and the read error, since the handler could only be
+/// given a truncated body.
+#[derive(Debug, Clone, Default)]
+pub struct LangSmithLayer {
+    capture: Arc<CaptureConfig>,
+}
+
+impl LangSmithLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capture(mut self, capture: CaptureConfig) -> Self {
+        self.capture = Arc::new(capture);
+        self
+    }
+}
+
+impl<S> Layer<S> for LangSmithLayer {
+    type Service = LangSmithService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LangSmithService {
+            inner,
+            capture: Arc::clone(&self.capture),
+        }
+    }
+}
+
+/// Service produced by `LangSmithLayer`
+#[derive(Debug, Clone)]
+pub struct LangSmithService<S> {
+    inner: S,
+    capture: Arc<CaptureConfig>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for LangSmithService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: std::fmt::Display + Send,
+    ReqBody: http_body::Body + From<Bytes> + Send + 'static,
+    ReqBody::Data: Send,
+    ReqBody::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    ResBody: From<Bytes> + Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // Use the instance that was polled ready and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let capture = Arc::clone(&self.capture);
+
+        Box::pin(async move {
+            if !Config::is_tracing_enabled() {
+                return inner.call(request).await;
+            }
+
+            let name = format!("{} {}", request.method(), request.uri().path());
+            let mut inputs = json!({
+                "method": request.method().as_str(),
+                "path": request.uri().path(),
+                "headers": capture.capture_headers(request.headers()),
+            });
+            if let Some(query) = capture.capture_query(request.uri()) {
+                inputs["query"] = query;
+            }
+            let parent = parent_context(request.headers());
+            let request = if capture.max_body_bytes > 0 {
+                buffer_body(request, capture.max_body_bytes)
+                    .await
+                    .map(|(body, restored)| {
+                        if let Some(body) = body {
+                            inputs["body"] = capture.capture_body(&body);
+                        }
+                        restored
+                    })
+            } else {
+                Ok(request)
+            };
+
+            let mut scope = RunScope::root_value(&name, RunType::Chain, inputs);
+            if let Some(parent) = parent {
+                scope = scope.with_parent_context(&parent);
+            }
+            // Fix the run's ids now so the context handed to the handler
+            // matches the run the background task posts
+            scope.tracer_mut().init_root_ids();
+            let context = scope.tracer().child_context();
+            let post = tokio::spawn(async move {
+                if let Err(e) = scope.post_start().await {
+                    report_trace_error(Some("post"), &e);
+                }
+                scope
+            });
+
+            let mut request = match request {
+                Ok(request) => request,
+                // The body was consumed while reading it, so the handler can't run
+                Err(e) => {
+                    let message = format!("Failed to read request body: {}", e);
+                    end_in_background(post, Some(message.clone()), json!({ "status": 400 }));
+                    let mut response = Response::new(ResBody::from(Bytes::from(message)));
+                    *response.status_mut() = StatusCode::BAD_REQUEST;
+                    return Ok(response);
+                }
+            };
+            request.extensions_mut().insert(context.clone());
+
+            match with_current_trace(context.clone(), inner.call(request)).await {
+                Ok(mut response) => {
+                    let status = response.status().as_u16();
+                    if let Ok(value) = HeaderValue::from_str(&context.trace_id.to_string()) {
+                        response.headers_mut().insert(TRACE_ID_HEADER, value);
+                    }
+                    end_in_background(post, None, json!({ "status": status }));
+                    Ok(response)
+                }
+                Err(e) => {
+                    end_in_background(post, Some(e.to_string()), json!({}));
+                    Err(e)
+                }
+            }
+        })
+    }
+}
+
+/// Ends the run once its POST has been sent, without holding up the response.
+fn end_in_background(post: JoinHandle<RunScope>, error: Option<String>, outputs: Value) {
+    tokio::spawn(async move {
+        let Ok(scope) = post.await else {
+            return;
+        };
+        let _ = match error {
+            Some(error) => scope.end_error(error, Some(outputs)).await,
+            None => scope.end_ok(outputs).await,
+        };
+    });
+}
+
+/// Reads the whole request body and rebuilds the request with an identical
+/// body so the handler can still consume it. Bodies that may be larger than
+/// `max_bytes` (including streamed bodies of unknown length) are left alone.
+/// A body that fails to read is lost, so its error is returned.
+async fn buffer_body<B>(
+    request: Request<B>,
+    max_bytes: usize,
+) -> Result<(Option<Bytes>, Request<B>), Box<dyn std::error::Error + Send + Sync>>
+where
+    B: http_body::Body + From<Bytes>,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let fits = request
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|upper| upper <= max_bytes as u64);
+    if !fits {
+        return Ok((None, request));
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = Limited::new(body, max_bytes).collect().await?.to_bytes();
+    Ok((Some(bytes.clone()), Request::from_parts(parts, B::from(bytes))))
+}
+
+/// Reads the parent run propagated by an upstream service, if any.
+fn parent_context(headers: &HeaderMap) -> Option<TraceContext> {
+    TraceContext::from_header_values(|name| headers.get(name).and_then(|v| v.to_str().ok()))
+}
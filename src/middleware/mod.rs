@@ -0,0 +1,9 @@
+//! Tower middleware that traces each HTTP request as a LangSmith root run.
+//!
+//! Available with the `axum` feature.
+
+pub mod capture;
+pub mod layer;
+
+pub use capture::CaptureConfig;
+pub use layer::{LangSmithLayer, LangSmithService};
@@ -0,0 +1,22 @@
+use bytes::Bytes;
+
+/// A file attached to a run (an image, audio clip, document, ...), sent as its
+/// own part of a multipart request so LangSmith can render it. The data is
+/// reference-counted, so cloning an attachment (or the `Tracer` holding it)
+/// does not copy the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub name: String,
+    pub mime_type: String,
+    pub data: Bytes,
+}
+
+impl Attachment {
+    pub fn new(name: impl Into<String>, mime_type: impl Into<String>, data: impl Into<Bytes>) -> Self {
+        Self {
+            name: name.into(),
+            mime_type: mime_type.into(),
+            data: data.into(),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use crate::utils::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// A named collection of examples used for evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dataset {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        not(feature = "chrono"),
+        serde(with = "crate::utils::timestamp::serde_rfc3339::option")
+    )]
+    pub created_at: Option<Timestamp>,
+}
+
+/// An input/output pair stored in a dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Example {
+    pub id: Uuid,
+    pub dataset_id: Uuid,
+    #[serde(default)]
+    pub inputs: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        not(feature = "chrono"),
+        serde(with = "crate::utils::timestamp::serde_rfc3339::option")
+    )]
+    pub created_at: Option<Timestamp>,
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Evaluation feedback attached to a run (e.g. a correctness score)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feedback {
+    #[serde(rename = "run_id")]
+    pub run_id: Uuid,
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(rename = "feedback_source_type", skip_serializing_if = "Option::is_none")]
+    pub feedback_source_type: Option<String>,
+}
+
+impl Feedback {
+    pub fn new(run_id: Uuid, key: impl Into<String>) -> Self {
+        Self {
+            run_id,
+            key: key.into(),
+            score: None,
+            value: None,
+            comment: None,
+            feedback_source_type: None,
+        }
+    }
+
+    pub fn with_score(mut self, score: f64) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    pub fn with_value(mut self, value: Value) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    pub fn with_source_type(mut self, source_type: impl Into<String>) -> Self {
+        self.feedback_source_type = Some(source_type.into());
+        self
+    }
+}
@@ -1,5 +1,7 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::borrow::Cow;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCall {
@@ -8,10 +10,88 @@ pub struct ToolCall {
     pub args: Value,
 }
 
+/// Message content: plain text, or a list of parts (OpenAI's multimodal format)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+/// One part of a multimodal message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ContentPart {
+    #[serde(rename = "text")]
+    Text { text: String },
+    #[serde(rename = "image_url")]
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageUrl {
+    /// An http(s) URL or a `data:` URL with base64 image data
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl MessageContent {
+    /// The text of the content; for parts, the text parts joined by newlines
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            MessageContent::Text(text) => Cow::Borrowed(text),
+            MessageContent::Parts(parts) => Cow::Owned(
+                parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        ContentPart::Text { text } => Some(text.as_str()),
+                        ContentPart::ImageUrl { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        }
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Text(text)
+    }
+}
+
+impl From<&str> for MessageContent {
+    fn from(text: &str) -> Self {
+        MessageContent::Text(text.to_string())
+    }
+}
+
+impl From<Vec<ContentPart>> for MessageContent {
+    fn from(parts: Vec<ContentPart>) -> Self {
+        MessageContent::Parts(parts)
+    }
+}
+
+impl ContentPart {
+    pub fn text(text: impl Into<String>) -> Self {
+        ContentPart::Text { text: text.into() }
+    }
+
+    pub fn image_url(url: impl Into<String>) -> Self {
+        ContentPart::ImageUrl {
+            image_url: ImageUrl {
+                url: url.into(),
+                detail: None,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIMessage {
-    pub content: String,
-    #[serde(rename = "tool_calls", skip_serializing_if = "Vec::is_empty")]
+    pub content: MessageContent,
+    #[serde(rename = "tool_calls", default, skip_serializing_if = "Vec::is_empty")]
     pub tool_calls: Vec<ToolCall>,
 }
 
@@ -25,7 +105,7 @@ pub struct ToolMessage {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HumanMessage {
-    pub content: String,
+    pub content: MessageContent,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,9 +113,52 @@ pub struct SystemMessage {
     pub content: String,
 }
 
+/// Message from a function call, in the legacy OpenAI function-calling format
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "role", rename_all = "lowercase")]
+pub struct FunctionMessage {
+    pub name: String,
+    pub content: String,
+}
+
+/// Message with an arbitrary role (e.g. `"user"`, `"assistant"`, `"developer"`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// A chat message, serialized with a `role` tag (`"ai"`, `"tool"`, `"human"`,
+/// `"system"`, `"function"`). Any other role deserializes as `Message::Chat`,
+/// which serializes its role as-is.
+#[derive(Debug, Clone)]
 pub enum Message {
+    AI(AIMessage),
+    Tool(ToolMessage),
+    Human(HumanMessage),
+    System(SystemMessage),
+    Function(FunctionMessage),
+    Chat(ChatMessage),
+}
+
+/// Role-tagged form of the messages with a fixed role
+#[derive(Serialize)]
+#[serde(tag = "role")]
+enum TaggedMessageRef<'a> {
+    #[serde(rename = "ai")]
+    AI(&'a AIMessage),
+    #[serde(rename = "tool")]
+    Tool(&'a ToolMessage),
+    #[serde(rename = "human")]
+    Human(&'a HumanMessage),
+    #[serde(rename = "system")]
+    System(&'a SystemMessage),
+    #[serde(rename = "function")]
+    Function(&'a FunctionMessage),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "role")]
+enum TaggedMessage {
     #[serde(rename = "ai")]
     AI(AIMessage),
     #[serde(rename = "tool")]
@@ -44,5 +167,92 @@ pub enum Message {
     Human(HumanMessage),
     #[serde(rename = "system")]
     System(SystemMessage),
+    #[serde(rename = "function")]
+    Function(FunctionMessage),
 }
 
+const TAGGED_ROLES: [&str; 5] = ["ai", "tool", "human", "system", "function"];
+
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Message::AI(m) => TaggedMessageRef::AI(m).serialize(serializer),
+            Message::Tool(m) => TaggedMessageRef::Tool(m).serialize(serializer),
+            Message::Human(m) => TaggedMessageRef::Human(m).serialize(serializer),
+            Message::System(m) => TaggedMessageRef::System(m).serialize(serializer),
+            Message::Function(m) => TaggedMessageRef::Function(m).serialize(serializer),
+            Message::Chat(m) => m.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let role = value
+            .get("role")
+            .and_then(Value::as_str)
+            .ok_or_else(|| D::Error::missing_field("role"))?;
+
+        if !TAGGED_ROLES.contains(&role) {
+            return ChatMessage::deserialize(value)
+                .map(Message::Chat)
+                .map_err(D::Error::custom);
+        }
+
+        let message = TaggedMessage::deserialize(value).map_err(D::Error::custom)?;
+        Ok(match message {
+            TaggedMessage::AI(m) => Message::AI(m),
+            TaggedMessage::Tool(m) => Message::Tool(m),
+            TaggedMessage::Human(m) => Message::Human(m),
+            TaggedMessage::System(m) => Message::System(m),
+            TaggedMessage::Function(m) => Message::Function(m),
+        })
+    }
+}
+
+impl Message {
+    /// The `role` tag this message serializes with
+    pub fn role(&self) -> &str {
+        match self {
+            Message::AI(_) => "ai",
+            Message::Tool(_) => "tool",
+            Message::Human(_) => "human",
+            Message::System(_) => "system",
+            Message::Function(_) => "function",
+            Message::Chat(m) => &m.role,
+        }
+    }
+
+    /// The message text; for multimodal content, its text parts
+    pub fn content(&self) -> Cow<'_, str> {
+        match self {
+            Message::AI(m) => m.content.text(),
+            Message::Tool(m) => Cow::Borrowed(&m.content),
+            Message::Human(m) => m.content.text(),
+            Message::System(m) => Cow::Borrowed(&m.content),
+            Message::Function(m) => Cow::Borrowed(&m.content),
+            Message::Chat(m) => Cow::Borrowed(&m.content),
+        }
+    }
+}
+
+/// Run inputs for a chat model call: `{"messages": [...]}` with role-tagged
+/// messages, the shape LangSmith renders as a chat transcript
+pub fn chat_inputs(messages: &[Message]) -> Value {
+    json!({ "messages": messages })
+}
+
+/// Run outputs for a chat model's reply: `{"messages": [{"role": "ai", ...}]}`
+pub fn chat_output(message: &AIMessage) -> Value {
+    json!({ "messages": [TaggedMessageRef::AI(message)] })
+}
+
+/// Renders messages as a single prompt string, one `role: content` line each
+pub fn render_prompt(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(|message| format!("{}: {}", message.role(), message.content()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
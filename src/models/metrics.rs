@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Metrics {
     pub prompt_tokens: Option<u64>,
     pub completion_tokens: Option<u64>,
@@ -10,19 +11,6 @@ pub struct Metrics {
     pub completion_cost: Option<f64>,
 }
 
-impl Default for Metrics {
-    fn default() -> Self {
-        Self {
-            prompt_tokens: None,
-            completion_tokens: None,
-            total_tokens: None,
-            total_cost: None,
-            prompt_cost: None,
-            completion_cost: None,
-        }
-    }
-}
-
 impl Metrics {
     pub fn new() -> Self {
         Self::default()
@@ -31,10 +19,37 @@ impl Metrics {
     pub fn with_tokens(mut self, prompt: u64, completion: u64) -> Self {
         self.prompt_tokens = Some(prompt);
         self.completion_tokens = Some(completion);
-        self.total_tokens = Some(prompt + completion);
+        self.total_tokens = Some(prompt.saturating_add(completion));
         self
     }
 
+    /// Reads token counts from an OpenAI chat-completions response
+    /// (`usage.prompt_tokens`/`completion_tokens`/`total_tokens`).
+    /// Missing fields are left as `None`.
+    pub fn from_openai_usage(response: &Value) -> Self {
+        let usage = &response["usage"];
+        Self {
+            prompt_tokens: usage["prompt_tokens"].as_u64(),
+            completion_tokens: usage["completion_tokens"].as_u64(),
+            total_tokens: usage["total_tokens"].as_u64(),
+            ..Self::default()
+        }
+    }
+
+    /// Reads token counts from an Anthropic messages response
+    /// (`usage.input_tokens`/`output_tokens`). The total is only set when both are present.
+    pub fn from_anthropic_usage(response: &Value) -> Self {
+        let usage = &response["usage"];
+        let prompt_tokens = usage["input_tokens"].as_u64();
+        let completion_tokens = usage["output_tokens"].as_u64();
+        Self {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens.zip(completion_tokens).map(|(p, c)| p + c),
+            ..Self::default()
+        }
+    }
+
     pub fn with_costs(mut self, prompt: f64, completion: f64) -> Self {
         self.prompt_cost = Some(prompt);
         self.completion_cost = Some(completion);
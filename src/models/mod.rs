@@ -1,7 +1,18 @@
 pub mod run;
 pub mod messages;
 pub mod metrics;
+pub mod feedback;
+pub mod attachment;
+pub mod dataset;
+pub mod query;
 
-pub use run::{Run, RunType, RunUpdate};
-pub use messages::{AIMessage, HumanMessage, Message, SystemMessage, ToolCall, ToolMessage};
+pub use run::{DottedOrderPrecision, Run, RunErrorKind, RunStatus, RunType, RunUpdate};
+pub use feedback::Feedback;
+pub use attachment::Attachment;
+pub use dataset::{Dataset, Example};
+pub use query::{QueryRunsRequest, RunsPage};
+pub use messages::{
+    chat_inputs, chat_output, AIMessage, ChatMessage, ContentPart, FunctionMessage, HumanMessage,
+    ImageUrl, Message, MessageContent, SystemMessage, ToolCall, ToolMessage,
+};
 
@@ -0,0 +1,98 @@
+use crate::models::run::{Run, RunType};
+use crate::utils::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Filters for `LangSmithClient::query_runs` (`POST /runs/query`)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueryRunsRequest {
+    /// Project (session) ids to search
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub session: Vec<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_type: Option<RunType>,
+    /// Only runs started at or after this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        not(feature = "chrono"),
+        serde(default, with = "crate::utils::timestamp::serde_rfc3339::option")
+    )]
+    pub start_time: Option<Timestamp>,
+    /// Only runs started before this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        not(feature = "chrono"),
+        serde(default, with = "crate::utils::timestamp::serde_rfc3339::option")
+    )]
+    pub end_time: Option<Timestamp>,
+    /// Maximum number of runs per page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Cursor of the page to fetch, from `RunsPage::next_cursor`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+impl QueryRunsRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_session(mut self, session_id: Uuid) -> Self {
+        self.session.push(session_id);
+        self
+    }
+
+    pub fn with_run_type(mut self, run_type: RunType) -> Self {
+        self.run_type = Some(run_type);
+        self
+    }
+
+    pub fn with_time_range(mut self, start: Timestamp, end: Timestamp) -> Self {
+        self.start_time = Some(start);
+        self.end_time = Some(end);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+/// One page of `POST /runs/query` results
+#[derive(Debug, Clone)]
+pub struct RunsPage {
+    pub runs: Vec<Run>,
+    /// Cursor for the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Raw `POST /runs/query` response
+#[derive(Deserialize)]
+pub(crate) struct QueryRunsResponse {
+    #[serde(default)]
+    pub runs: Vec<Run>,
+    #[serde(default)]
+    pub cursors: Cursors,
+}
+
+#[derive(Default, Deserialize)]
+pub(crate) struct Cursors {
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+impl From<QueryRunsResponse> for RunsPage {
+    fn from(response: QueryRunsResponse) -> Self {
+        Self {
+            runs: response.runs,
+            next_cursor: response.cursors.next,
+        }
+    }
+}
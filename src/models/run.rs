@@ -1,11 +1,16 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use crate::error::LangSmithError;
+use crate::models::messages::ToolCall;
+use crate::models::metrics::Metrics;
+use crate::utils::timestamp::{self, Timestamp};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+/// Kind of run. Serialized as the flat string LangSmith expects (`"llm"`,
+/// `"chain"`, or the custom name itself).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RunType {
     Chain,
     Llm,
@@ -32,19 +37,156 @@ impl RunType {
     }
 }
 
+impl From<&str> for RunType {
+    fn from(s: &str) -> Self {
+        match s {
+            "chain" => RunType::Chain,
+            "llm" => RunType::Llm,
+            "tool" => RunType::Tool,
+            "retriever" => RunType::Retriever,
+            "embedding" => RunType::Embedding,
+            "prompt" => RunType::Prompt,
+            "runnable" => RunType::Runnable,
+            other => RunType::Custom(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for RunType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RunType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(RunType::from(s.as_str()))
+    }
+}
+
+/// Fractional-second digits in the time part of `dotted_order` segments.
+/// Nanoseconds keep sibling runs ordered on platforms with a coarse clock,
+/// where many runs would otherwise share a microsecond stamp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DottedOrderPrecision {
+    /// 6 digits, understood by every LangSmith server
+    #[default]
+    Micros,
+    /// 9 digits, accepted by newer LangSmith servers
+    Nanos,
+}
+
+impl DottedOrderPrecision {
+    /// Parses `micros`/`us` or `nanos`/`ns`, in any case
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "micros" | "us" => Some(DottedOrderPrecision::Micros),
+            "nanos" | "ns" => Some(DottedOrderPrecision::Nanos),
+            _ => None,
+        }
+    }
+}
+
+/// Broad category of a failure, recorded in `extra.error_kind` by
+/// `Tracer::set_error_kind` so failed runs can be filtered by cause
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunErrorKind {
+    Timeout,
+    RateLimit,
+    InvalidInput,
+    ProviderError,
+    Other,
+}
+
+impl RunErrorKind {
+    /// Category of an HTTP error status: 429 is a rate limit, 408 and 504
+    /// are timeouts, other 4xx are invalid input and 5xx provider errors
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            429 => RunErrorKind::RateLimit,
+            408 | 504 => RunErrorKind::Timeout,
+            400..=499 => RunErrorKind::InvalidInput,
+            500..=599 => RunErrorKind::ProviderError,
+            _ => RunErrorKind::Other,
+        }
+    }
+}
+
+impl From<&LangSmithError> for RunErrorKind {
+    fn from(error: &LangSmithError) -> Self {
+        match error {
+            LangSmithError::Timeout(_) => RunErrorKind::Timeout,
+            LangSmithError::Api { status, .. } => RunErrorKind::from_status(*status),
+            LangSmithError::Http(e) if e.is_timeout() => RunErrorKind::Timeout,
+            LangSmithError::Http(e) => e
+                .status()
+                .map_or(RunErrorKind::Other, |status| RunErrorKind::from_status(status.as_u16())),
+            _ => RunErrorKind::Other,
+        }
+    }
+}
+
+/// A `dotted_order` whose last segment is `time` (at `precision`) and `id`,
+/// under `parent_dotted_order` if given
+pub(crate) fn dotted_order_at(
+    parent_dotted_order: Option<&str>,
+    time: &Timestamp,
+    id: Uuid,
+    precision: DottedOrderPrecision,
+) -> String {
+    // Format: YYYYMMDDTHHMMSS{microseconds}Z{uuid}
+    // Example: 20240919T171648521691Z0e01bf50-474d-4536-810f-67d3ee7ea3e7
+    let time = match precision {
+        DottedOrderPrecision::Micros => timestamp::dotted_order_time(time),
+        DottedOrderPrecision::Nanos => timestamp::dotted_order_time_nanos(time),
+    };
+    let uuid_str = id.to_string(); // Full UUID with hyphens
+
+    let current_part = format!("{}{}", time, uuid_str);
+
+    if let Some(parent) = parent_dotted_order {
+        format!("{}.{}", parent, current_part)
+    } else {
+        current_part
+    }
+}
+
+/// Lifecycle state of a run, as shown in the LangSmith UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Pending,
+    Success,
+    Error,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Run {
     pub id: Uuid,
     pub name: String,
     #[serde(rename = "run_type")]
     pub run_type: RunType,
+    #[serde(default)]
     pub inputs: Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub outputs: Option<Value>,
+    /// Short text shown in the LangSmith UI instead of loading full inputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs_preview: Option<String>,
+    /// Short text shown in the LangSmith UI instead of loading full outputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs_preview: Option<String>,
     #[serde(rename = "start_time")]
-    pub start_time: DateTime<Utc>,
+    #[cfg_attr(not(feature = "chrono"), serde(with = "crate::utils::timestamp::serde_rfc3339"))]
+    pub start_time: Timestamp,
     #[serde(rename = "end_time", skip_serializing_if = "Option::is_none")]
-    pub end_time: Option<DateTime<Utc>>,
+    #[cfg_attr(
+        not(feature = "chrono"),
+        serde(default, with = "crate::utils::timestamp::serde_rfc3339::option")
+    )]
+    pub end_time: Option<Timestamp>,
     #[serde(rename = "parent_run_id", skip_serializing_if = "Option::is_none")]
     pub parent_run_id: Option<Uuid>,
     #[serde(rename = "trace_id", skip_serializing_if = "Option::is_none")]
@@ -57,11 +199,16 @@ pub struct Run {
     pub session_name: Option<String>,
     #[serde(rename = "thread_id", skip_serializing_if = "Option::is_none")]
     pub thread_id: Option<String>,
+    /// Dataset example this run was evaluated against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_example_id: Option<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<RunStatus>,
+    #[serde(default, deserialize_with = "null_as_default", skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    #[serde(default, deserialize_with = "null_as_default", skip_serializing_if = "HashMap::is_empty")]
     pub extra: HashMap<String, Value>,
     // Metrics
     #[serde(rename = "prompt_tokens", skip_serializing_if = "Option::is_none")]
@@ -80,8 +227,18 @@ pub struct Run {
 
 impl Run {
     pub fn new(name: String, run_type: RunType, inputs: Value) -> Self {
+        Self::new_at(name, run_type, inputs, timestamp::now())
+    }
+
+    /// Creates a run that started at `start_time`, e.g. when importing a trace
+    /// after the fact
+    pub fn new_at(
+        name: String,
+        run_type: RunType,
+        inputs: Value,
+        start_time: Timestamp,
+    ) -> Self {
         let id = Uuid::new_v4();
-        let start_time = Utc::now();
 
         Self {
             id,
@@ -89,6 +246,8 @@ impl Run {
             run_type,
             inputs,
             outputs: None,
+            inputs_preview: None,
+            outputs_preview: None,
             start_time,
             end_time: None,
             parent_run_id: None,
@@ -97,7 +256,9 @@ impl Run {
             session_id: None,
             session_name: None,
             thread_id: None,
+            reference_example_id: None,
             error: None,
+            status: Some(RunStatus::Pending),
             tags: Vec::new(),
             extra: HashMap::new(),
             prompt_tokens: None,
@@ -109,40 +270,95 @@ impl Run {
         }
     }
 
+    /// Creates a tool run for a model's tool call: named after the tool, with
+    /// `{name, args}` as inputs and the call id in `extra.tool_call_id`
+    pub fn from_tool_call(tool: &ToolCall) -> Self {
+        let inputs = json!({"name": tool.name, "args": tool.args});
+        let mut run = Self::new(tool.name.clone(), RunType::Tool, inputs);
+        run.extra.insert("tool_call_id".to_string(), json!(tool.id));
+        run
+    }
+
     pub fn generate_dotted_order(&self, parent_dotted_order: Option<&str>) -> String {
-        // Format: YYYYMMDDTHHMMSS{microseconds}Z{uuid}
-        // Example: 20240919T171648521691Z0e01bf50-474d-4536-810f-67d3ee7ea3e7
-        let timestamp = self.start_time.format("%Y%m%dT%H%M%S");
-        let microseconds = self.start_time.timestamp_subsec_micros();
-        let uuid_str = self.id.to_string(); // Full UUID with hyphens
-        
-        let current_part = format!("{}{:06}Z{}", timestamp, microseconds, uuid_str);
-        
-        if let Some(parent) = parent_dotted_order {
-            format!("{}.{}", parent, current_part)
-        } else {
-            current_part
-        }
+        self.generate_dotted_order_with(parent_dotted_order, DottedOrderPrecision::Micros)
+    }
+
+    /// Like `generate_dotted_order`, with the time part at `precision`
+    pub fn generate_dotted_order_with(
+        &self,
+        parent_dotted_order: Option<&str>,
+        precision: DottedOrderPrecision,
+    ) -> String {
+        dotted_order_at(parent_dotted_order, &self.start_time, self.id, precision)
+    }
+
+    /// Deserializes the inputs into `T`, e.g. to check a recorded run in a test.
+    /// Fails with serde's description of the mismatch.
+    pub fn inputs_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.inputs)
+    }
+
+    /// Deserializes the outputs into `T`. Outputs of a run that hasn't ended
+    /// read as `null`, so use `Option<T>` when that is expected.
+    pub fn outputs_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(self.outputs.as_ref().unwrap_or(&Value::Null))
     }
 
     pub fn set_error(&mut self, error: &str) {
         self.error = Some(error.to_string());
+        self.status = Some(RunStatus::Error);
+    }
+
+    /// Copies token counts and costs from `metrics` onto the run
+    pub fn set_metrics(&mut self, metrics: &Metrics) {
+        self.prompt_tokens = metrics.prompt_tokens;
+        self.completion_tokens = metrics.completion_tokens;
+        self.total_tokens = metrics.total_tokens;
+        self.total_cost = metrics.total_cost;
+        self.prompt_cost = metrics.prompt_cost;
+        self.completion_cost = metrics.completion_cost;
     }
 
     pub fn end(&mut self, outputs: Value) {
+        self.end_at(outputs, timestamp::now());
+    }
+
+    /// Ends the run at `end_time` rather than now, e.g. for replayed spans
+    pub fn end_at(&mut self, outputs: Value, end_time: Timestamp) {
         self.outputs = Some(outputs);
-        self.end_time = Some(Utc::now());
+        self.end_time = Some(end_time);
+        self.status = Some(if self.error.is_some() {
+            RunStatus::Error
+        } else {
+            RunStatus::Success
+        });
     }
 }
 
+/// Deserializes an explicit `null` (as returned by the API for empty
+/// collections) into the type's default value.
+fn null_as_default<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunUpdate {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub outputs: Option<Value>,
     #[serde(rename = "end_time", skip_serializing_if = "Option::is_none")]
-    pub end_time: Option<DateTime<Utc>>,
+    #[cfg_attr(
+        not(feature = "chrono"),
+        serde(default, with = "crate::utils::timestamp::serde_rfc3339::option")
+    )]
+    pub end_time: Option<Timestamp>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<RunStatus>,
     #[serde(rename = "prompt_tokens", skip_serializing_if = "Option::is_none")]
     pub prompt_tokens: Option<u64>,
     #[serde(rename = "completion_tokens", skip_serializing_if = "Option::is_none")]
@@ -151,6 +367,16 @@ pub struct RunUpdate {
     pub total_tokens: Option<u64>,
     #[serde(rename = "total_cost", skip_serializing_if = "Option::is_none")]
     pub total_cost: Option<f64>,
+    #[serde(rename = "prompt_cost", skip_serializing_if = "Option::is_none")]
+    pub prompt_cost: Option<f64>,
+    #[serde(rename = "completion_cost", skip_serializing_if = "Option::is_none")]
+    pub completion_cost: Option<f64>,
+    /// Run metadata, sent so entries added after the run was posted (e.g.
+    /// error details) reach LangSmith
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra: Option<HashMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs_preview: Option<String>,
 }
 
 impl From<&Run> for RunUpdate {
@@ -159,10 +385,15 @@ impl From<&Run> for RunUpdate {
             outputs: run.outputs.clone(),
             end_time: run.end_time,
             error: run.error.clone(),
+            status: run.status,
             prompt_tokens: run.prompt_tokens,
             completion_tokens: run.completion_tokens,
             total_tokens: run.total_tokens,
             total_cost: run.total_cost,
+            prompt_cost: run.prompt_cost,
+            completion_cost: run.completion_cost,
+            extra: (!run.extra.is_empty()).then(|| run.extra.clone()),
+            outputs_preview: run.outputs_preview.clone(),
         }
     }
 }
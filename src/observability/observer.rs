@@ -1,4 +1,12 @@
+use crate::config::Config;
+use crate::error::{report_trace_error, LangSmithError};
+use crate::models::run::{Run, RunType};
+use crate::tracing::tracer::Tracer;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+use uuid::Uuid;
 
 /// Observer trait for observing node execution events
 pub trait Observer: Send + Sync {
@@ -12,38 +20,124 @@ pub trait Observer: Send + Sync {
     fn on_node_error(&self, node_name: &str, error: &str);
 }
 
-/// LangSmith observer that traces to LangSmith
+/// Observer that records each observed node as a LangSmith run.
+///
+/// `on_node_start` creates a `Tracer` and keeps it in flight under the node
+/// name; `on_node_end`/`on_node_error` finish the most recent in-flight run
+/// for that name, so nested or recursive nodes with the same name pair up
+/// last-in, first-out.
+///
+/// Observer callbacks are synchronous, so when tracing is enabled each run is
+/// sent by a task spawned on the current tokio runtime: it posts the run, then
+/// waits for the node to finish and patches it. Runs are still tracked when
+/// tracing is disabled, they're just never sent.
+#[derive(Default)]
 pub struct LangSmithObserver {
-    // Can hold tracer or client
+    in_flight: Mutex<HashMap<String, Vec<InFlightRun>>>,
+}
+
+struct InFlightRun {
+    tracer: Tracer,
+    /// Hands the finished tracer to the task that posted it
+    finished: Option<oneshot::Sender<Tracer>>,
 }
 
 impl LangSmithObserver {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
-}
 
-impl Observer for LangSmithObserver {
-    fn on_node_start(&self, node_name: &str, _inputs: &Value) {
-        // Implementation would create a tracer and post the run
-        // This is a simplified version - actual implementation would use Tracer
-        eprintln!("LangSmithObserver: Node '{}' started", node_name);
+    /// Number of started runs that haven't ended yet
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().values().map(Vec::len).sum()
     }
 
-    fn on_node_end(&self, node_name: &str, _outputs: &Value) {
-        // Implementation would patch the run with outputs
-        eprintln!("LangSmithObserver: Node '{}' completed", node_name);
+    /// Starts a run for `node_name`; what `on_node_start` does.
+    pub fn start_run(&self, node_name: &str, inputs: &Value) -> Uuid {
+        let tracer = Tracer::new(node_name, RunType::Chain, inputs.clone());
+        let run_id = tracer.run_id();
+        let finished = if Config::is_tracing_enabled() {
+            send_when_finished(tracer.clone())
+        } else {
+            None
+        };
+
+        self.in_flight
+            .lock()
+            .unwrap()
+            .entry(node_name.to_string())
+            .or_default()
+            .push(InFlightRun { tracer, finished });
+        run_id
     }
 
-    fn on_node_error(&self, node_name: &str, error: &str) {
-        // Implementation would patch the run with error
-        eprintln!("LangSmithObserver: Node '{}' error: {}", node_name, error);
+    /// Ends the latest in-flight run for `node_name` with `outputs`, or with
+    /// `error` when set. Returns the finished run, or `None` if no run was
+    /// started for that name.
+    pub fn end_run(&self, node_name: &str, outputs: &Value, error: Option<&str>) -> Option<Run> {
+        let InFlightRun {
+            mut tracer,
+            finished,
+        } = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let runs = in_flight.get_mut(node_name)?;
+            let run = runs.pop();
+            if runs.is_empty() {
+                in_flight.remove(node_name);
+            }
+            run?
+        };
+
+        if let Some(error) = error {
+            tracer.set_error(error);
+        }
+        tracer.end(outputs.clone());
+        let run = tracer.run().clone();
+        if let Some(finished) = finished {
+            let _ = finished.send(tracer);
+        }
+        Some(run)
     }
 }
 
-impl Default for LangSmithObserver {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Spawns a task that posts `tracer` and patches it once the node finishes
+fn send_when_finished(mut tracer: Tracer) -> Option<oneshot::Sender<Tracer>> {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        report_trace_error(
+            Some("observer"),
+            &LangSmithError::Other("no tokio runtime to send observed runs".to_string()),
+        );
+        return None;
+    };
+
+    let (finished, receiver) = oneshot::channel::<Tracer>();
+    handle.spawn(async move {
+        let _ = tracer.post().await;
+        if let Ok(tracer) = receiver.await {
+            let _ = tracer.patch().await;
+        }
+    });
+    Some(finished)
 }
 
+impl Observer for LangSmithObserver {
+    fn on_node_start(&self, node_name: &str, inputs: &Value) {
+        self.start_run(node_name, inputs);
+        log::debug!(target: "langsmith", "LangSmithObserver: Node '{}' started", node_name);
+    }
+
+    fn on_node_end(&self, node_name: &str, outputs: &Value) {
+        if self.end_run(node_name, outputs, None).is_none() {
+            log::warn!(
+                target: "langsmith",
+                "LangSmithObserver: Node '{}' ended without being started",
+                node_name
+            );
+        }
+    }
+
+    fn on_node_error(&self, node_name: &str, error: &str) {
+        self.end_run(node_name, &Value::Object(Default::default()), Some(error));
+        log::warn!(target: "langsmith", "LangSmithObserver: Node '{}' error: {}", node_name, error);
+    }
+}
@@ -0,0 +1,175 @@
+//! OpenTelemetry export: converts runs into OTLP spans.
+//!
+//! Available with the `otel` feature.
+
+use crate::error::{LangSmithError, Result};
+use crate::models::run::Run;
+use opentelemetry::trace::{
+    SpanContext, SpanId, SpanKind, Status, TraceFlags, TraceId, TraceState,
+};
+use opentelemetry::{InstrumentationScope, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{SpanData, SpanEvents, SpanExporter, SpanLinks};
+use serde_json::Value;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// Ships runs to an OpenTelemetry collector as spans.
+///
+/// Run ids map to span ids (the low 64 bits of the UUID) and trace ids map
+/// one-to-one. A span's parent is the previous run in its `dotted_order`, so
+/// the run tree becomes the span tree even when runs are exported one by one.
+/// Inputs, outputs, token counts and costs become span attributes, and runs
+/// with an error get an error status.
+#[derive(Debug)]
+pub struct OtelExporter<E: SpanExporter = opentelemetry_otlp::SpanExporter> {
+    exporter: E,
+}
+
+impl OtelExporter {
+    /// OTLP/HTTP (protobuf) exporter sending to `endpoint`, e.g.
+    /// `http://localhost:4318/v1/traces`
+    pub fn new(endpoint: impl Into<String>) -> Result<Self> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()
+            .map_err(|e| LangSmithError::Config(format!("OTLP exporter: {}", e)))?;
+        Ok(Self::with_exporter(exporter))
+    }
+}
+
+impl<E: SpanExporter> OtelExporter<E> {
+    /// Wraps any span exporter (OTLP over gRPC, in-memory, ...)
+    pub fn with_exporter(exporter: E) -> Self {
+        Self { exporter }
+    }
+
+    /// Exports a single run as one span
+    pub async fn export(&self, run: &Run) -> Result<()> {
+        self.export_all(std::slice::from_ref(run)).await
+    }
+
+    /// Exports a run tree (or any set of runs) in one batch
+    pub async fn export_all(&self, runs: &[Run]) -> Result<()> {
+        let spans = runs.iter().map(to_span_data).collect();
+        self.exporter
+            .export(spans)
+            .await
+            .map_err(|e| LangSmithError::Other(format!("OTLP export failed: {}", e)))
+    }
+
+    pub fn exporter(&self) -> &E {
+        &self.exporter
+    }
+}
+
+/// Converts a run into the span the exporter sends
+pub fn to_span_data(run: &Run) -> SpanData {
+    let ancestry = dotted_order_ids(run.dotted_order.as_deref());
+    let trace_id = run.trace_id.or_else(|| ancestry.first().copied()).unwrap_or(run.id);
+    let parent = ancestry
+        .len()
+        .checked_sub(2)
+        .map(|i| ancestry[i])
+        .or(run.parent_run_id);
+
+    let start_time = SystemTime::from(run.start_time);
+    let end_time = run.end_time.map(SystemTime::from).unwrap_or(start_time);
+    let status = match &run.error {
+        Some(error) => Status::error(error.clone()),
+        None if run.end_time.is_some() => Status::Ok,
+        None => Status::Unset,
+    };
+
+    SpanData {
+        span_context: SpanContext::new(
+            TraceId::from_bytes(*trace_id.as_bytes()),
+            span_id(run.id),
+            TraceFlags::SAMPLED,
+            false,
+            TraceState::default(),
+        ),
+        parent_span_id: parent.map(span_id).unwrap_or(SpanId::INVALID),
+        parent_span_is_remote: false,
+        span_kind: SpanKind::Internal,
+        name: run.name.clone().into(),
+        start_time,
+        end_time,
+        attributes: attributes(run),
+        dropped_attributes_count: 0,
+        events: SpanEvents::default(),
+        links: SpanLinks::default(),
+        status,
+        instrumentation_scope: InstrumentationScope::builder("langsmith-rust")
+            .with_version(env!("CARGO_PKG_VERSION"))
+            .build(),
+    }
+}
+
+/// Span id for a run: the low 64 bits of its UUID
+pub fn span_id(run_id: Uuid) -> SpanId {
+    let bytes = run_id.as_bytes();
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[8..]);
+    SpanId::from_bytes(low)
+}
+
+/// Run ids along a dotted order, root first
+fn dotted_order_ids(dotted_order: Option<&str>) -> Vec<Uuid> {
+    dotted_order
+        .into_iter()
+        .flat_map(|order| order.split('.'))
+        .filter_map(|segment| segment.split_once('Z'))
+        .filter_map(|(_, id)| Uuid::parse_str(id).ok())
+        .collect()
+}
+
+fn attributes(run: &Run) -> Vec<KeyValue> {
+    let mut attributes = vec![
+        KeyValue::new("langsmith.run.id", run.id.to_string()),
+        KeyValue::new("langsmith.run.type", run.run_type.as_str().to_string()),
+    ];
+    if !run.inputs.is_null() {
+        attributes.push(KeyValue::new("langsmith.inputs", json_attribute(&run.inputs)));
+    }
+    if let Some(outputs) = &run.outputs {
+        attributes.push(KeyValue::new("langsmith.outputs", json_attribute(outputs)));
+    }
+    if let Some(session_name) = &run.session_name {
+        attributes.push(KeyValue::new("langsmith.session.name", session_name.clone()));
+    }
+    if let Some(thread_id) = &run.thread_id {
+        attributes.push(KeyValue::new("langsmith.thread.id", thread_id.clone()));
+    }
+
+    let tokens = [
+        ("gen_ai.usage.input_tokens", run.prompt_tokens),
+        ("gen_ai.usage.output_tokens", run.completion_tokens),
+        ("langsmith.usage.total_tokens", run.total_tokens),
+    ];
+    for (key, value) in tokens {
+        if let Some(value) = value {
+            attributes.push(KeyValue::new(key, value as i64));
+        }
+    }
+
+    let costs = [
+        ("langsmith.cost.prompt", run.prompt_cost),
+        ("langsmith.cost.completion", run.completion_cost),
+        ("langsmith.cost.total", run.total_cost),
+    ];
+    for (key, value) in costs {
+        if let Some(value) = value {
+            attributes.push(KeyValue::new(key, value));
+        }
+    }
+    attributes
+}
+
+fn json_attribute(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,7 @@
+//! The types most tracing code needs, for a single glob import:
+//! `use langsmith_rust::prelude::*;`
+
+pub use crate::error::Result;
+pub use crate::models::metrics::Metrics;
+pub use crate::models::run::RunType;
+pub use crate::tracing::{trace_node, GraphTrace, RunScope, TraceContext, Tracer};
@@ -0,0 +1,73 @@
+//! Cost estimation from token counts and per-model pricing.
+
+use crate::models::metrics::Metrics;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Price of a model in USD per 1K tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input_per_1k: f64,
+    pub output_per_1k: f64,
+}
+
+static PRICING: Lazy<RwLock<HashMap<String, ModelPricing>>> = Lazy::new(|| {
+    let known = [
+        ("gpt-4o", 0.0025, 0.01),
+        ("gpt-4o-mini", 0.00015, 0.0006),
+        ("gpt-4-turbo", 0.01, 0.03),
+        ("gpt-3.5-turbo", 0.0005, 0.0015),
+        ("claude-3-5-sonnet", 0.003, 0.015),
+        ("claude-3-5-haiku", 0.0008, 0.004),
+        ("claude-3-opus", 0.015, 0.075),
+        ("claude-3-haiku", 0.00025, 0.00125),
+    ];
+    let table = known
+        .into_iter()
+        .map(|(model, input, output)| (model.to_string(), ModelPricing::new(input, output)))
+        .collect();
+    RwLock::new(table)
+});
+
+impl ModelPricing {
+    pub fn new(input_per_1k: f64, output_per_1k: f64) -> Self {
+        Self {
+            input_per_1k,
+            output_per_1k,
+        }
+    }
+
+    /// Adds or replaces the pricing for `model`
+    pub fn register(model: impl Into<String>, pricing: ModelPricing) {
+        PRICING.write().unwrap().insert(model.into(), pricing);
+    }
+
+    /// Finds pricing for `model`. Dated or suffixed names such as
+    /// `gpt-4o-2024-08-06` match the longest registered prefix.
+    pub fn lookup(model: &str) -> Option<ModelPricing> {
+        let table = PRICING.read().unwrap();
+        if let Some(pricing) = table.get(model) {
+            return Some(*pricing);
+        }
+        table
+            .iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, pricing)| *pricing)
+    }
+}
+
+/// Builds token and cost metrics for a call to `model`, or `None` if the
+/// model's pricing is unknown.
+pub fn estimate_cost(model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<Metrics> {
+    let pricing = ModelPricing::lookup(model)?;
+    let prompt_cost = prompt_tokens as f64 / 1000.0 * pricing.input_per_1k;
+    let completion_cost = completion_tokens as f64 / 1000.0 * pricing.output_per_1k;
+
+    Some(
+        Metrics::new()
+            .with_tokens(prompt_tokens, completion_tokens)
+            .with_costs(prompt_cost, completion_cost),
+    )
+}
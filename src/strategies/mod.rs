@@ -1,6 +1,8 @@
 pub mod tracing_strategy;
 pub mod serialization_strategy;
 
-pub use tracing_strategy::{TracingStrategy, AsyncTracingStrategy, SyncTracingStrategy};
+pub use tracing_strategy::{
+    TracingStrategy, AsyncTracingStrategy, NoOpTracingStrategy, SyncTracingStrategy,
+};
 pub use serialization_strategy::SerializationStrategy;
 
@@ -1,16 +1,42 @@
+use crate::utils::serialization::object_or_messages;
 use serde::Serialize;
 use serde_json::Value;
 
 /// Strategy for serialization approaches
+///
+/// Implementors shape already-serialized values, which keeps the trait usable
+/// as `Arc<dyn SerializationStrategy>` (see `TraceOptions::with_serialization`).
+/// By default values are wrapped like `DefaultSerializationStrategy` does.
 pub trait SerializationStrategy: Send + Sync {
+    /// Turns serialized inputs into the run's inputs object
+    fn inputs_object(&self, value: Value) -> Value {
+        object_or_messages(value, "input")
+    }
+
+    /// Turns serialized outputs into the run's outputs object
+    fn outputs_object(&self, value: Value) -> Value {
+        object_or_messages(value, "output")
+    }
+
     /// Serialize inputs ensuring they're always an object
-    fn serialize_inputs<T: Serialize>(&self, value: &T) -> Result<Value, serde_json::Error>;
-    
+    fn serialize_inputs<T: Serialize>(&self, value: &T) -> Result<Value, serde_json::Error>
+    where
+        Self: Sized,
+    {
+        serde_json::to_value(value).map(|value| self.inputs_object(value))
+    }
+
     /// Serialize outputs ensuring they're always an object
-    fn serialize_outputs<T: Serialize>(&self, value: &T) -> Result<Value, serde_json::Error>;
+    fn serialize_outputs<T: Serialize>(&self, value: &T) -> Result<Value, serde_json::Error>
+    where
+        Self: Sized,
+    {
+        serde_json::to_value(value).map(|value| self.outputs_object(value))
+    }
 }
 
-/// Default serialization strategy (wraps primitives in objects)
+/// Default serialization strategy (wraps primitives in objects, and chat
+/// message lists under `"messages"`)
 pub struct DefaultSerializationStrategy {
     input_key: String,
     output_key: String,
@@ -39,22 +65,11 @@ impl Default for DefaultSerializationStrategy {
 }
 
 impl SerializationStrategy for DefaultSerializationStrategy {
-    fn serialize_inputs<T: Serialize>(&self, value: &T) -> Result<Value, serde_json::Error> {
-        let mut json_value = serde_json::to_value(value)?;
-        if !json_value.is_object() {
-            let key = self.input_key.clone();
-            json_value = serde_json::json!({ key: json_value });
-        }
-        Ok(json_value)
+    fn inputs_object(&self, value: Value) -> Value {
+        object_or_messages(value, &self.input_key)
     }
 
-    fn serialize_outputs<T: Serialize>(&self, value: &T) -> Result<Value, serde_json::Error> {
-        let mut json_value = serde_json::to_value(value)?;
-        if !json_value.is_object() {
-            let key = self.output_key.clone();
-            json_value = serde_json::json!({ key: json_value });
-        }
-        Ok(json_value)
+    fn outputs_object(&self, value: Value) -> Value {
+        object_or_messages(value, &self.output_key)
     }
 }
-
@@ -1,6 +1,9 @@
+use crate::client::LangSmithClient;
 use crate::error::Result;
-use crate::models::run::Run;
+use crate::models::run::{Run, RunUpdate};
 use async_trait::async_trait;
+use once_cell::sync::OnceCell;
+use std::sync::Arc;
 
 /// Strategy pattern for different tracing implementations
 #[async_trait]
@@ -15,37 +18,58 @@ pub trait TracingStrategy: Send + Sync {
     async fn trace_error(&self, run: &Run, error: &str) -> Result<()>;
 }
 
+fn shared_client(cell: &OnceCell<Arc<LangSmithClient>>) -> Result<Arc<LangSmithClient>> {
+    cell.get_or_try_init(|| LangSmithClient::new().map(Arc::new))
+        .cloned()
+}
+
 /// Async tracing strategy (default)
 pub struct AsyncTracingStrategy {
-    // Can hold client or other dependencies
+    client: OnceCell<Arc<LangSmithClient>>,
 }
 
 impl AsyncTracingStrategy {
+    /// Creates a strategy whose client is built from the global config on first use
     pub fn new() -> Self {
-        Self {}
+        Self {
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Creates a strategy that sends runs through `client`
+    pub fn with_client(client: Arc<LangSmithClient>) -> Self {
+        Self {
+            client: OnceCell::with_value(client),
+        }
+    }
+
+    /// The client shared by every call on this strategy
+    pub fn client(&self) -> Result<Arc<LangSmithClient>> {
+        shared_client(&self.client)
+    }
+}
+
+impl Default for AsyncTracingStrategy {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl TracingStrategy for AsyncTracingStrategy {
     async fn trace_start(&self, run: &Run) -> Result<()> {
-        use crate::client::LangSmithClient;
-        let client = LangSmithClient::new()?;
+        let client = self.client()?;
         client.post_run(run).await
     }
 
     async fn trace_end(&self, run: &Run) -> Result<()> {
-        use crate::client::LangSmithClient;
-        use crate::models::run::RunUpdate;
-        let client = LangSmithClient::new()?;
+        let client = self.client()?;
         let updates = RunUpdate::from(run);
         client.patch_run(run.id, &updates).await
     }
 
     async fn trace_error(&self, run: &Run, error: &str) -> Result<()> {
-        use crate::client::LangSmithClient;
-        use crate::models::run::RunUpdate;
-        let client = LangSmithClient::new()?;
+        let client = self.client()?;
         let mut updates = RunUpdate::from(run);
         updates.error = Some(error.to_string());
         client.patch_run(run.id, &updates).await
@@ -54,41 +78,82 @@ impl TracingStrategy for AsyncTracingStrategy {
 
 /// Sync tracing strategy (uses blocking runtime)
 pub struct SyncTracingStrategy {
-    // Can hold client or other dependencies
+    client: OnceCell<Arc<LangSmithClient>>,
 }
 
 impl SyncTracingStrategy {
+    /// Creates a strategy whose client is built from the global config on first use
     pub fn new() -> Self {
-        Self {}
+        Self {
+            client: OnceCell::new(),
+        }
+    }
+
+    /// Creates a strategy that sends runs through `client`
+    pub fn with_client(client: Arc<LangSmithClient>) -> Self {
+        Self {
+            client: OnceCell::with_value(client),
+        }
+    }
+
+    /// The client shared by every call on this strategy
+    pub fn client(&self) -> Result<Arc<LangSmithClient>> {
+        shared_client(&self.client)
+    }
+}
+
+impl Default for SyncTracingStrategy {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[async_trait]
 impl TracingStrategy for SyncTracingStrategy {
     async fn trace_start(&self, run: &Run) -> Result<()> {
-        use crate::client::LangSmithClient;
         let rt = tokio::runtime::Runtime::new().unwrap();
-        let client = LangSmithClient::new()?;
+        let client = self.client()?;
         rt.block_on(client.post_run(run))
     }
 
     async fn trace_end(&self, run: &Run) -> Result<()> {
-        use crate::client::LangSmithClient;
-        use crate::models::run::RunUpdate;
         let rt = tokio::runtime::Runtime::new().unwrap();
-        let client = LangSmithClient::new()?;
+        let client = self.client()?;
         let updates = RunUpdate::from(run);
         rt.block_on(client.patch_run(run.id, &updates))
     }
 
     async fn trace_error(&self, run: &Run, error: &str) -> Result<()> {
-        use crate::client::LangSmithClient;
-        use crate::models::run::RunUpdate;
         let rt = tokio::runtime::Runtime::new().unwrap();
-        let client = LangSmithClient::new()?;
+        let client = self.client()?;
         let mut updates = RunUpdate::from(run);
         updates.error = Some(error.to_string());
         rt.block_on(client.patch_run(run.id, &updates))
     }
 }
 
+/// Tracing strategy that does nothing, for tests and offline development.
+/// It never reads the config or touches the network.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoOpTracingStrategy;
+
+impl NoOpTracingStrategy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl TracingStrategy for NoOpTracingStrategy {
+    async fn trace_start(&self, _run: &Run) -> Result<()> {
+        Ok(())
+    }
+
+    async fn trace_end(&self, _run: &Run) -> Result<()> {
+        Ok(())
+    }
+
+    async fn trace_error(&self, _run: &Run, _error: &str) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use crate::client::LangSmithClient;
+use crate::config::Config;
+use crate::error::{report_trace_error, LangSmithError};
+use crate::models::run::RunType;
+use crate::tracing::context::TraceContext;
+use crate::tracing::tracer::Tracer;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// `tracing_subscriber::Layer` that records each `tracing` span as a run.
+///
+/// - the span name becomes the run name;
+/// - fields set when the span is created become the run's inputs, and fields
+///   recorded later (`span.record(...)`) its outputs;
+/// - the span's target and level are added to `extra`;
+/// - opening the span posts the run and closing it patches the end time;
+/// - `ERROR` events inside a span set the run's error.
+///
+/// Each span stores its `TraceContext` in the span extensions; child spans
+/// use it to set `parent_run_id`, so the span tree becomes the run tree.
+///
+/// Requests are sent in order by a background task on the tokio runtime that
+/// is current when the first span opens. Keep a clone of the layer to `flush`
+/// pending requests before shutting down.
+///
+/// Spans and events of the layer's own HTTP stack (see `IGNORED_TARGETS`), and
+/// any opened while the background task sends a request, are not recorded:
+/// otherwise each request would trace itself and cause another one.
+#[derive(Clone)]
+pub struct LangSmithTracingLayer {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: Option<Arc<LangSmithClient>>,
+    run_type: RunType,
+    sender: Mutex<Option<mpsc::UnboundedSender<Op>>>,
+}
+
+enum Op {
+    Post(Tracer),
+    Patch(Tracer),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Targets whose spans and events are never recorded, since sending runs
+/// creates them
+const IGNORED_TARGETS: &[&str] = &["hyper", "h2", "reqwest", "langsmith_rust"];
+
+tokio::task_local! {
+    /// Set while the background task sends a request
+    static SENDING: ();
+}
+
+/// Whether a span or event must not be recorded because it comes from sending
+/// runs rather than from the application
+fn is_own(target: &str) -> bool {
+    let ignored = IGNORED_TARGETS.iter().any(|ignored| {
+        target
+            .strip_prefix(ignored)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    });
+    ignored || SENDING.try_with(|_| ()).is_ok()
+}
+
+/// Per-span state kept in the span extensions
+struct SpanRun {
+    tracer: Tracer,
+    outputs: Map<String, Value>,
+}
+
+impl LangSmithTracingLayer {
+    pub fn new() -> Self {
+        Self::build(None, RunType::Chain)
+    }
+
+    /// Sends runs with `client` instead of one built from the global config
+    pub fn with_client(self, client: Arc<LangSmithClient>) -> Self {
+        Self::build(Some(client), self.inner.run_type.clone())
+    }
+
+    /// Run type used for every span (default `Chain`)
+    pub fn with_run_type(self, run_type: RunType) -> Self {
+        Self::build(self.inner.client.clone(), run_type)
+    }
+
+    fn build(client: Option<Arc<LangSmithClient>>, run_type: RunType) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client,
+                run_type,
+                sender: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Whether spans are recorded: decided by the injected client's config when
+    /// there is one, otherwise by the global config
+    fn tracing_enabled(&self) -> bool {
+        match &self.inner.client {
+            Some(client) => client.config().tracing_enabled,
+            None => Config::is_tracing_enabled(),
+        }
+    }
+
+    /// Waits until every run recorded so far has been sent
+    pub async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.send(Op::Flush(done)) {
+            let _ = wait.await;
+        }
+    }
+
+    /// Queues `op` on the background task, starting it if needed. Returns
+    /// false when there is no tokio runtime to run it on.
+    fn send(&self, op: Op) -> bool {
+        let mut sender = self.inner.sender.lock().unwrap();
+        let op = match sender.as_ref() {
+            Some(tx) => match tx.send(op) {
+                Ok(()) => return true,
+                // The task went away with its runtime; start a new one
+                Err(mpsc::error::SendError(op)) => op,
+            },
+            None => op,
+        };
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            report_trace_error(
+                Some("subscriber"),
+                &LangSmithError::Other("no tokio runtime to send span runs".to_string()),
+            );
+            return false;
+        };
+        let (tx, rx) = mpsc::unbounded_channel();
+        handle.spawn(process(rx));
+        let sent = tx.send(op).is_ok();
+        *sender = Some(tx);
+        sent
+    }
+}
+
+impl Default for LangSmithTracingLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LangSmithTracingLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LangSmithTracingLayer")
+            .field("run_type", &self.inner.run_type)
+            .finish_non_exhaustive()
+    }
+}
+
+async fn process(mut rx: mpsc::UnboundedReceiver<Op>) {
+    while let Some(op) = rx.recv().await {
+        match op {
+            Op::Post(mut tracer) => {
+                let _ = SENDING.scope((), tracer.post()).await;
+            }
+            Op::Patch(tracer) => {
+                let _ = SENDING.scope((), tracer.patch()).await;
+            }
+            Op::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for LangSmithTracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !self.tracing_enabled() || is_own(attrs.metadata().target()) {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut inputs = FieldVisitor::default();
+        attrs.record(&mut inputs);
+
+        let metadata = attrs.metadata();
+        let mut tracer = Tracer::new(
+            metadata.name(),
+            self.inner.run_type.clone(),
+            Value::Object(inputs.0),
+        )
+        .with_metadata("target", json!(metadata.target()))
+        .with_metadata("level", json!(metadata.level().as_str()));
+        if let Some(client) = &self.inner.client {
+            tracer = tracer.with_client(Arc::clone(client));
+        }
+
+        let parent = span
+            .parent()
+            .and_then(|parent| parent.extensions().get::<TraceContext>().cloned());
+        if let Some(parent) = parent {
+            tracer = tracer.with_parent_context(&parent);
+        }
+
+        let mut outgoing = tracer.clone();
+        // Initializes the root ids so the context below matches the posted run
+        outgoing.outgoing_run();
+        let context = outgoing.child_context();
+        self.send(Op::Post(outgoing.clone()));
+
+        let mut extensions = span.extensions_mut();
+        extensions.insert(context);
+        extensions.insert(SpanRun {
+            tracer: outgoing,
+            outputs: Map::new(),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(run) = extensions.get_mut::<SpanRun>() {
+            let mut outputs = FieldVisitor::default();
+            values.record(&mut outputs);
+            run.outputs.extend(outputs.0);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::ERROR || is_own(event.metadata().target()) {
+            return;
+        }
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(run) = extensions.get_mut::<SpanRun>() {
+            let mut fields = FieldVisitor::default();
+            event.record(&mut fields);
+            let error = match fields.0.remove("message") {
+                Some(Value::String(message)) => message,
+                _ => Value::Object(fields.0).to_string(),
+            };
+            run.tracer.set_error(&error);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(mut run) = span.extensions_mut().remove::<SpanRun>() else {
+            return;
+        };
+        run.tracer.end(Value::Object(run.outputs));
+        self.send(Op::Patch(run.tracer));
+    }
+}
+
+/// Collects span and event fields into a JSON object
+#[derive(Default)]
+struct FieldVisitor(Map<String, Value>);
+
+impl Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+}
@@ -0,0 +1,7 @@
+//! `tracing-subscriber` layer that records `tracing` spans as LangSmith runs.
+//!
+//! Available with the `subscriber` feature.
+
+pub mod layer;
+
+pub use layer::LangSmithTracingLayer;
@@ -0,0 +1,5 @@
+//! Helpers for asserting on traced runs in downstream tests.
+
+pub mod run_tree;
+
+pub use run_tree::{assert_run_tree, assert_run_tree_with, run_tree_shape, TreeCompareOptions};
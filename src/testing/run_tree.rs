@@ -0,0 +1,82 @@
+use crate::models::run::Run;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// What `assert_run_tree_with` compares besides names, run types and hierarchy
+#[derive(Debug, Clone, Default)]
+pub struct TreeCompareOptions {
+    pub compare_inputs: bool,
+    pub compare_outputs: bool,
+}
+
+impl TreeCompareOptions {
+    /// Compare inputs and outputs too
+    pub fn with_io() -> Self {
+        Self {
+            compare_inputs: true,
+            compare_outputs: true,
+        }
+    }
+}
+
+/// Builds the structure of a flat list of runs as nested JSON, ignoring ids,
+/// timestamps and dotted orders.
+///
+/// Runs are linked through `parent_run_id`; runs whose parent isn't in the list
+/// are treated as roots. Siblings keep the order they appear in `runs`.
+pub fn run_tree_shape(runs: &[Run], options: &TreeCompareOptions) -> Value {
+    let roots = runs
+        .iter()
+        .filter(|run| match run.parent_run_id {
+            Some(parent) => !runs.iter().any(|r| r.id == parent),
+            None => true,
+        })
+        .map(|run| node_shape(run, runs, options))
+        .collect();
+    Value::Array(roots)
+}
+
+fn node_shape(run: &Run, runs: &[Run], options: &TreeCompareOptions) -> Value {
+    let children: Vec<Value> = children_of(run.id, runs)
+        .map(|child| node_shape(child, runs, options))
+        .collect();
+
+    let mut shape = json!({
+        "name": run.name,
+        "run_type": run.run_type,
+        "children": children,
+    });
+    if options.compare_inputs {
+        shape["inputs"] = run.inputs.clone();
+    }
+    if options.compare_outputs {
+        shape["outputs"] = run.outputs.clone().unwrap_or(Value::Null);
+    }
+    shape
+}
+
+fn children_of(id: Uuid, runs: &[Run]) -> impl Iterator<Item = &Run> {
+    runs.iter().filter(move |run| run.parent_run_id == Some(id))
+}
+
+/// Asserts two run trees have the same names, run types and hierarchy.
+///
+/// # Panics
+///
+/// Panics with both shapes pretty-printed when the trees differ.
+pub fn assert_run_tree(actual: &[Run], expected: &[Run]) {
+    assert_run_tree_with(actual, expected, &TreeCompareOptions::default());
+}
+
+/// Like `assert_run_tree`, optionally comparing inputs and outputs.
+pub fn assert_run_tree_with(actual: &[Run], expected: &[Run], options: &TreeCompareOptions) {
+    let actual_shape = run_tree_shape(actual, options);
+    let expected_shape = run_tree_shape(expected, options);
+    if actual_shape != expected_shape {
+        panic!(
+            "run trees differ\nactual:\n{}\nexpected:\n{}",
+            serde_json::to_string_pretty(&actual_shape).unwrap_or_default(),
+            serde_json::to_string_pretty(&expected_shape).unwrap_or_default(),
+        );
+    }
+}
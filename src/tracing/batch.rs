@@ -0,0 +1,127 @@
+use crate::client::LangSmithClient;
+use crate::config::Config;
+use crate::error::{report_trace_error, LangSmithError, Result};
+use crate::models::run::{Run, RunType};
+use crate::tracing::tracer::Tracer;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+/// Collects finished runs and sends them in a single `POST /runs/batch`.
+///
+/// Used with `trace_node_batched` so a tight loop of traced nodes costs one
+/// request instead of a post and a patch per node. Call `flush` when done;
+/// runs still pending when the scope is dropped are flushed by a best-effort
+/// task on the current tokio runtime.
+pub struct BatchScope {
+    client: Option<Arc<LangSmithClient>>,
+    enabled: bool,
+    runs: Mutex<Vec<Run>>,
+}
+
+impl BatchScope {
+    pub fn new() -> Self {
+        Self {
+            client: None,
+            enabled: Config::is_tracing_enabled(),
+            runs: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_client(mut self, client: Arc<LangSmithClient>) -> Self {
+        self.enabled = client.config().tracing_enabled;
+        self.client = Some(client);
+        self
+    }
+
+    /// Whether runs are recorded at all; false when tracing is disabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Creates a tracer that reports through this batch's client
+    pub fn tracer(&self, name: &str, run_type: RunType, inputs: Value) -> Tracer {
+        self.attach(Tracer::new(name, run_type, inputs))
+    }
+
+    /// Makes `tracer` report through this batch's client
+    pub(crate) fn attach(&self, tracer: Tracer) -> Tracer {
+        match &self.client {
+            Some(client) => tracer.with_client(Arc::clone(client)),
+            None => tracer,
+        }
+    }
+
+    /// Queues the tracer's run (usually already ended) for the next flush
+    pub fn add(&self, tracer: &mut Tracer) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(run) = tracer.outgoing_run() {
+            self.runs.lock().unwrap().push(run);
+        }
+    }
+
+    /// Number of runs waiting to be flushed
+    pub fn len(&self) -> usize {
+        self.runs.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends all pending runs in one request. Delivery failures are reported
+    /// like other tracing errors rather than returned.
+    pub async fn flush(&self) -> Result<()> {
+        let runs = std::mem::take(&mut *self.runs.lock().unwrap());
+        send_batch(self.client.clone(), runs).await
+    }
+}
+
+impl Default for BatchScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for BatchScope {
+    fn drop(&mut self) {
+        let runs = std::mem::take(self.runs.get_mut().unwrap());
+        if runs.is_empty() {
+            return;
+        }
+
+        let client = self.client.take();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = send_batch(client, runs).await {
+                        report_trace_error(Some("batch"), &e);
+                    }
+                });
+            }
+            Err(_) => report_trace_error(
+                Some("batch"),
+                &LangSmithError::Other("no tokio runtime to flush batched runs".to_string()),
+            ),
+        }
+    }
+}
+
+async fn send_batch(client: Option<Arc<LangSmithClient>>, mut runs: Vec<Run>) -> Result<()> {
+    if runs.is_empty() {
+        return Ok(());
+    }
+    // Runs are queued as they end, children before their parents; a parent's
+    // dotted_order is a prefix of its children's, so sorting puts it first
+    runs.sort_by(|a, b| a.dotted_order.cmp(&b.dotted_order));
+
+    let client = match client {
+        Some(client) => client,
+        None => Arc::new(LangSmithClient::new()?),
+    };
+    if let Err(e) = client.batch_ingest(&runs, &[]).await {
+        report_trace_error(Some("batch"), &e);
+    }
+    Ok(())
+}
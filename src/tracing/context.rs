@@ -1,5 +1,43 @@
+use crate::error::{LangSmithError, Result};
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::future::Future;
 use uuid::Uuid;
 
+/// Header carrying the trace id between services
+pub const TRACE_ID_HEADER: &str = "langsmith-trace-id";
+/// Header carrying the id of the run new runs should be attached under
+pub const PARENT_RUN_ID_HEADER: &str = "langsmith-parent-run-id";
+/// Header carrying the parent run's dotted_order
+pub const DOTTED_ORDER_HEADER: &str = "langsmith-dotted-order";
+/// Header set to `false` when the trace was not sampled
+pub const SAMPLED_HEADER: &str = "langsmith-sampled";
+
+tokio::task_local! {
+    static CURRENT_TRACE: TraceContext;
+}
+
+/// Runs `fut` with `ctx` (from `Tracer::child_context`) as the current trace
+/// context: runs started inside it by `trace_node` and the other decorator
+/// helpers become children of the run `ctx` describes, without passing the
+/// context down explicitly.
+///
+/// The context is task-local, so tasks spawned from `fut` don't inherit it;
+/// wrap them in `with_current_trace` as well.
+pub async fn with_current_trace<F: Future>(ctx: TraceContext, fut: F) -> F::Output {
+    CURRENT_TRACE.scope(ctx, fut).await
+}
+
+/// The context set by the enclosing `with_current_trace`, if any
+pub fn current_trace() -> Option<TraceContext> {
+    CURRENT_TRACE.try_with(TraceContext::clone).ok()
+}
+
+/// Synchronous counterpart of `with_current_trace`
+pub(crate) fn with_current_trace_sync<R>(ctx: TraceContext, f: impl FnOnce() -> R) -> R {
+    CURRENT_TRACE.sync_scope(ctx, f)
+}
+
 /// Context for trace propagation
 #[derive(Debug, Clone)]
 pub struct TraceContext {
@@ -8,6 +46,11 @@ pub struct TraceContext {
     pub dotted_order: Option<String>,
     pub thread_id: Option<String>,
     pub session_name: Option<String>,
+    /// Project UUID, sent as the runs' `session_id`
+    pub session_id: Option<Uuid>,
+    /// Whether the trace is recorded; runs created from an unsampled context
+    /// are never sent
+    pub sampled: bool,
 }
 
 impl TraceContext {
@@ -18,6 +61,8 @@ impl TraceContext {
             dotted_order: None,
             thread_id: None,
             session_name: None,
+            session_id: None,
+            sampled: true,
         }
     }
 
@@ -40,5 +85,109 @@ impl TraceContext {
         self.session_name = Some(session_name);
         self
     }
-}
 
+    pub fn with_session_id(mut self, session_id: Uuid) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    /// Marks whether the trace is recorded; runs created from an unsampled
+    /// context are never sent.
+    pub fn with_sampled(mut self, sampled: bool) -> Self {
+        self.sampled = sampled;
+        self
+    }
+
+    /// Whether the trace this context belongs to is being recorded
+    pub fn is_sampled(&self) -> bool {
+        self.sampled
+    }
+
+    /// Headers that let a downstream service continue this trace with
+    /// `from_headers`
+    pub fn to_headers(&self) -> HashMap<String, String> {
+        let mut headers = HashMap::new();
+        headers.insert(TRACE_ID_HEADER.to_string(), self.trace_id.to_string());
+        if let Some(parent_run_id) = self.parent_run_id {
+            headers.insert(PARENT_RUN_ID_HEADER.to_string(), parent_run_id.to_string());
+        }
+        if let Some(dotted_order) = &self.dotted_order {
+            headers.insert(DOTTED_ORDER_HEADER.to_string(), dotted_order.clone());
+        }
+        if !self.sampled {
+            headers.insert(SAMPLED_HEADER.to_string(), "false".to_string());
+        }
+        headers
+    }
+
+    /// Reads a context sent with `to_headers`. Returns `None` when there is no
+    /// valid trace id; malformed optional headers are ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        Self::from_header_values(|name| headers.get(name).and_then(|v| v.to_str().ok()))
+    }
+
+    /// `from_headers` over any header representation
+    pub(crate) fn from_header_values<'a>(header: impl Fn(&str) -> Option<&'a str>) -> Option<Self> {
+        let trace_id = Uuid::parse_str(header(TRACE_ID_HEADER)?).ok()?;
+        let mut context = TraceContext::new(trace_id);
+        if let Some(parent) = header(PARENT_RUN_ID_HEADER).and_then(|v| Uuid::parse_str(v).ok()) {
+            context = context.with_parent(parent);
+        }
+        if let Some(dotted_order) = header(DOTTED_ORDER_HEADER) {
+            context = context.with_dotted_order(dotted_order.to_string());
+        }
+        if header(SAMPLED_HEADER).is_some_and(|v| v.eq_ignore_ascii_case("false")) {
+            context = context.with_sampled(false);
+        }
+        Some(context)
+    }
+
+    /// Formats the context as a W3C `traceparent` header
+    /// (`00-{trace_id}-{span_id}-{flags}`). The trace id is the trace's UUID;
+    /// the span id is the low 64 bits of the parent run id (the trace's root
+    /// run when there is no parent), matching `otel::span_id`.
+    pub fn to_traceparent(&self) -> String {
+        let parent = self.parent_run_id.unwrap_or(self.trace_id);
+        let span_id = &parent.as_bytes()[8..];
+        let span_hex: String = span_id.iter().map(|b| format!("{:02x}", b)).collect();
+        let flags = if self.sampled { "01" } else { "00" };
+        format!("00-{}-{}-{}", self.trace_id.simple(), span_hex, flags)
+    }
+
+    /// Continues a trace from a W3C `traceparent` header. The span id becomes
+    /// the low 64 bits of `parent_run_id` (the high bits are zero), and the
+    /// sampled flag carries over. The context has no `dotted_order`, so
+    /// `with_parent_context` starts a root run in the trace that links the span
+    /// as a remote parent instead of nesting under it.
+    pub fn from_traceparent(traceparent: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            LangSmithError::InvalidTraceparent(format!("'{}': {}", traceparent, reason))
+        };
+
+        let fields: Vec<&str> = traceparent.trim().split('-').collect();
+        let [version, trace_id, span_id, flags, ..] = fields[..] else {
+            return Err(invalid("expected version-trace_id-span_id-flags"));
+        };
+        let hex = |field: &str, len: usize| {
+            field.len() == len
+                && field.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        };
+        if !hex(version, 2) || version == "ff" || (version == "00" && fields.len() != 4) {
+            return Err(invalid("unsupported version"));
+        }
+        if !hex(trace_id, 32) || !hex(span_id, 16) || !hex(flags, 2) {
+            return Err(invalid("fields must be lowercase hex of the right length"));
+        }
+
+        let trace_id = u128::from_str_radix(trace_id, 16).map_err(|e| invalid(&e.to_string()))?;
+        let span_id = u64::from_str_radix(span_id, 16).map_err(|e| invalid(&e.to_string()))?;
+        let flags = u8::from_str_radix(flags, 16).map_err(|e| invalid(&e.to_string()))?;
+        if trace_id == 0 || span_id == 0 {
+            return Err(invalid("trace_id and span_id must not be all zeros"));
+        }
+
+        Ok(TraceContext::new(Uuid::from_u128(trace_id))
+            .with_parent(Uuid::from_u128(span_id as u128))
+            .with_sampled(flags & 0x01 == 0x01))
+    }
+}
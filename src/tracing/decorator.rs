@@ -1,9 +1,85 @@
-use crate::error::Result;
-use crate::models::run::RunType;
+use crate::error::{report_trace_error, LangSmithError, Result};
+use crate::models::metrics::Metrics;
+use crate::models::run::{RunErrorKind, RunType};
+use crate::strategies::serialization_strategy::{
+    DefaultSerializationStrategy, SerializationStrategy,
+};
+use crate::tracing::batch::BatchScope;
+use crate::tracing::context::{current_trace, with_current_trace, with_current_trace_sync};
 use crate::tracing::tracer::Tracer;
-use crate::utils::serialization::{ensure_inputs_object, ensure_outputs_object};
 use serde::Serialize;
+use futures_util::stream::{self, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
 use std::future::Future;
+use std::sync::Arc;
+
+/// Extra labels applied to the run created by `trace_node_with`/`trace_node_sync_with`
+#[derive(Clone, Default)]
+pub struct TraceOptions {
+    pub tags: Vec<String>,
+    pub metadata: HashMap<String, Value>,
+    /// Project the run is sent to, overriding the configured default
+    pub project: Option<String>,
+    /// How inputs and outputs become the run's objects; defaults to
+    /// `DefaultSerializationStrategy`
+    pub serialization: Option<Arc<dyn SerializationStrategy>>,
+}
+
+impl fmt::Debug for TraceOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceOptions")
+            .field("tags", &self.tags)
+            .field("metadata", &self.metadata)
+            .field("project", &self.project)
+            .field("serialization", &self.serialization.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl TraceOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_metadata(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    pub fn with_serialization(mut self, serialization: Arc<dyn SerializationStrategy>) -> Self {
+        self.serialization = Some(serialization);
+        self
+    }
+
+    fn serialization(&self) -> Arc<dyn SerializationStrategy> {
+        self.serialization
+            .clone()
+            .unwrap_or_else(|| Arc::new(DefaultSerializationStrategy::new()))
+    }
+
+    fn apply(self, mut tracer: Tracer) -> Tracer {
+        tracer = tracer.with_tags(self.tags);
+        if let Some(project) = self.project {
+            tracer = tracer.with_project(project);
+        }
+        for (key, value) in self.metadata {
+            tracer = tracer.with_metadata(key, value);
+        }
+        tracer
+    }
+}
 
 /// Helper function to trace a node execution
 /// 
@@ -19,6 +95,23 @@ pub async fn trace_node<F, Fut, I, O>(
     inputs: I,
     f: F,
 ) -> Result<O>
+where
+    F: FnOnce(I) -> Fut,
+    Fut: Future<Output = Result<O>>,
+    I: Serialize,
+    O: Serialize,
+{
+    trace_node_with(name, run_type, TraceOptions::default(), inputs, f).await
+}
+
+/// Like `trace_node`, with tags and metadata attached to the run
+pub async fn trace_node_with<F, Fut, I, O>(
+    name: &str,
+    run_type: RunType,
+    options: TraceOptions,
+    inputs: I,
+    f: F,
+) -> Result<O>
 where
     F: FnOnce(I) -> Fut,
     Fut: Future<Output = Result<O>>,
@@ -31,45 +124,314 @@ where
     }
 
     // 1. Serialize inputs - ensure it's always an object
-    let inputs_value = ensure_inputs_object(&inputs)
-        .map_err(|e| crate::error::LangSmithError::Serialization(e))?;
+    let serialization = options.serialization();
+    let inputs_value = inputs_value(serialization.as_ref(), &inputs)?;
 
     // 2. Create tracer
-    let mut tracer = Tracer::new(name, run_type, inputs_value);
+    let tracer = options.apply(new_tracer(name, run_type, inputs_value));
+
+    run_traced(tracer, serialization.as_ref(), || f(inputs)).await
+}
+
+/// Creates the tracer for a decorated call, as a child of the current trace
+/// (see `with_current_trace`) when there is one
+fn new_tracer(name: &str, run_type: RunType, inputs: Value) -> Tracer {
+    let tracer = Tracer::new(name, run_type, inputs);
+    match current_trace() {
+        Some(ctx) => tracer.with_parent_context(&ctx),
+        None => tracer,
+    }
+}
+
+/// Records a failed call on the run, with the error's type (including the
+/// `LangSmithError` variant) in `extra.error_type` and its category in
+/// `extra.error_kind`
+fn record_error(tracer: &mut Tracer, error: &LangSmithError) {
+    let error_type = format!(
+        "{}::{}",
+        std::any::type_name::<LangSmithError>(),
+        error.variant_name()
+    );
+    tracer.set_error_details(&error_type, &error.to_string(), None);
+    tracer.set_error_kind(RunErrorKind::from(error), &error.to_string());
+}
+
+/// Serializes the call's inputs into the run's inputs object
+fn inputs_value<I: Serialize>(
+    serialization: &dyn SerializationStrategy,
+    inputs: &I,
+) -> Result<Value> {
+    let value = serde_json::to_value(inputs).map_err(LangSmithError::Serialization)?;
+    Ok(serialization.inputs_object(value))
+}
+
+/// Serializes a successful result for the run's outputs. A result that can't be
+/// serialized is reported and recorded as empty outputs: a tracing failure must
+/// never turn the caller's `Ok` into an error.
+fn outputs_value<O: Serialize>(serialization: &dyn SerializationStrategy, output: &O) -> Value {
+    match serde_json::to_value(output) {
+        Ok(value) => serialization.outputs_object(value),
+        Err(e) => {
+            report_trace_error(Some("outputs"), &LangSmithError::Serialization(e));
+            json!({})
+        }
+    }
+}
 
+/// Traces an LLM call whose function returns its output together with the
+/// token and cost metrics reported by the provider. The run records both; the
+/// caller gets the output back.
+pub async fn trace_llm_node<F, Fut, I, O>(name: &str, inputs: I, f: F) -> Result<O>
+where
+    F: FnOnce(I) -> Fut,
+    Fut: Future<Output = Result<(O, Metrics)>>,
+    I: Serialize,
+    O: Serialize,
+{
+    if !crate::config::Config::is_tracing_enabled() {
+        return f(inputs).await.map(|(output, _)| output);
+    }
+
+    let serialization = DefaultSerializationStrategy::new();
+    let tracer = new_tracer(name, RunType::Llm, inputs_value(&serialization, &inputs)?);
+
+    run_traced_with_metrics(tracer, &serialization, || async move {
+        f(inputs).await.map(|(output, metrics)| (output, Some(metrics)))
+    })
+    .await
+}
+
+/// Posts the tracer's run, runs `f` and patches the run with its outcome.
+async fn run_traced<F, Fut, O>(
+    tracer: Tracer,
+    serialization: &dyn SerializationStrategy,
+    f: F,
+) -> Result<O>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<O>>,
+    O: Serialize,
+{
+    run_traced_with_metrics(tracer, serialization, || async move {
+        f().await.map(|output| (output, None))
+    })
+    .await
+}
+
+/// Like `run_traced`, also recording any metrics returned alongside the output.
+async fn run_traced_with_metrics<F, Fut, O>(
+    mut tracer: Tracer,
+    serialization: &dyn SerializationStrategy,
+    f: F,
+) -> Result<O>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<(O, Option<Metrics>)>>,
+    O: Serialize,
+{
     // 3. POST /runs - save initial run (start_time, inputs)
     if let Err(e) = tracer.post().await {
         // Log error but don't fail the node execution
-        eprintln!("LangSmith tracing error (post): {}", e);
+        report_trace_error(Some("post"), &e);
     }
 
-    // 4. Execute the function
-    match f(inputs).await {
-        Ok(output) => {
+    // 4. Execute the function, as the parent of runs traced inside it
+    match with_current_trace(tracer.child_context(), f()).await {
+        Ok((output, metrics)) => {
+            if let Some(metrics) = metrics {
+                tracer.set_metrics(metrics);
+            }
+
             // 5. Serialize outputs - ensure it's always an object
-            let output_value = ensure_outputs_object(&output)
-                .map_err(|e| crate::error::LangSmithError::Serialization(e))?;
+            let output_value = outputs_value(serialization, &output);
 
             // 6. Mark run as finished and PATCH /runs/{run_id} - save outputs and end_time
             tracer.end(output_value);
             if let Err(e) = tracer.patch().await {
                 // Log error but don't fail the node execution
-                eprintln!("LangSmith tracing error (patch): {}", e);
+                report_trace_error(Some("patch"), &e);
             }
 
             Ok(output)
         }
         Err(e) => {
             // In case of error, mark run with error
-            tracer.set_error(&e.to_string());
+            record_error(&mut tracer, &e);
             if let Err(trace_err) = tracer.patch().await {
-                eprintln!("LangSmith tracing error (patch): {}", trace_err);
+                report_trace_error(Some("patch"), &trace_err);
             }
             Err(e)
         }
     }
 }
 
+/// Traces a fan-out over `items`: one parent run named `name`, with a child run
+/// per item whose inputs carry the item and its index. Items are processed
+/// sequentially; see `trace_map_concurrent` to run several at once.
+///
+/// Returns the results in item order, or the first error once every item has run.
+pub async fn trace_map<T, F, Fut, O>(name: &str, items: Vec<T>, f: F) -> Result<Vec<O>>
+where
+    T: Serialize,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<O>>,
+    O: Serialize,
+{
+    trace_map_concurrent(name, items, 1, f).await
+}
+
+/// Like `trace_map`, with up to `concurrency` items in flight at a time
+pub async fn trace_map_concurrent<T, F, Fut, O>(
+    name: &str,
+    items: Vec<T>,
+    concurrency: usize,
+    f: F,
+) -> Result<Vec<O>>
+where
+    T: Serialize,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<O>>,
+    O: Serialize,
+{
+    let concurrency = concurrency.max(1);
+
+    if !crate::config::Config::is_tracing_enabled() {
+        return stream::iter(items.into_iter().map(&f))
+            .buffered(concurrency)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect();
+    }
+
+    let mut parent = new_tracer(name, RunType::Chain, json!({"count": items.len()}));
+    if let Err(e) = parent.post().await {
+        report_trace_error(Some("post"), &e);
+    }
+
+    let children = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let inputs = json!({"index": index, "item": serde_json::to_value(&item)?});
+            let child = parent.create_child(format!("{}[{}]", name, index), RunType::Chain, inputs);
+            Ok((child, item))
+        })
+        .collect::<std::result::Result<Vec<_>, serde_json::Error>>();
+    let children = match children {
+        Ok(children) => children,
+        Err(e) => {
+            let e = LangSmithError::Serialization(e);
+            record_error(&mut parent, &e);
+            parent.end(json!({}));
+            patch_parent(&mut parent).await;
+            return Err(e);
+        }
+    };
+
+    let serialization = DefaultSerializationStrategy::new();
+    let results: Vec<Result<O>> = stream::iter(
+        children
+            .into_iter()
+            .map(|(child, item)| run_traced(child, &serialization, || f(item))),
+    )
+    .buffered(concurrency)
+    .collect()
+    .await;
+
+    let failure = results.iter().find_map(|result| result.as_ref().err());
+    match failure {
+        Some(e) => {
+            record_error(&mut parent, e);
+            parent.end(json!({}));
+        }
+        None => {
+            let outputs = results
+                .iter()
+                .filter_map(|result| result.as_ref().ok())
+                .map(serde_json::to_value)
+                .collect::<std::result::Result<Vec<_>, _>>();
+            match outputs {
+                Ok(outputs) => parent.end(json!({"results": outputs})),
+                Err(e) => {
+                    let e = LangSmithError::Serialization(e);
+                    report_trace_error(Some("outputs"), &e);
+                    record_error(&mut parent, &e);
+                    parent.end(json!({}));
+                }
+            }
+        }
+    }
+    patch_parent(&mut parent).await;
+
+    results.into_iter().collect()
+}
+
+/// Sends the ended parent run of `trace_map`, reporting a failed patch
+async fn patch_parent(parent: &mut Tracer) {
+    if let Err(e) = parent.patch().await {
+        report_trace_error(Some("patch"), &e);
+    }
+}
+
+/// Like `trace_node`, but instead of posting and patching the run it is queued
+/// on `batch` once the function completes, and sent when the batch is flushed.
+/// The run nests under the current trace, and calls traced inside `f` nest
+/// under it.
+pub async fn trace_node_batched<F, Fut, I, O>(
+    batch: &BatchScope,
+    name: &str,
+    run_type: RunType,
+    inputs: I,
+    f: F,
+) -> Result<O>
+where
+    F: FnOnce(I) -> Fut,
+    Fut: Future<Output = Result<O>>,
+    I: Serialize,
+    O: Serialize,
+{
+    if !batch.is_enabled() {
+        return f(inputs).await;
+    }
+
+    let serialization = DefaultSerializationStrategy::new();
+    let mut tracer =
+        batch.attach(new_tracer(name, run_type, inputs_value(&serialization, &inputs)?));
+
+    match with_current_trace(tracer.child_context(), f(inputs)).await {
+        Ok(output) => {
+            tracer.end(outputs_value(&serialization, &output));
+            batch.add(&mut tracer);
+            Ok(output)
+        }
+        Err(e) => {
+            record_error(&mut tracer, &e);
+            tracer.end(json!({}));
+            batch.add(&mut tracer);
+            Err(e)
+        }
+    }
+}
+
+/// Traces an already-constructed future.
+///
+/// Unlike `trace_node`, the computation doesn't take the inputs: they are only
+/// recorded on the run, and the future's output is captured on completion.
+pub async fn trace_future<Fut, I, O>(
+    name: &str,
+    run_type: RunType,
+    inputs: I,
+    fut: Fut,
+) -> Result<O>
+where
+    Fut: Future<Output = Result<O>>,
+    I: Serialize,
+    O: Serialize,
+{
+    trace_node(name, run_type, inputs, |_| fut).await
+}
+
 /// Synchronous version of trace_node
 pub fn trace_node_sync<F, I, O>(
     name: &str,
@@ -77,6 +439,22 @@ pub fn trace_node_sync<F, I, O>(
     inputs: I,
     f: F,
 ) -> Result<O>
+where
+    F: FnOnce(I) -> Result<O>,
+    I: Serialize,
+    O: Serialize,
+{
+    trace_node_sync_with(name, run_type, TraceOptions::default(), inputs, f)
+}
+
+/// Synchronous version of trace_node_with
+pub fn trace_node_sync_with<F, I, O>(
+    name: &str,
+    run_type: RunType,
+    options: TraceOptions,
+    inputs: I,
+    f: F,
+) -> Result<O>
 where
     F: FnOnce(I) -> Result<O>,
     I: Serialize,
@@ -88,39 +466,38 @@ where
     }
 
     // 1. Serialize inputs - ensure it's always an object
-    let inputs_value = ensure_inputs_object(&inputs)
-        .map_err(|e| crate::error::LangSmithError::Serialization(e))?;
+    let serialization = options.serialization();
+    let inputs_value = inputs_value(serialization.as_ref(), &inputs)?;
 
     // 2. Create tracer
-    let mut tracer = Tracer::new(name, run_type, inputs_value);
+    let mut tracer = options.apply(new_tracer(name, run_type, inputs_value));
 
     // 3. POST /runs - save initial run (start_time, inputs)
     // For sync version, we need to use tokio runtime
     let rt = tokio::runtime::Runtime::new().unwrap();
     if let Err(e) = rt.block_on(tracer.post()) {
-        eprintln!("LangSmith tracing error (post): {}", e);
+        report_trace_error(Some("post"), &e);
     }
 
-    // 4. Execute the function
-    match f(inputs) {
+    // 4. Execute the function, as the parent of runs traced inside it
+    match with_current_trace_sync(tracer.child_context(), || f(inputs)) {
         Ok(output) => {
             // 5. Serialize outputs - ensure it's always an object
-            let output_value = ensure_outputs_object(&output)
-                .map_err(|e| crate::error::LangSmithError::Serialization(e))?;
+            let output_value = outputs_value(serialization.as_ref(), &output);
 
             // 6. Mark run as finished and PATCH /runs/{run_id} - save outputs and end_time
             tracer.end(output_value);
             if let Err(e) = rt.block_on(tracer.patch()) {
-                eprintln!("LangSmith tracing error (patch): {}", e);
+                report_trace_error(Some("patch"), &e);
             }
 
             Ok(output)
         }
         Err(e) => {
             // In case of error, mark run with error
-            tracer.set_error(&e.to_string());
+            record_error(&mut tracer, &e);
             if let Err(trace_err) = rt.block_on(tracer.patch()) {
-                eprintln!("LangSmith tracing error (patch): {}", trace_err);
+                report_trace_error(Some("patch"), &trace_err);
             }
             Err(e)
         }
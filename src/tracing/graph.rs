@@ -1,8 +1,12 @@
 use crate::error::Result;
 use crate::models::run::RunType;
+use crate::tracing::batch::BatchScope;
+use crate::tracing::run_tree::RunTree;
 use crate::tracing::scope::RunScope;
 use crate::tracing::tracer::Tracer;
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Opinionated tracing helpers to build a Graph-style hierarchy in LangSmith:
 /// - Root run named `Graph` (RunType::Chain)
@@ -13,17 +17,53 @@ use serde_json::Value;
 /// provide LangSmith-compatible payloads such as `{ \"messages\": [...] }`.
 pub struct GraphTrace {
     root: RunScope,
+    /// When set, repeated node names get an iteration suffix (`chatbot #2`)
+    number_iterations: bool,
+    iterations: Mutex<HashMap<String, usize>>,
+    /// Set for buffered graphs: runs are queued here and sent in one request
+    batch: Option<Arc<BatchScope>>,
 }
 
 impl GraphTrace {
     /// Starts the root Graph run (name: `Graph`, type: Chain) and POSTs it.
     pub async fn start_root(inputs: Value, thread_id: Option<String>) -> Result<Self> {
-        let mut root = RunScope::root_value("Graph", RunType::Chain, inputs);
-        if let Some(tid) = thread_id {
-            root = root.with_thread_id(tid);
+        Self::builder().inputs(inputs).thread_id(thread_id).start().await
+    }
+
+    /// Starts a Graph whose runs are buffered instead of sent one by one: each
+    /// run is queued once it ends, and everything is sent in a single
+    /// `POST /runs/batch` by `end_root` (or earlier by `flush`).
+    pub fn buffered(inputs: Value, thread_id: Option<String>) -> Self {
+        Self::builder().inputs(inputs).thread_id(thread_id).buffered()
+    }
+
+    /// Configures the root run, e.g. to name it after the app instead of `Graph`
+    pub fn builder() -> GraphTraceBuilder {
+        GraphTraceBuilder::new()
+    }
+
+    fn new(root: RunScope, batch: Option<Arc<BatchScope>>) -> Self {
+        Self {
+            root,
+            number_iterations: false,
+            iterations: Mutex::new(HashMap::new()),
+            batch,
         }
-        root.post_start().await?;
-        Ok(Self { root })
+    }
+
+    /// Appends an iteration index to repeated node names (`chatbot`, `chatbot #2`, ...),
+    /// keeping the original name in `extra.base_name`. Useful for iterative agents
+    /// where the same node runs many times.
+    pub fn with_numbered_iterations(mut self) -> Self {
+        self.number_iterations = true;
+        self
+    }
+
+    /// Records every run of the graph in `tree` as it ends, so the whole trace
+    /// can be exported with `RunTree::to_json` or sent later with `post_all`
+    pub fn with_run_tree(mut self, tree: Arc<RunTree>) -> Self {
+        self.root = self.root.with_run_tree(tree);
+        self
     }
 
     pub fn root_scope(&self) -> &RunScope {
@@ -37,7 +77,27 @@ impl GraphTrace {
     /// Starts a top-level step/node under the root run (POSTs it) and returns the scope.
     /// Use this for nodes like "chatbot", "tools", etc. that may contain nested runs.
     pub async fn start_node_iteration(&self, node_name: &str, inputs: Value) -> Result<RunScope> {
-        let mut step = self.root.child_value(node_name, RunType::Chain, inputs);
+        let mut step = if self.number_iterations {
+            let iteration = {
+                let mut iterations = self.iterations.lock().unwrap();
+                let count = iterations.entry(node_name.to_string()).or_insert(0);
+                *count += 1;
+                *count
+            };
+            let name = if iteration == 1 {
+                node_name.to_string()
+            } else {
+                format!("{} #{}", node_name, iteration)
+            };
+            let mut step = self.root.child_value(&name, RunType::Chain, inputs);
+            step.tracer_mut()
+                .run
+                .extra
+                .insert("base_name".to_string(), json!(node_name));
+            step
+        } else {
+            self.root.child_value(node_name, RunType::Chain, inputs)
+        };
         step.post_start().await?;
         Ok(step)
     }
@@ -86,17 +146,110 @@ impl GraphTrace {
         inputs: Value,
         outputs: Value,
     ) -> Result<()> {
+        let tool = self.start_tool_call(parent_node, tool_name, inputs).await?;
+        tool.end_ok(outputs).await
+    }
+
+    /// Starts a tool run within a parent node (POSTs it) and returns the scope,
+    /// so runs the tool makes itself (e.g. an LLM call) can nest under it.
+    /// End it with `end_ok`/`end_error` once the tool returns.
+    pub async fn start_tool_call(
+        &self,
+        parent_node: &RunScope,
+        tool_name: &str,
+        inputs: Value,
+    ) -> Result<RunScope> {
         // Format tool name as "tool/{name}" to match LangGraph Python convention
         let formatted_name = format!("tool/{}", tool_name);
         let mut tool = parent_node.child_value(&formatted_name, RunType::Tool, inputs);
         tool.post_start().await?;
-        tool.end_ok(outputs).await
+        Ok(tool)
+    }
+
+    /// Sends the runs buffered so far. Does nothing for unbuffered graphs.
+    pub async fn flush(&self) -> Result<()> {
+        match &self.batch {
+            Some(batch) => batch.flush().await,
+            None => Ok(()),
+        }
     }
 
     /// Ends the root run with the provided outputs (PATCH). Consumes self.
+    /// A buffered graph sends all of its queued runs here.
     pub async fn end_root(self, outputs: Value) -> Result<()> {
-        self.root.end_ok(outputs).await
+        self.root.end_ok(outputs).await?;
+        match &self.batch {
+            Some(batch) => batch.flush().await,
+            None => Ok(()),
+        }
     }
 }
 
+/// Builds a `GraphTrace` whose root run isn't the default `Graph` chain, e.g.
+/// `GraphTrace::builder().name("MyPipeline").run_type(RunType::Tool).start()`.
+/// Unset fields keep the `start_root` defaults.
+pub struct GraphTraceBuilder {
+    name: String,
+    run_type: RunType,
+    inputs: Value,
+    thread_id: Option<String>,
+}
+
+impl GraphTraceBuilder {
+    pub fn new() -> Self {
+        Self {
+            name: "Graph".to_string(),
+            run_type: RunType::Chain,
+            inputs: json!({}),
+            thread_id: None,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn run_type(mut self, run_type: RunType) -> Self {
+        self.run_type = run_type;
+        self
+    }
+
+    pub fn inputs(mut self, inputs: Value) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    pub fn thread_id(mut self, thread_id: Option<String>) -> Self {
+        self.thread_id = thread_id;
+        self
+    }
+
+    /// Starts the root run and POSTs it (see `GraphTrace::start_root`)
+    pub async fn start(self) -> Result<GraphTrace> {
+        let mut graph = GraphTrace::new(self.root(), None);
+        graph.root.post_start().await?;
+        Ok(graph)
+    }
 
+    /// Starts a buffered graph (see `GraphTrace::buffered`)
+    pub fn buffered(self) -> GraphTrace {
+        let batch = Arc::new(BatchScope::new());
+        let root = self.root().with_batch(Arc::clone(&batch));
+        GraphTrace::new(root, Some(batch))
+    }
+
+    fn root(self) -> RunScope {
+        let root = RunScope::root_value(&self.name, self.run_type, self.inputs);
+        match self.thread_id {
+            Some(tid) => root.with_thread_id(tid),
+            None => root,
+        }
+    }
+}
+
+impl Default for GraphTraceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
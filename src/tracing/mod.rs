@@ -1,12 +1,25 @@
 pub mod tracer;
+pub mod batch;
 pub mod context;
 pub mod decorator;
 pub mod scope;
 pub mod graph;
+pub mod registry;
+pub mod run_tree;
+pub mod shared;
+pub mod streaming;
 
-pub use tracer::Tracer;
-pub use context::TraceContext;
-pub use decorator::{trace_node, trace_node_sync};
-pub use scope::RunScope;
-pub use graph::GraphTrace;
+pub use tracer::{Tracer, TracerGuard};
+pub use context::{current_trace, with_current_trace, TraceContext};
+pub use batch::BatchScope;
+pub use decorator::{
+    trace_future, trace_llm_node, trace_map, trace_map_concurrent, trace_node,
+    trace_node_batched, trace_node_sync, trace_node_sync_with, trace_node_with, TraceOptions,
+};
+pub use scope::{RunScope, ScopeGuard};
+pub use graph::{GraphTrace, GraphTraceBuilder};
+pub use registry::TraceRegistry;
+pub use run_tree::RunTree;
+pub use shared::SharedTracer;
+pub use streaming::{StreamingTracer, Tokenizer};
 
@@ -0,0 +1,46 @@
+use crate::tracing::context::TraceContext;
+use crate::tracing::graph::GraphTrace;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+static REGISTRY: Lazy<Mutex<HashMap<String, Arc<GraphTrace>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Process-wide registry of in-flight root traces, keyed by an id chosen by the caller
+/// (e.g. a request id).
+///
+/// This lets a web framework middleware start a root `GraphTrace` and the handlers
+/// running on other tasks look it up to attach their own runs.
+pub struct TraceRegistry;
+
+impl TraceRegistry {
+    /// Registers a root trace under `id`, replacing any previous entry.
+    pub fn register(id: impl Into<String>, graph: GraphTrace) -> Arc<GraphTrace> {
+        let graph = Arc::new(graph);
+        REGISTRY
+            .lock()
+            .unwrap()
+            .insert(id.into(), Arc::clone(&graph));
+        graph
+    }
+
+    /// Returns the root trace registered under `id`.
+    pub fn get(id: &str) -> Option<Arc<GraphTrace>> {
+        REGISTRY.lock().unwrap().get(id).map(Arc::clone)
+    }
+
+    /// Returns the context for runs created under the root run registered
+    /// under `id` (see `Tracer::child_context`).
+    pub fn context(id: &str) -> Option<TraceContext> {
+        Self::get(id).map(|graph| graph.root_tracer().child_context())
+    }
+
+    /// Removes the root trace registered under `id`.
+    ///
+    /// Once every other handle is dropped, `Arc::try_unwrap` yields the
+    /// `GraphTrace` back so the root can be ended.
+    pub fn remove(id: &str) -> Option<Arc<GraphTrace>> {
+        REGISTRY.lock().unwrap().remove(id)
+    }
+}
@@ -0,0 +1,114 @@
+use crate::client::LangSmithClient;
+use crate::error::{report_trace_error, Result};
+use crate::export::{to_tree_json, TimeFormat};
+use crate::models::run::Run;
+use crate::tracing::tracer::Tracer;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Collects the finished runs of a trace so the whole tree can be dumped for
+/// offline inspection or sent to LangSmith later.
+///
+/// Attach it with `GraphTrace::with_run_tree` (or `RunScope::with_run_tree`):
+/// each run of the graph is recorded as it ends, redacted the way it is sent,
+/// in addition to being traced as usual. Runs of disabled or unsampled traces
+/// are not recorded.
+pub struct RunTree {
+    runs: Mutex<HashMap<Uuid, RecordedRun>>,
+}
+
+struct RecordedRun {
+    run: Run,
+    /// Whether the run was already sent to LangSmith (or queued on a batch),
+    /// so `post_all` leaves it out
+    sent: bool,
+}
+
+impl RunTree {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the tracer's run, replacing an earlier copy of the same run
+    pub fn add(&self, tracer: &mut Tracer) {
+        let sent = tracer.is_posted();
+        self.record(tracer, sent);
+    }
+
+    /// Records the tracer's run, noting whether it was delivered some other way
+    pub(crate) fn record(&self, tracer: &mut Tracer, sent: bool) {
+        let Some(run) = tracer.outgoing_run() else {
+            return;
+        };
+        self.runs
+            .lock()
+            .unwrap()
+            .insert(run.id, RecordedRun { run, sent });
+    }
+
+    /// The recorded runs, parents before their children and siblings in the
+    /// order they started
+    pub fn runs(&self) -> Vec<Run> {
+        self.runs_where(|_| true)
+    }
+
+    fn runs_where(&self, keep: impl Fn(&RecordedRun) -> bool) -> Vec<Run> {
+        let mut runs: Vec<Run> = self
+            .runs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|recorded| keep(recorded))
+            .map(|recorded| recorded.run.clone())
+            .collect();
+        runs.sort_by(|a, b| a.dotted_order.cmp(&b.dotted_order));
+        runs
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The recorded runs nested under their parents in `child_runs` arrays:
+    /// an array of root runs, normally just one (see `export::to_tree_json`)
+    pub fn to_json(&self) -> Value {
+        to_tree_json(&self.runs(), TimeFormat::Rfc3339).unwrap_or_else(|e| {
+            report_trace_error(Some("run tree"), &e);
+            Value::Array(Vec::new())
+        })
+    }
+
+    /// Sends the recorded runs that weren't sent yet (e.g. because their post
+    /// failed, or they were added from tracers that never posted) in one
+    /// `POST /runs/batch`, and marks them as sent. Unlike live tracing,
+    /// delivery errors are returned to the caller.
+    pub async fn post_all(&self, client: &LangSmithClient) -> Result<()> {
+        let runs = self.runs_where(|recorded| !recorded.sent);
+        if runs.is_empty() {
+            return Ok(());
+        }
+        client.batch_ingest(&runs, &[]).await?;
+
+        let mut recorded = self.runs.lock().unwrap();
+        for run in &runs {
+            if let Some(recorded) = recorded.get_mut(&run.id) {
+                recorded.sent = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for RunTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,10 +1,18 @@
+use crate::config::Config;
 use crate::error::{LangSmithError, Result};
+use crate::models::messages::Message;
+use crate::models::metrics::Metrics;
 use crate::models::run::RunType;
+use crate::tracing::batch::BatchScope;
+use crate::tracing::run_tree::RunTree;
 use crate::tracing::tracer::Tracer;
 use crate::tracing::TraceContext;
+use crate::utils::redaction::Redactor;
 use crate::utils::serialization::{ensure_inputs_object, ensure_outputs_object};
 use serde::Serialize;
 use serde_json::Value;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 /// Ergonomic wrapper around `Tracer` that standardizes:
 /// - inputs/outputs serialization
@@ -12,25 +20,54 @@ use serde_json::Value;
 /// - error handling (best-effort patching)
 ///
 /// This is intended to keep application code small and declarative.
+///
+/// When tracing is disabled, scopes are no-ops: inputs and outputs are never
+/// serialized and nothing is sent.
 pub struct RunScope {
     tracer: Tracer,
     posted: bool,
+    enabled: bool,
+    /// When set, runs are queued here once ended instead of posted and patched
+    batch: Option<Arc<BatchScope>>,
+    /// When set, runs are also recorded here once ended
+    tree: Option<Arc<RunTree>>,
 }
 
 impl RunScope {
     pub fn root<I: Serialize>(name: &str, run_type: RunType, inputs: I) -> Result<Self> {
+        if !Config::is_tracing_enabled() {
+            return Ok(Self::disabled(name, run_type));
+        }
         let inputs_value =
             ensure_inputs_object(inputs).map_err(LangSmithError::Serialization)?;
-        Ok(Self {
-            tracer: Tracer::new(name, run_type, inputs_value),
-            posted: false,
-        })
+        Ok(Self::enabled(Tracer::new(name, run_type, inputs_value)))
     }
 
     pub fn root_value(name: &str, run_type: RunType, inputs: Value) -> Self {
+        if !Config::is_tracing_enabled() {
+            return Self::disabled(name, run_type);
+        }
+        Self::enabled(Tracer::new(name, run_type, inputs))
+    }
+
+    fn enabled(tracer: Tracer) -> Self {
+        Self {
+            tracer,
+            posted: false,
+            enabled: true,
+            batch: None,
+            tree: None,
+        }
+    }
+
+    /// Placeholder scope that keeps ids and names but no inputs
+    fn disabled(name: &str, run_type: RunType) -> Self {
         Self {
-            tracer: Tracer::new(name, run_type, inputs),
+            tracer: Tracer::placeholder(name, run_type, Value::Null),
             posted: false,
+            enabled: false,
+            batch: None,
+            tree: None,
         }
     }
 
@@ -39,11 +76,45 @@ impl RunScope {
         self
     }
 
+    /// Sends this run and its children to `project` instead of the configured default
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.tracer = self.tracer.with_project(project);
+        self
+    }
+
+    pub fn with_redactor(mut self, redactor: Arc<dyn Redactor>) -> Self {
+        self.tracer = self.tracer.with_redactor(redactor);
+        self
+    }
+
     pub fn with_context(mut self, ctx: &TraceContext) -> Self {
         self.tracer = self.tracer.with_context(ctx);
         self
     }
 
+    /// Attaches this run under the run `ctx` describes; see
+    /// `Tracer::with_parent_context`
+    pub fn with_parent_context(mut self, ctx: &TraceContext) -> Self {
+        self.tracer = self.tracer.with_parent_context(ctx);
+        self
+    }
+
+    /// Buffers this run and its children in `batch`: `post_start` sends
+    /// nothing, and ended runs are queued whole until the batch is flushed.
+    pub fn with_batch(mut self, batch: Arc<BatchScope>) -> Self {
+        // Children build their dotted_order from this run's, which a root
+        // would otherwise only get when posted
+        self.tracer.init_root_ids();
+        self.batch = Some(batch);
+        self
+    }
+
+    /// Records this run and its children in `tree` as they end
+    pub fn with_run_tree(mut self, tree: Arc<RunTree>) -> Self {
+        self.tree = Some(tree);
+        self
+    }
+
     pub fn tracer(&self) -> &Tracer {
         &self.tracer
     }
@@ -53,47 +124,197 @@ impl RunScope {
     }
 
     pub fn child<I: Serialize>(&self, name: &str, run_type: RunType, inputs: I) -> Result<Self> {
+        if !self.enabled {
+            return Ok(self.disabled_child(name, run_type));
+        }
         let inputs_value =
             ensure_inputs_object(inputs).map_err(LangSmithError::Serialization)?;
-        Ok(Self {
-            tracer: self.tracer.create_child(name, run_type, inputs_value),
-            posted: false,
-        })
+        Ok(self.enabled_child(self.tracer.create_child(name, run_type, inputs_value)))
     }
 
     pub fn child_value(&self, name: &str, run_type: RunType, inputs: Value) -> Self {
+        if !self.enabled {
+            return self.disabled_child(name, run_type);
+        }
+        self.enabled_child(self.tracer.create_child(name, run_type, inputs))
+    }
+
+    /// LLM child run for a chat model call; see `Tracer::child_llm`
+    pub fn child_llm(&self, name: &str, model: &str, messages: &[Message]) -> Self {
+        if !self.enabled {
+            return self.disabled_child(name, RunType::Llm);
+        }
+        self.enabled_child(self.tracer.child_llm(name, model, messages))
+    }
+
+    fn enabled_child(&self, tracer: Tracer) -> Self {
+        Self {
+            batch: self.batch.clone(),
+            tree: self.tree.clone(),
+            ..Self::enabled(tracer)
+        }
+    }
+
+    fn disabled_child(&self, name: &str, run_type: RunType) -> Self {
         Self {
-            tracer: self.tracer.create_child(name, run_type, inputs),
+            tracer: self.tracer.placeholder_child(name, run_type),
             posted: false,
+            enabled: false,
+            batch: None,
+            tree: None,
         }
     }
 
+    /// Whether this scope records anything; false when tracing was disabled
+    /// at the time the root scope was created.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Wraps the scope in a guard that closes the run if it's dropped without
+    /// being ended (early return, `?`, panic).
+    pub fn guard(self) -> ScopeGuard {
+        ScopeGuard { scope: Some(self) }
+    }
+
     /// Posts the run start to LangSmith. Safe to call multiple times.
     pub async fn post_start(&mut self) -> Result<()> {
-        if self.posted {
+        if self.posted || !self.enabled {
             return Ok(());
         }
-        self.tracer.post().await?;
+        if self.batch.is_none() {
+            self.tracer.post().await?;
+        }
         self.posted = true;
         Ok(())
     }
 
     /// Ends the run successfully and PATCHes it (best-effort).
     pub async fn end_ok<O: Serialize>(mut self, outputs: O) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
         let outputs_value =
             ensure_outputs_object(outputs).map_err(LangSmithError::Serialization)?;
         self.tracer.end(outputs_value);
-        let _ = self.tracer.patch().await;
+        self.send_end().await;
         Ok(())
     }
 
+    /// Ends the run successfully with token/cost metrics and PATCHes it (best-effort).
+    pub async fn end_ok_with_metrics<O: Serialize>(
+        mut self,
+        outputs: O,
+        metrics: Metrics,
+    ) -> Result<()> {
+        self.tracer.set_metrics(metrics);
+        self.end_ok(outputs).await
+    }
+
     /// Ends the run with error and PATCHes it (best-effort).
     pub async fn end_error(mut self, error: impl ToString, outputs: Option<Value>) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
         self.tracer.set_error(&error.to_string());
         self.tracer.end(outputs.unwrap_or_else(|| serde_json::json!({})));
-        let _ = self.tracer.patch().await;
+        self.send_end().await;
         Ok(())
     }
+
+    /// PATCHes the ended run (best-effort), or queues it on the scope's batch
+    async fn send_end(&mut self) {
+        if let Some(tree) = &self.tree {
+            tree.record(&mut self.tracer, self.posted);
+        }
+        match &self.batch {
+            Some(batch) => batch.add(&mut self.tracer),
+            None => {
+                let _ = self.tracer.patch().await;
+            }
+        }
+    }
+}
+
+/// `RunScope` that patches its run with an error and end_time when dropped
+/// without an explicit `end_ok`/`end_error`.
+///
+/// `Drop` can't await, so the patch is spawned as a best-effort task on the
+/// current tokio runtime. Runs that were never posted are not patched.
+pub struct ScopeGuard {
+    scope: Option<RunScope>,
+}
+
+impl ScopeGuard {
+    /// Ends the run successfully and PATCHes it (best-effort).
+    pub async fn end_ok<O: Serialize>(mut self, outputs: O) -> Result<()> {
+        self.take().end_ok(outputs).await
+    }
+
+    /// Ends the run successfully with token/cost metrics and PATCHes it (best-effort).
+    pub async fn end_ok_with_metrics<O: Serialize>(
+        mut self,
+        outputs: O,
+        metrics: Metrics,
+    ) -> Result<()> {
+        self.take().end_ok_with_metrics(outputs, metrics).await
+    }
+
+    /// Ends the run with error and PATCHes it (best-effort).
+    pub async fn end_error(mut self, error: impl ToString, outputs: Option<Value>) -> Result<()> {
+        self.take().end_error(error, outputs).await
+    }
+
+    /// Releases the scope without closing the run
+    pub fn into_inner(mut self) -> RunScope {
+        self.take()
+    }
+
+    fn take(&mut self) -> RunScope {
+        self.scope.take().expect("scope is only taken once")
+    }
 }
 
+impl Deref for ScopeGuard {
+    type Target = RunScope;
+
+    fn deref(&self) -> &RunScope {
+        self.scope.as_ref().expect("scope is only taken once")
+    }
+}
 
+impl DerefMut for ScopeGuard {
+    fn deref_mut(&mut self) -> &mut RunScope {
+        self.scope.as_mut().expect("scope is only taken once")
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let Some(scope) = self.scope.take() else {
+            return;
+        };
+        if !scope.posted {
+            return;
+        }
+
+        let batch = scope.batch;
+        let tree = scope.tree;
+        let mut tracer = scope.tracer;
+        let error = if std::thread::panicking() {
+            "Run panicked before it was ended"
+        } else {
+            "Run dropped before it was ended"
+        };
+        tracer.end_dropped(error);
+
+        if let Some(tree) = tree {
+            tree.record(&mut tracer, true);
+        }
+        if let Some(batch) = batch {
+            batch.add(&mut tracer);
+            return;
+        }
+        tracer.spawn_patch();
+    }
+}
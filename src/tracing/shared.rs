@@ -0,0 +1,65 @@
+use crate::models::run::RunType;
+use crate::tracing::context::TraceContext;
+use crate::tracing::tracer::Tracer;
+use serde_json::Value;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Cheaply cloneable, thread-safe handle to an open parent run.
+///
+/// Clone it into `tokio::spawn`ed tasks to create children concurrently while
+/// the parent is still running. Once the children are done, take the parent
+/// back with `try_into_inner` to end and patch it.
+#[derive(Clone)]
+pub struct SharedTracer {
+    tracer: Arc<Tracer>,
+}
+
+impl SharedTracer {
+    /// Shares `tracer`. A root run gets its trace id and dotted order now so
+    /// every child sees the same ones.
+    pub fn new(mut tracer: Tracer) -> Self {
+        tracer.init_root_ids();
+        Self {
+            tracer: Arc::new(tracer),
+        }
+    }
+
+    pub fn create_child(
+        &self,
+        name: impl Into<String>,
+        run_type: RunType,
+        inputs: Value,
+    ) -> Tracer {
+        self.tracer.create_child(name, run_type, inputs)
+    }
+
+    pub fn context(&self) -> TraceContext {
+        self.tracer.context()
+    }
+
+    /// Context for runs created under the parent; see `Tracer::child_context`
+    pub fn child_context(&self) -> TraceContext {
+        self.tracer.child_context()
+    }
+
+    pub fn tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
+    pub fn run_id(&self) -> Uuid {
+        self.tracer.run_id()
+    }
+
+    /// Returns the parent tracer if this is the last handle, or the handle
+    /// back if clones are still alive.
+    pub fn try_into_inner(self) -> std::result::Result<Tracer, Self> {
+        Arc::try_unwrap(self.tracer).map_err(|tracer| Self { tracer })
+    }
+}
+
+impl From<Tracer> for SharedTracer {
+    fn from(tracer: Tracer) -> Self {
+        Self::new(tracer)
+    }
+}
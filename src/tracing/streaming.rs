@@ -0,0 +1,120 @@
+use crate::error::Result;
+use crate::tracing::tracer::Tracer;
+use crate::utils::timestamp::{self, Timestamp};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Counts the tokens in a chunk of streamed text
+pub type Tokenizer = Arc<dyn Fn(&str) -> u64 + Send + Sync>;
+
+/// Wraps a `Tracer` for a streamed LLM completion.
+///
+/// Chunks pushed with `push_chunk` are accumulated and, on `end`, the assembled
+/// text becomes the run output. When a tokenizer is set, tokens are counted per
+/// chunk and the running total is recorded as `completion_tokens`, which is
+/// useful for providers that don't report usage on streams.
+///
+/// The wrapped run is marked as streaming (see `Tracer::mark_streaming`). The
+/// arrival of the first chunk is recorded as `first_token_time` in `extra`, and
+/// `end` adds `time_to_first_token_ms` measured from the run's start time.
+pub struct StreamingTracer {
+    tracer: Tracer,
+    output: String,
+    tokenizer: Option<Tokenizer>,
+    completion_tokens: u64,
+    first_token_time: Option<Timestamp>,
+}
+
+impl StreamingTracer {
+    pub fn new(mut tracer: Tracer) -> Self {
+        tracer.mark_streaming();
+        Self {
+            tracer,
+            output: String::new(),
+            tokenizer: None,
+            completion_tokens: 0,
+            first_token_time: None,
+        }
+    }
+
+    pub fn with_tokenizer(
+        mut self,
+        tokenizer: impl Fn(&str) -> u64 + Send + Sync + 'static,
+    ) -> Self {
+        self.tokenizer = Some(Arc::new(tokenizer));
+        self
+    }
+
+    pub fn tracer(&self) -> &Tracer {
+        &self.tracer
+    }
+
+    pub fn tracer_mut(&mut self) -> &mut Tracer {
+        &mut self.tracer
+    }
+
+    /// Appends a chunk to the output and counts its tokens. The first chunk
+    /// also records `first_token_time`.
+    pub fn push_chunk(&mut self, chunk: &str) {
+        if self.first_token_time.is_none() {
+            let now = timestamp::now();
+            self.first_token_time = Some(now);
+            self.tracer.run.extra.insert(
+                "first_token_time".to_string(),
+                Value::String(timestamp::to_rfc3339(&now)),
+            );
+        }
+        if let Some(tokenizer) = &self.tokenizer {
+            self.completion_tokens += tokenizer(chunk);
+        }
+        self.output.push_str(chunk);
+    }
+
+    /// The output assembled so far
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// When the first chunk arrived, or `None` before any chunk
+    pub fn first_token_time(&self) -> Option<Timestamp> {
+        self.first_token_time
+    }
+
+    /// Milliseconds between the run's start and the first chunk
+    pub fn time_to_first_token_ms(&self) -> Option<i64> {
+        let start = timestamp::unix_micros(&self.tracer.run.start_time);
+        self.first_token_time
+            .map(|first| (timestamp::unix_micros(&first) - start) / 1_000)
+    }
+
+    /// Tokens counted so far, or `None` without a tokenizer
+    pub fn completion_tokens(&self) -> Option<u64> {
+        self.tokenizer.as_ref().map(|_| self.completion_tokens)
+    }
+
+    pub async fn post(&mut self) -> Result<()> {
+        self.tracer.post().await
+    }
+
+    /// Ends the run with the assembled output, the counted completion tokens and
+    /// the time to first token.
+    pub fn end(&mut self) {
+        if let Some(ttft) = self.time_to_first_token_ms() {
+            self.tracer
+                .run
+                .extra
+                .insert("time_to_first_token_ms".to_string(), json!(ttft));
+        }
+        if let Some(completion_tokens) = self.completion_tokens() {
+            let run = &mut self.tracer.run;
+            run.completion_tokens = Some(completion_tokens);
+            let prompt_tokens = run.prompt_tokens.unwrap_or(0);
+            run.total_tokens = Some(prompt_tokens.saturating_add(completion_tokens));
+        }
+        self.tracer.end(json!({ "output": self.output }));
+    }
+
+    pub async fn patch(&self) -> Result<()> {
+        self.tracer.patch().await
+    }
+}
@@ -1,47 +1,245 @@
 use crate::client::LangSmithClient;
 use crate::config::Config;
-use crate::error::Result;
-use crate::models::run::{Run, RunType, RunUpdate};
+use crate::error::{report_trace_error, LangSmithError, Result};
+use crate::models::attachment::Attachment;
+use crate::models::dataset::Example;
+use crate::models::feedback::Feedback;
+use crate::models::messages::{
+    chat_inputs, chat_output, render_prompt, AIMessage, Message, ToolCall,
+};
+use crate::models::metrics::Metrics;
+use crate::models::run::{
+    dotted_order_at, DottedOrderPrecision, Run, RunErrorKind, RunType, RunUpdate,
+};
 use crate::tracing::context::TraceContext;
-use serde_json::Value;
+use crate::utils::redaction::Redactor;
+use crate::utils::timestamp::{self, TimeDelta, Timestamp};
+use crate::utils::truncation::{preview, TruncationStrategy};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
 use uuid::Uuid;
 
 pub struct Tracer {
     pub(crate) run: Run,
     client: Option<Arc<LangSmithClient>>,
+    /// Whether this run's trace is recorded; decided once at the root
+    sampled: bool,
+    redactor: Option<Arc<dyn Redactor>>,
     #[allow(dead_code)]
     parent_tracer: Option<Arc<Tracer>>,
+    /// `dotted_order` time (ns since epoch) handed to the latest child, shared
+    /// by clones
+    last_child_order_nanos: Arc<AtomicI64>,
+    /// Files sent with the run when it's posted
+    attachments: Vec<Attachment>,
+    /// Set once the run has been accepted by LangSmith, so `post` doesn't
+    /// create it twice
+    posted: bool,
+    /// Length of the inputs/outputs previews sent with the run, if any
+    preview_chars: Option<usize>,
+    /// Precision of the time stamps in this run's `dotted_order`
+    dotted_order_precision: DottedOrderPrecision,
 }
 
 impl Tracer {
     pub fn new(name: impl Into<String>, run_type: RunType, inputs: Value) -> Self {
-        let mut run = Run::new(name.into(), run_type, inputs);
+        let mut tracer = Self::placeholder(name, run_type, inputs);
+        let mut sample_rate = 1.0;
         
-        // Set session_name from config if available (project name, not UUID)
+        // Set session_name (project name) and session_id (its UUID) from config
         if let Ok(config) = Config::get() {
             if let Some(project) = &config.project {
-                run.session_name = Some(project.clone());
+                tracer.run.session_name = Some(project.clone());
             }
+            tracer.run.session_id = config.project_id.map(|id| id.to_string());
+            sample_rate = config.sample_rate;
+            tracer.dotted_order_precision = config.dotted_order_precision;
         }
 
+        tracer.sampled = should_sample(sample_rate);
+        tracer
+    }
+
+    /// Tracer that is never sent, built without reading the config: a cheap
+    /// stand-in for runs of disabled scopes
+    pub(crate) fn placeholder(name: impl Into<String>, run_type: RunType, inputs: Value) -> Self {
         Self {
-            run,
+            run: Run::new(name.into(), run_type, inputs),
             client: None,
+            sampled: false,
+            redactor: None,
             parent_tracer: None,
+            last_child_order_nanos: Arc::new(AtomicI64::new(0)),
+            attachments: Vec::new(),
+            posted: false,
+            preview_chars: None,
+            dotted_order_precision: DottedOrderPrecision::default(),
         }
     }
 
+    /// LLM tracer whose inputs are the chat `messages` (see `chat_inputs`)
+    pub fn new_chat(name: impl Into<String>, messages: Vec<Message>) -> Self {
+        Self::new(name, RunType::Llm, chat_inputs(&messages))
+    }
+
+    /// Tracer for executing a model's tool call; see `Run::from_tool_call`
+    pub fn for_tool_call(tool: &ToolCall) -> Self {
+        let run = Run::from_tool_call(tool);
+        let mut tracer = Self::new(run.name, run.run_type, run.inputs);
+        tracer.run.extra = run.extra;
+        tracer
+    }
+
+    /// Uses `client` for this run. A root run re-evaluates sampling against
+    /// the client's `sample_rate`, since that is the config it reports under.
     pub fn with_client(mut self, client: Arc<LangSmithClient>) -> Self {
+        if self.is_new_root() {
+            self.sampled = should_sample(client.config().sample_rate);
+        }
         self.client = Some(client);
         self
     }
 
+    /// Sends an `inputs_preview`/`outputs_preview` of at most `max_chars`
+    /// alongside the full inputs and outputs, so the LangSmith UI can list
+    /// large runs without loading them. Previews are taken after redaction.
+    /// Child runs inherit the setting.
+    pub fn with_preview(mut self, max_chars: usize) -> Self {
+        self.preview_chars = Some(max_chars);
+        self
+    }
+
+    /// Scrubs inputs/outputs with `redactor` before they are sent.
+    /// Child runs inherit the redactor.
+    pub fn with_redactor(mut self, redactor: Arc<dyn Redactor>) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Seeds outputs known up front (e.g. the chosen route) so they're recorded
+    /// even if the run fails. Merged with the final outputs on `end`.
+    pub fn with_outputs(mut self, outputs: Value) -> Self {
+        self.run.outputs = Some(outputs);
+        self
+    }
+
+    /// Records the rendered prompt for the run's `inputs.messages` in
+    /// `extra.rendered_prompt`, using role-tagged lines (`human: Hi`).
+    pub fn with_rendered_prompt(self) -> Self {
+        self.with_prompt_renderer(render_prompt)
+    }
+
+    /// Like `with_rendered_prompt`, with a custom renderer. Does nothing if
+    /// the inputs have no `messages` list of `Message`s.
+    pub fn with_prompt_renderer(mut self, renderer: impl Fn(&[Message]) -> String) -> Self {
+        let messages = self
+            .run
+            .inputs
+            .get("messages")
+            .and_then(|messages| Vec::<Message>::deserialize(messages).ok());
+        if let Some(messages) = messages {
+            self.run
+                .extra
+                .insert("rendered_prompt".to_string(), Value::String(renderer(&messages)));
+        }
+        self
+    }
+
     pub fn with_thread_id(mut self, thread_id: String) -> Self {
         self.run.thread_id = Some(thread_id);
         self
     }
 
+    /// Sends this run to `project` instead of the configured default.
+    /// Children and runs created from `context()` inherit it. The project id
+    /// taken from the config belongs to the default project and is dropped;
+    /// call `with_project_id` afterwards to send the new project's id.
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        let project = project.into();
+        if self.run.session_name.as_ref() != Some(&project) {
+            self.run.session_id = None;
+        }
+        self.run.session_name = Some(project);
+        self
+    }
+
+    /// Sends the project's UUID as the run's `session_id`, independently of
+    /// the project name. Children and runs created from `context()` inherit it.
+    pub fn with_project_id(mut self, project_id: Uuid) -> Self {
+        self.run.session_id = Some(project_id.to_string());
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.run.tags = tags;
+        self
+    }
+
+    pub fn add_tag(&mut self, tag: String) {
+        self.run.tags.push(tag);
+    }
+
+    /// Attaches a file to the run; runs with attachments are posted as a
+    /// multipart request. Attachments must be added before `post`.
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    pub fn add_attachment(&mut self, attachment: Attachment) {
+        self.attachments.push(attachment);
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    /// Adds a metadata entry to the run's `extra` map
+    pub fn with_metadata(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.run.extra.insert(key.into(), value);
+        self
+    }
+
+    /// Links the run to the dataset example it is evaluated against, so
+    /// LangSmith can score it
+    pub fn with_reference_example(mut self, example_id: Uuid) -> Self {
+        self.run.reference_example_id = Some(example_id);
+        self
+    }
+
+    /// Deep-merges `metadata` into the run's metadata: nested objects are
+    /// merged key by key, anything else replaces the existing value. Lets
+    /// several observers or middleware contribute to the same run without
+    /// overwriting each other's entries.
+    pub fn merge_metadata(&mut self, metadata: Map<String, Value>) {
+        for (key, value) in metadata {
+            match self.run.extra.get_mut(&key) {
+                Some(existing) => merge_value(existing, value),
+                None => {
+                    self.run.extra.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Backdates the run to `start_time`, e.g. when importing a trace after the
+    /// fact. The run's segment of `dotted_order` encodes the start time, so it
+    /// is regenerated to match.
+    pub fn with_start_time(mut self, start_time: Timestamp) -> Self {
+        self.run.start_time = start_time;
+        if let Some(dotted_order) = self.run.dotted_order.take() {
+            let parent = dotted_order.rsplit_once('.').map(|(parent, _)| parent);
+            self.run.dotted_order = Some(self.generate_dotted_order(parent));
+        }
+        self
+    }
+
+    /// Places this run where `context` says: its trace, parent and, when
+    /// given, `dotted_order` are taken from the context as-is.
     pub fn with_context(mut self, context: &TraceContext) -> Self {
         self.run.trace_id = Some(context.trace_id);
         if let Some(parent_id) = context.parent_run_id {
@@ -50,15 +248,65 @@ impl Tracer {
         if let Some(ref dotted_order) = context.dotted_order {
             self.run.dotted_order = Some(dotted_order.clone());
         }
+        self.inherit_context(context)
+    }
+
+    /// Attaches this run under the run a `child_context` describes (e.g. one
+    /// received through `TraceContext::from_headers`), appending its own
+    /// segment to the parent's `dotted_order`.
+    ///
+    /// A context without a `dotted_order` (e.g. from
+    /// `TraceContext::from_traceparent`) can't place the run under its parent,
+    /// so the run becomes a root in the context's trace and only links the
+    /// parent in `extra.otel_parent_span_id` (a 16-digit hex span id).
+    pub fn with_parent_context(mut self, context: &TraceContext) -> Self {
+        self.run.trace_id = Some(context.trace_id);
+        match context.dotted_order.as_deref() {
+            Some(parent_dotted_order) => {
+                if let Some(parent_id) = context.parent_run_id {
+                    self.run.parent_run_id = Some(parent_id);
+                }
+                self.run.dotted_order = Some(self.generate_dotted_order(Some(parent_dotted_order)));
+            }
+            None => {
+                self.run.dotted_order = Some(self.generate_dotted_order(None));
+                if let Some(parent_id) = context.parent_run_id {
+                    let span_id = format!("{:016x}", parent_id.as_u128() as u64);
+                    self = self.with_metadata("otel_parent_span_id", json!(span_id));
+                }
+            }
+        }
+        self.inherit_context(context)
+    }
+
+    /// Thread, project and sampling shared by every run of a context
+    fn inherit_context(mut self, context: &TraceContext) -> Self {
         if let Some(ref thread_id) = context.thread_id {
             self.run.thread_id = Some(thread_id.clone());
         }
+        // The context's project replaces ours together with its id, so a run
+        // never pairs one project's name with another's id
         if let Some(ref session_name) = context.session_name {
             self.run.session_name = Some(session_name.clone());
+            self.run.session_id = context.session_id.map(|id| id.to_string());
+        } else if let Some(session_id) = context.session_id {
+            self.run.session_id = Some(session_id.to_string());
         }
+        self.sampled = context.sampled;
         self
     }
 
+    /// `placeholder` child of this run, linked to it by id only
+    pub(crate) fn placeholder_child(&self, name: impl Into<String>, run_type: RunType) -> Self {
+        let mut child = Self::placeholder(name, run_type, Value::Null);
+        child.run.parent_run_id = Some(self.run.id);
+        child.run.trace_id = self.run.trace_id.or(Some(self.run.id));
+        child
+    }
+
+    /// Creates a child run under this one. The child starts from copies of the
+    /// parent's tags and metadata; use `add_tag`/`with_metadata` to extend them
+    /// without affecting the parent.
     pub fn create_child(
         &self,
         name: impl Into<String>,
@@ -66,20 +314,42 @@ impl Tracer {
         inputs: Value,
     ) -> Self {
         let mut child = Self::new(name, run_type, inputs);
-        
+        child.dotted_order_precision = self.dotted_order_precision;
+
         // Set parent relationship
         child.run.parent_run_id = Some(self.run.id);
         child.run.trace_id = self.run.trace_id.or(Some(self.run.id));
         
-        // Generate dotted_order
-        let parent_dotted_order = self.run.dotted_order.as_deref();
-        child.run.dotted_order = Some(child.run.generate_dotted_order(parent_dotted_order));
+        // Generate dotted_order. A root that hasn't been posted yet has no
+        // dotted_order; use the one `init_root_ids` will give it.
+        let parent_dotted_order = match &self.run.dotted_order {
+            Some(dotted_order) => Some(dotted_order.clone()),
+            None if self.is_new_root() => Some(self.generate_dotted_order(None)),
+            None => None,
+        };
+        let order_time = self.next_child_order_time(child.run.start_time);
+        child.run.dotted_order = Some(dotted_order_at(
+            parent_dotted_order.as_deref(),
+            &order_time,
+            child.run.id,
+            child.dotted_order_precision,
+        ));
         
         // Inherit thread_id
         child.run.thread_id = self.run.thread_id.clone();
         
-        // Inherit session_name
+        // Inherit session_name and session_id
         child.run.session_name = self.run.session_name.clone();
+        child.run.session_id = self.run.session_id.clone();
+
+        // Inherit tags and metadata
+        child.run.tags = self.run.tags.clone();
+        child.run.extra = self.run.extra.clone();
+
+        // Sampling is decided once per trace
+        child.sampled = self.sampled;
+        child.redactor = self.redactor.clone();
+        child.preview_chars = self.preview_chars;
         
         // Share client if available
         if let Some(client) = &self.client {
@@ -89,54 +359,279 @@ impl Tracer {
         child
     }
 
+    /// Creates an LLM child run whose inputs are the chat `messages` plus the
+    /// `model` name: `{"messages": [...], "model": "..."}`
+    pub fn child_llm(&self, name: impl Into<String>, model: &str, messages: &[Message]) -> Self {
+        let mut inputs = chat_inputs(messages);
+        inputs["model"] = json!(model);
+        self.create_child(name, RunType::Llm, inputs)
+    }
+
+    /// Starts a new, independent trace linked back to this run through
+    /// `extra.forked_from`, e.g. for a retry that should show up as its own
+    /// trace. The fork keeps this run's thread, project, client, redactor and
+    /// preview settings, but not its tags or metadata.
+    pub fn fork_as_root(
+        &self,
+        name: impl Into<String>,
+        run_type: RunType,
+        inputs: Value,
+    ) -> Self {
+        let mut fork = Self::new(name, run_type, inputs)
+            .with_metadata("forked_from", json!(self.run.id));
+        fork.run.thread_id = self.run.thread_id.clone();
+        fork.run.session_name = self.run.session_name.clone();
+        fork.run.session_id = self.run.session_id.clone();
+        fork.redactor = self.redactor.clone();
+        fork.preview_chars = self.preview_chars;
+        fork.dotted_order_precision = self.dotted_order_precision;
+        fork.client = self.client.clone();
+        fork
+    }
+
+    /// Creates the run in LangSmith. Once a post has succeeded, further calls
+    /// are no-ops so a retried call can't create a duplicate run; use
+    /// `force_post` to send it again anyway.
     pub async fn post(&mut self) -> Result<()> {
-        // Initialize trace_id if this is the root run
-        if self.run.trace_id.is_none() {
-            self.run.trace_id = Some(self.run.id);
-            self.run.dotted_order = Some(self.run.generate_dotted_order(None));
+        if self.posted {
+            return Ok(());
         }
+        self.force_post().await
+    }
 
-        // Get or create client
-        let client = if let Some(client) = &self.client {
-            Arc::clone(client)
-        } else {
-            Arc::new(LangSmithClient::new()?)
+    /// Posts the run even if it was already posted
+    pub async fn force_post(&mut self) -> Result<()> {
+        let Some(mut run) = self.outgoing_run() else {
+            return Ok(());
         };
 
+        let client = self.client()?;
+        if let Some(max_bytes) = client.config().max_string_bytes {
+            TruncationStrategy::bytes(max_bytes).truncate_run_in_place(&mut run);
+        }
+
         // Post run - await to ensure it completes
-        if let Err(e) = client.post_run(&self.run).await {
-            eprintln!("LangSmith tracing error: {}", e);
+        let result = if self.attachments.is_empty() {
+            client.post_run(&run).await
+        } else {
+            client.post_run_multipart(&run, &self.attachments).await
+        };
+        match result {
+            Ok(()) => self.posted = true,
+            Err(e) => report_trace_error(None, &e),
         }
 
         Ok(())
     }
 
     pub async fn patch(&self) -> Result<()> {
-        let client = if let Some(client) = &self.client {
-            Arc::clone(client)
-        } else {
-            Arc::new(LangSmithClient::new()?)
-        };
+        if !self.sampled {
+            return Ok(());
+        }
+
+        let client = self.client()?;
 
         let run_id = self.run.id;
-        let updates = RunUpdate::from(&self.run);
+        let mut updates = match client.config().max_string_bytes {
+            Some(max_bytes) => {
+                let mut run = self.run.clone();
+                TruncationStrategy::bytes(max_bytes).truncate_run_in_place(&mut run);
+                RunUpdate::from(&run)
+            }
+            None => RunUpdate::from(&self.run),
+        };
+        if let Some(redactor) = &self.redactor {
+            updates.outputs = updates.outputs.map(|outputs| redactor.redact_outputs(outputs));
+        }
+        if let Some(max_chars) = self.preview_chars {
+            updates.outputs_preview = updates.outputs.as_ref().map(|o| preview(o, max_chars));
+        }
         
         // Patch run - await to ensure it completes
         if let Err(e) = client.patch_run(run_id, &updates).await {
-            eprintln!("LangSmith tracing error: {}", e);
+            report_trace_error(None, &e);
         }
 
         Ok(())
     }
 
+    /// Deep-merges `partial` into the run's outputs and patches the run, so
+    /// progress shows up in LangSmith before the run ends. Outputs passed to
+    /// `end` are merged on top of everything recorded here.
+    pub async fn update_outputs(&mut self, partial: Value) -> Result<()> {
+        match &mut self.run.outputs {
+            Some(outputs) => merge_value(outputs, partial),
+            None => self.run.outputs = Some(partial),
+        }
+        self.patch().await
+    }
+
+    /// The run as it should be sent, with root ids initialized and redaction
+    /// applied, or `None` if the trace isn't sampled.
+    pub(crate) fn outgoing_run(&mut self) -> Option<Run> {
+        self.init_root_ids();
+
+        if !self.sampled {
+            return None;
+        }
+
+        let mut run = match &self.redactor {
+            Some(redactor) => {
+                let mut run = self.run.clone();
+                run.inputs = redactor.redact_inputs(run.inputs);
+                run.outputs = run.outputs.map(|outputs| redactor.redact_outputs(outputs));
+                run
+            }
+            None => self.run.clone(),
+        };
+        if let Some(max_chars) = self.preview_chars {
+            run.inputs_preview = Some(preview(&run.inputs, max_chars));
+            run.outputs_preview = run.outputs.as_ref().map(|o| preview(o, max_chars));
+        }
+        Some(run)
+    }
+
+    /// Sends a scored feedback entry for this run (e.g. `feedback("correctness", 1.0)`).
+    pub async fn feedback(&self, key: impl Into<String>, score: f64) -> Result<()> {
+        if !self.sampled {
+            return Ok(());
+        }
+        let feedback = Feedback::new(self.run.id, key).with_score(score);
+        self.client()?.create_feedback(&feedback).await
+    }
+
+    /// Saves this run's inputs and outputs as an example in `dataset_id`,
+    /// after redaction. Runs that haven't ended are saved with empty outputs.
+    pub async fn to_example(&self, dataset_id: Uuid) -> Result<Example> {
+        let mut inputs = self.run.inputs.clone();
+        let mut outputs = self.run.outputs.clone().unwrap_or_else(|| json!({}));
+        if let Some(redactor) = &self.redactor {
+            inputs = redactor.redact_inputs(inputs);
+            outputs = redactor.redact_outputs(outputs);
+        }
+        self.client()?.create_example(dataset_id, &inputs, &outputs).await
+    }
+
+    /// Returns the shared client, or creates one from the global config.
+    fn client(&self) -> Result<Arc<LangSmithClient>> {
+        match &self.client {
+            Some(client) => Ok(Arc::clone(client)),
+            None => Ok(Arc::new(LangSmithClient::new()?)),
+        }
+    }
+
     pub fn end(&mut self, outputs: Value) {
-        self.run.end(outputs);
+        self.end_at(outputs, timestamp::now());
+    }
+
+    /// Like `end`, recording `end_time` instead of the current time. Use it for
+    /// spans measured elsewhere or replayed from logs.
+    pub fn end_at(&mut self, outputs: Value, end_time: Timestamp) {
+        // Final outputs take precedence over seeded ones
+        let outputs = match (self.run.outputs.take(), outputs) {
+            (Some(Value::Object(mut seeded)), Value::Object(outputs)) => {
+                seeded.extend(outputs);
+                Value::Object(seeded)
+            }
+            (_, outputs) => outputs,
+        };
+        self.run.end_at(outputs, end_time);
+    }
+
+    /// Ends a chat run with the model's reply as outputs (see `chat_output`)
+    pub fn end_with_message(&mut self, message: &AIMessage) {
+        self.end(chat_output(message));
+    }
+
+    /// Ends an LLM run, recording the provider's response `id` and
+    /// `system_fingerprint` (when present) in `extra.ls_response_metadata`.
+    pub fn end_with_response(&mut self, outputs: Value, response: &Value) {
+        self.set_response_metadata(response);
+        self.end(outputs);
+    }
+
+    /// Copies provider-side identifiers (`id`, `system_fingerprint`, `model`)
+    /// from a completion response into `extra.ls_response_metadata`.
+    pub fn set_response_metadata(&mut self, response: &Value) {
+        let metadata: serde_json::Map<String, Value> = ["id", "system_fingerprint", "model"]
+            .into_iter()
+            .filter_map(|key| {
+                response
+                    .get(key)
+                    .filter(|value| !value.is_null())
+                    .map(|value| (key.to_string(), value.clone()))
+            })
+            .collect();
+
+        if !metadata.is_empty() {
+            self.run
+                .extra
+                .insert("ls_response_metadata".to_string(), Value::Object(metadata));
+        }
+    }
+
+    /// Flags the run as a streamed generation (`extra.invocation_params.stream`),
+    /// which LangSmith uses to render it as streaming.
+    pub fn mark_streaming(&mut self) {
+        let params = self
+            .run
+            .extra
+            .entry("invocation_params".to_string())
+            .or_insert_with(|| Value::Object(Default::default()));
+        if !params.is_object() {
+            *params = Value::Object(Default::default());
+        }
+        params["stream"] = Value::Bool(true);
     }
 
     pub fn set_error(&mut self, error: &str) {
         self.run.set_error(error);
     }
 
+    /// Like `set_error`, also recording the error's type and an optional
+    /// stack trace in `extra.error_type` / `extra.stacktrace`
+    pub fn set_error_details(
+        &mut self,
+        error_type: &str,
+        message: &str,
+        stacktrace: Option<String>,
+    ) {
+        self.run.set_error(message);
+        self.run.extra.insert("error_type".to_string(), json!(error_type));
+        if let Some(stacktrace) = stacktrace {
+            self.run.extra.insert("stacktrace".to_string(), json!(stacktrace));
+        }
+    }
+
+    /// Like `set_error`, also recording the error's category in `extra.error_kind`
+    /// (e.g. `"rate_limit"`). Convert client errors with `RunErrorKind::from`.
+    pub fn set_error_kind(&mut self, kind: RunErrorKind, message: &str) {
+        self.run.set_error(message);
+        self.run.extra.insert("error_kind".to_string(), json!(kind));
+    }
+
+    /// Records token counts and costs on the run; they are sent with the next patch
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.run.set_metrics(&metrics);
+    }
+
+    /// Records token counts (and their total) without touching costs
+    pub fn set_tokens(&mut self, prompt: u64, completion: u64) {
+        self.run.prompt_tokens = Some(prompt);
+        self.run.completion_tokens = Some(completion);
+        self.run.total_tokens = Some(prompt.saturating_add(completion));
+    }
+
+    /// Records the run's total cost, for providers that don't split it
+    pub fn set_cost(&mut self, total: f64) {
+        self.run.total_cost = Some(total);
+    }
+
+    /// The underlying run as it would be sent to LangSmith
+    pub fn run(&self) -> &Run {
+        &self.run
+    }
+
     pub fn run_id(&self) -> Uuid {
         self.run.id
     }
@@ -161,6 +656,21 @@ impl Tracer {
         self.run.dotted_order.as_ref()
     }
 
+    /// Whether this run has no parent
+    pub fn is_root(&self) -> bool {
+        self.run.parent_run_id.is_none()
+    }
+
+    /// Nesting level of the run: 0 for a root, 1 for its children, and so on,
+    /// counted from `dotted_order`. Without a dotted order only root vs. child
+    /// is known, so a child reports 1.
+    pub fn depth(&self) -> usize {
+        match &self.run.dotted_order {
+            Some(dotted_order) => dotted_order.split('.').count() - 1,
+            None => usize::from(!self.is_root()),
+        }
+    }
+
     pub fn thread_id(&self) -> Option<&String> {
         self.run.thread_id.as_ref()
     }
@@ -169,6 +679,94 @@ impl Tracer {
         self.run.session_name.as_ref()
     }
 
+    pub fn session_id(&self) -> Option<&String> {
+        self.run.session_id.as_ref()
+    }
+
+    pub fn start_time(&self) -> Timestamp {
+        self.run.start_time
+    }
+
+    /// When the run ended, `None` until `end` (or `end_with_response`) is called
+    pub fn end_time(&self) -> Option<Timestamp> {
+        self.run.end_time
+    }
+
+    /// Time between start and end, `None` while the run is still open
+    pub fn duration(&self) -> Option<TimeDelta> {
+        self.run.end_time.map(|end| end - self.run.start_time)
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.run.tags
+    }
+
+    pub fn metadata(&self) -> &HashMap<String, Value> {
+        &self.run.extra
+    }
+
+    /// Wraps the tracer in a guard that closes the run if it is dropped after
+    /// being posted but before being ended.
+    pub fn into_guard(self) -> TracerGuard {
+        TracerGuard {
+            tracer: Some(self),
+        }
+    }
+
+    /// Whether a post of this run has succeeded
+    pub fn is_posted(&self) -> bool {
+        self.posted
+    }
+
+    /// Whether this run's trace is recorded. Unsampled runs are never sent.
+    pub fn is_sampled(&self) -> bool {
+        self.sampled
+    }
+
+    /// Time in a new child's `dotted_order` segment: its start time, or one
+    /// tick (1µs, or 1ns at nanosecond precision) past the previous child's
+    /// when siblings start within the same tick, so they still sort by
+    /// creation order. The child's `start_time` keeps the real time.
+    fn next_child_order_time(&self, start_time: Timestamp) -> Timestamp {
+        let Some(start_nanos) = timestamp::unix_nanos(&start_time) else {
+            return start_time;
+        };
+        let tick = match self.dotted_order_precision {
+            DottedOrderPrecision::Micros => 1_000,
+            DottedOrderPrecision::Nanos => 1,
+        };
+        let start = start_nanos - start_nanos.rem_euclid(tick);
+        let previous = self
+            .last_child_order_nanos
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(start.max(last + tick))
+            })
+            .unwrap_or_else(|last| last);
+        let assigned = start.max(previous + tick);
+        timestamp::from_unix_nanos(assigned).unwrap_or(start_time)
+    }
+
+    /// Sets trace_id and dotted_order on a root run that doesn't have them yet
+    pub(crate) fn init_root_ids(&mut self) {
+        if self.run.trace_id.is_none() {
+            self.run.trace_id = Some(self.run.id);
+            self.run.dotted_order = Some(self.generate_dotted_order(None));
+        }
+    }
+
+    /// This run's `dotted_order` segment at the configured precision, under
+    /// `parent_dotted_order` if given
+    pub(crate) fn generate_dotted_order(&self, parent_dotted_order: Option<&str>) -> String {
+        self.run.generate_dotted_order_with(parent_dotted_order, self.dotted_order_precision)
+    }
+
+    /// A root that hasn't joined or started a trace yet
+    fn is_new_root(&self) -> bool {
+        self.run.parent_run_id.is_none() && self.run.trace_id.is_none()
+    }
+
+    /// Context describing this run itself: its trace, parent and
+    /// `dotted_order`. Use `child_context` to create runs under it.
     pub fn context(&self) -> TraceContext {
         TraceContext {
             trace_id: self.run.trace_id.unwrap_or(self.run.id),
@@ -176,6 +774,29 @@ impl Tracer {
             dotted_order: self.run.dotted_order.clone(),
             thread_id: self.run.thread_id.clone(),
             session_name: self.run.session_name.clone(),
+            session_id: self.run.session_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+            sampled: self.sampled,
+        }
+    }
+
+    /// Context for runs that should be created as children of this run,
+    /// e.g. on another task or service: `parent_run_id` is this run and
+    /// `dotted_order` its own. Attach runs to it with `with_parent_context`.
+    pub fn child_context(&self) -> TraceContext {
+        let dotted_order = self
+            .run
+            .dotted_order
+            .clone()
+            .unwrap_or_else(|| self.generate_dotted_order(None));
+
+        TraceContext {
+            trace_id: self.run.trace_id.unwrap_or(self.run.id),
+            parent_run_id: Some(self.run.id),
+            dotted_order: Some(dotted_order),
+            thread_id: self.run.thread_id.clone(),
+            session_name: self.run.session_name.clone(),
+            session_id: self.run.session_id.as_deref().and_then(|id| Uuid::parse_str(id).ok()),
+            sampled: self.sampled,
         }
     }
 }
@@ -185,8 +806,113 @@ impl Clone for Tracer {
         Self {
             run: self.run.clone(),
             client: self.client.as_ref().map(Arc::clone),
+            sampled: self.sampled,
+            redactor: self.redactor.clone(),
             parent_tracer: None, // Don't clone parent to avoid cycles
+            last_child_order_nanos: Arc::clone(&self.last_child_order_nanos),
+            attachments: self.attachments.clone(),
+            posted: self.posted,
+            preview_chars: self.preview_chars,
+            dotted_order_precision: self.dotted_order_precision,
+        }
+    }
+}
+
+/// `Tracer` that patches its run with an error and end_time when dropped after
+/// `post` but before `end`, so an early return or panic doesn't leave the run
+/// open in LangSmith.
+///
+/// `Drop` can't await, so the patch is spawned as a best-effort task on the
+/// current tokio runtime.
+pub struct TracerGuard {
+    tracer: Option<Tracer>,
+}
+
+impl TracerGuard {
+    /// Releases the tracer without closing the run
+    pub fn into_inner(mut self) -> Tracer {
+        self.tracer.take().expect("tracer is only taken once")
+    }
+}
+
+impl Deref for TracerGuard {
+    type Target = Tracer;
+
+    fn deref(&self) -> &Tracer {
+        self.tracer.as_ref().expect("tracer is only taken once")
+    }
+}
+
+impl DerefMut for TracerGuard {
+    fn deref_mut(&mut self) -> &mut Tracer {
+        self.tracer.as_mut().expect("tracer is only taken once")
+    }
+}
+
+impl Drop for TracerGuard {
+    fn drop(&mut self) {
+        let Some(mut tracer) = self.tracer.take() else {
+            return;
+        };
+        if !tracer.posted || tracer.run.end_time.is_some() {
+            return;
+        }
+
+        tracer.end_dropped("run dropped without completion");
+        tracer.spawn_patch();
+    }
+}
+
+impl Tracer {
+    /// Ends a run whose guard is dropped before it was ended, with `error`
+    pub(crate) fn end_dropped(&mut self, error: &str) {
+        self.set_error(error);
+        self.end(json!({}));
+    }
+
+    /// Patches the run from a task on the current tokio runtime, for `Drop`
+    /// impls that can't await
+    pub(crate) fn spawn_patch(self) {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    let _ = self.patch().await;
+                });
+            }
+            Err(_) => report_trace_error(
+                Some("drop"),
+                &LangSmithError::Other("no tokio runtime to patch dropped run".to_string()),
+            ),
+        }
+    }
+}
+
+/// Merges `value` into `target`, recursing where both are objects
+fn merge_value(target: &mut Value, value: Value) {
+    match (target, value) {
+        (Value::Object(target), Value::Object(value)) => {
+            for (key, value) in value {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
         }
+        (target, value) => *target = value,
     }
 }
 
+/// Randomly decides whether a new trace is recorded at the given rate.
+fn should_sample(rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    // The leading 32 bits of a v4 UUID are random
+    let roll = (Uuid::new_v4().as_u128() >> 96) as f64 / (u32::MAX as f64 + 1.0);
+    roll < rate
+}
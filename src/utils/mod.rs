@@ -1,6 +1,13 @@
+pub mod redaction;
 pub mod serialization;
+pub mod timestamp;
+pub mod truncation;
 pub mod validation;
 
+pub use redaction::{KeyRedactor, Redactor};
 pub use serialization::ensure_object;
-pub use validation::validate_run;
+pub use truncation::{TruncationLimit, TruncationStrategy};
+pub use validation::{
+    normalize_run, validate_run, validate_run_with, NonObjectInputs, ValidationOptions,
+};
 
@@ -0,0 +1,65 @@
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Placeholder written in place of redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Scrubs sensitive data from run inputs/outputs before they are sent to LangSmith
+pub trait Redactor: Send + Sync {
+    fn redact_inputs(&self, inputs: Value) -> Value;
+
+    fn redact_outputs(&self, outputs: Value) -> Value;
+}
+
+/// Redactor that masks the values of the configured keys, at any depth
+#[derive(Debug, Clone, Default)]
+pub struct KeyRedactor {
+    keys: HashSet<String>,
+}
+
+impl KeyRedactor {
+    pub fn new<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        Self {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.keys.insert(key.into());
+        self
+    }
+
+    fn redact(&self, value: Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        if self.keys.contains(&key) {
+                            (key, Value::String(REDACTED.to_string()))
+                        } else {
+                            (key, self.redact(value))
+                        }
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.into_iter().map(|item| self.redact(item)).collect())
+            }
+            other => other,
+        }
+    }
+}
+
+impl Redactor for KeyRedactor {
+    fn redact_inputs(&self, inputs: Value) -> Value {
+        self.redact(inputs)
+    }
+
+    fn redact_outputs(&self, outputs: Value) -> Value {
+        self.redact(outputs)
+    }
+}
@@ -14,13 +14,42 @@ pub fn ensure_object<T: Serialize>(value: T, key: &str) -> Result<Value, serde_j
     Ok(json_value)
 }
 
+/// Like `ensure_object`, but a list of chat messages (objects with a string
+/// `role`) is wrapped under `"messages"` so LangSmith renders it as a chat
+/// transcript; anything else goes under `default_key`.
+pub fn ensure_object_or_messages<T: Serialize>(
+    value: T,
+    default_key: &str,
+) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(value).map(|value| object_or_messages(value, default_key))
+}
+
+/// `ensure_object_or_messages` for a value that is already serialized
+pub fn object_or_messages(value: Value, default_key: &str) -> Value {
+    if value.is_object() {
+        return value;
+    }
+    let key = if is_message_list(&value) { "messages" } else { default_key };
+    serde_json::json!({ key: value })
+}
+
 /// Ensures inputs are always an object
 pub fn ensure_inputs_object<T: Serialize>(value: T) -> Result<Value, serde_json::Error> {
-    ensure_object(value, "input")
+    ensure_object_or_messages(value, "input")
 }
 
 /// Ensures outputs are always an object
 pub fn ensure_outputs_object<T: Serialize>(value: T) -> Result<Value, serde_json::Error> {
-    ensure_object(value, "output")
+    ensure_object_or_messages(value, "output")
+}
+
+/// Whether `value` is a non-empty array of role-tagged objects
+fn is_message_list(value: &Value) -> bool {
+    match value.as_array() {
+        Some(items) if !items.is_empty() => items
+            .iter()
+            .all(|item| item.get("role").is_some_and(Value::is_string)),
+        _ => false,
+    }
 }
 
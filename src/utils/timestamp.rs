@@ -0,0 +1,219 @@
+//! Run timestamps, backed by `chrono` (the default `chrono` feature) or by the
+//! lighter `time` crate when only the `time` feature is enabled.
+//!
+//! Both backends serialize to the same RFC3339 text and produce the same
+//! `dotted_order` segments, so LangSmith sees identical payloads.
+
+#[cfg(feature = "chrono")]
+pub use self::chrono_impl::*;
+#[cfg(not(feature = "chrono"))]
+pub use self::time_impl::*;
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use chrono::{DateTime, Utc};
+
+    /// A point in time, in UTC
+    pub type Timestamp = DateTime<Utc>;
+
+    /// Difference between two timestamps
+    pub type TimeDelta = chrono::Duration;
+
+    pub fn now() -> Timestamp {
+        Utc::now()
+    }
+
+    /// Parses an RFC3339 timestamp with any offset, converted to UTC
+    pub fn parse_rfc3339(s: &str) -> Option<Timestamp> {
+        DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    /// RFC3339 with a `+00:00` offset
+    pub fn to_rfc3339(timestamp: &Timestamp) -> String {
+        timestamp.to_rfc3339()
+    }
+
+    pub fn unix_micros(timestamp: &Timestamp) -> i64 {
+        timestamp.timestamp_micros()
+    }
+
+    pub fn from_unix_micros(micros: i64) -> Option<Timestamp> {
+        DateTime::from_timestamp_micros(micros)
+    }
+
+    /// Nanoseconds since the epoch, `None` outside the years 1677-2262
+    pub fn unix_nanos(timestamp: &Timestamp) -> Option<i64> {
+        timestamp.timestamp_nanos_opt()
+    }
+
+    pub fn from_unix_nanos(nanos: i64) -> Option<Timestamp> {
+        Some(DateTime::from_timestamp_nanos(nanos))
+    }
+
+    /// `YYYYMMDDTHHMMSSffffffZ`, the time part of a `dotted_order` segment
+    pub fn dotted_order_time(timestamp: &Timestamp) -> String {
+        format!(
+            "{}{:06}Z",
+            timestamp.format("%Y%m%dT%H%M%S"),
+            timestamp.timestamp_subsec_micros()
+        )
+    }
+
+    /// Like `dotted_order_time`, with 9 fractional digits (nanoseconds)
+    pub fn dotted_order_time_nanos(timestamp: &Timestamp) -> String {
+        format!(
+            "{}{:09}Z",
+            timestamp.format("%Y%m%dT%H%M%S"),
+            timestamp.timestamp_subsec_nanos()
+        )
+    }
+}
+
+#[cfg(not(feature = "chrono"))]
+mod time_impl {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::fmt::Write;
+    use time::format_description::well_known::Rfc3339;
+    use time::{OffsetDateTime, UtcOffset};
+
+    /// A point in time, in UTC
+    pub type Timestamp = OffsetDateTime;
+
+    /// Difference between two timestamps
+    pub type TimeDelta = time::Duration;
+
+    pub fn now() -> Timestamp {
+        OffsetDateTime::now_utc()
+    }
+
+    /// Parses an RFC3339 timestamp with any offset, converted to UTC
+    pub fn parse_rfc3339(s: &str) -> Option<Timestamp> {
+        OffsetDateTime::parse(s, &Rfc3339)
+            .ok()
+            .map(|t| t.to_offset(UtcOffset::UTC))
+    }
+
+    /// RFC3339 with a `+00:00` offset
+    pub fn to_rfc3339(timestamp: &Timestamp) -> String {
+        format_rfc3339(timestamp, "+00:00")
+    }
+
+    pub fn unix_micros(timestamp: &Timestamp) -> i64 {
+        (timestamp.unix_timestamp_nanos() / 1_000) as i64
+    }
+
+    pub fn from_unix_micros(micros: i64) -> Option<Timestamp> {
+        OffsetDateTime::from_unix_timestamp_nanos(i128::from(micros) * 1_000).ok()
+    }
+
+    /// Nanoseconds since the epoch, `None` outside the years 1677-2262
+    pub fn unix_nanos(timestamp: &Timestamp) -> Option<i64> {
+        i64::try_from(timestamp.unix_timestamp_nanos()).ok()
+    }
+
+    pub fn from_unix_nanos(nanos: i64) -> Option<Timestamp> {
+        OffsetDateTime::from_unix_timestamp_nanos(i128::from(nanos)).ok()
+    }
+
+    /// `YYYYMMDDTHHMMSSffffffZ`, the time part of a `dotted_order` segment
+    pub fn dotted_order_time(timestamp: &Timestamp) -> String {
+        let t = timestamp.to_offset(UtcOffset::UTC);
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}{:06}Z",
+            t.year(),
+            u8::from(t.month()),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second(),
+            t.microsecond()
+        )
+    }
+
+    /// Like `dotted_order_time`, with 9 fractional digits (nanoseconds)
+    pub fn dotted_order_time_nanos(timestamp: &Timestamp) -> String {
+        let t = timestamp.to_offset(UtcOffset::UTC);
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}{:09}Z",
+            t.year(),
+            u8::from(t.month()),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second(),
+            t.nanosecond()
+        )
+    }
+
+    /// Writes the timestamp the way chrono does: seconds fraction omitted when
+    /// zero, otherwise 3, 6 or 9 digits, whichever is exact.
+    fn format_rfc3339(timestamp: &Timestamp, offset: &str) -> String {
+        let t = timestamp.to_offset(UtcOffset::UTC);
+        let mut out = String::with_capacity(32);
+        if (0..=9999).contains(&t.year()) {
+            let _ = write!(out, "{:04}", t.year());
+        } else {
+            let _ = write!(out, "{:+05}", t.year());
+        }
+        let _ = write!(
+            out,
+            "-{:02}-{:02}T{:02}:{:02}:{:02}",
+            u8::from(t.month()),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second()
+        );
+        let _ = match t.nanosecond() {
+            0 => Ok(()),
+            nanos if nanos % 1_000_000 == 0 => write!(out, ".{:03}", nanos / 1_000_000),
+            nanos if nanos % 1_000 == 0 => write!(out, ".{:06}", nanos / 1_000),
+            nanos => write!(out, ".{:09}", nanos),
+        };
+        out.push_str(offset);
+        out
+    }
+
+    /// Serde format for `Timestamp` fields, matching chrono's `Z`-suffixed output
+    pub(crate) mod serde_rfc3339 {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(timestamp: &Timestamp, s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_str(&format_rfc3339(timestamp, "Z"))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Timestamp, D::Error> {
+            let s = String::deserialize(d)?;
+            parse_rfc3339(&s)
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp '{}'", s)))
+        }
+
+        /// Same format for `Option<Timestamp>` fields
+        pub(crate) mod option {
+            use super::*;
+
+            pub fn serialize<S: Serializer>(
+                timestamp: &Option<Timestamp>,
+                s: S,
+            ) -> Result<S::Ok, S::Error> {
+                match timestamp {
+                    Some(timestamp) => super::serialize(timestamp, s),
+                    None => s.serialize_none(),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                d: D,
+            ) -> Result<Option<Timestamp>, D::Error> {
+                match Option::<String>::deserialize(d)? {
+                    Some(s) => parse_rfc3339(&s).map(Some).ok_or_else(|| {
+                        serde::de::Error::custom(format!("invalid timestamp '{}'", s))
+                    }),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+}
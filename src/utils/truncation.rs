@@ -0,0 +1,110 @@
+use crate::models::run::{Run, RunUpdate};
+use serde_json::Value;
+
+/// `extra` key set to `true` on runs shortened by a `TruncationStrategy`
+pub const TRUNCATED_FLAG: &str = "langsmith:truncated";
+
+/// Compact JSON of `value` cut to `max_chars` characters (plus the
+/// `...[truncated N bytes]` marker), for a run's `inputs_preview`/`outputs_preview`
+pub fn preview(value: &Value, max_chars: usize) -> String {
+    let mut preview = value.to_string();
+    TruncationStrategy::new(max_chars).truncate_string(&mut preview);
+    preview
+}
+
+/// Unit a `TruncationStrategy` measures string values in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationLimit {
+    Chars(usize),
+    /// Cut on a char boundary at or below this many bytes
+    Bytes(usize),
+}
+
+/// How to shorten long strings anywhere in a run's inputs/outputs, e.g. to
+/// retry a run LangSmith rejected as too large. Cut strings end with a
+/// `...[truncated N bytes]` marker giving the number of bytes removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncationStrategy {
+    pub limit: TruncationLimit,
+}
+
+impl TruncationStrategy {
+    /// Cuts strings longer than `max_string_chars` characters
+    pub fn new(max_string_chars: usize) -> Self {
+        Self {
+            limit: TruncationLimit::Chars(max_string_chars),
+        }
+    }
+
+    /// Cuts strings longer than `max_string_bytes` bytes
+    pub fn bytes(max_string_bytes: usize) -> Self {
+        Self {
+            limit: TruncationLimit::Bytes(max_string_bytes),
+        }
+    }
+
+    pub fn truncate_value(&self, mut value: Value) -> Value {
+        self.truncate_in(&mut value);
+        value
+    }
+
+    /// A copy of the run with long strings cut (see `truncate_run_in_place`)
+    pub fn truncate_run(&self, run: &Run) -> Run {
+        let mut run = run.clone();
+        self.truncate_run_in_place(&mut run);
+        run
+    }
+
+    /// Cuts long strings in the run's inputs and outputs. Sets
+    /// `extra["langsmith:truncated"]` and returns true if anything was cut.
+    pub fn truncate_run_in_place(&self, run: &mut Run) -> bool {
+        let mut truncated = self.truncate_in(&mut run.inputs);
+        if let Some(outputs) = run.outputs.as_mut() {
+            truncated |= self.truncate_in(outputs);
+        }
+        if truncated {
+            run.extra.insert(TRUNCATED_FLAG.to_string(), Value::Bool(true));
+        }
+        truncated
+    }
+
+    pub fn truncate_update(&self, update: &RunUpdate) -> RunUpdate {
+        let mut update = update.clone();
+        update.outputs = update.outputs.map(|outputs| self.truncate_value(outputs));
+        update
+    }
+
+    fn truncate_in(&self, value: &mut Value) -> bool {
+        match value {
+            Value::String(s) => self.truncate_string(s),
+            Value::Array(items) => items
+                .iter_mut()
+                .fold(false, |truncated, item| self.truncate_in(item) | truncated),
+            Value::Object(map) => map
+                .values_mut()
+                .fold(false, |truncated, item| self.truncate_in(item) | truncated),
+            _ => false,
+        }
+    }
+
+    fn truncate_string(&self, s: &mut String) -> bool {
+        let cut = match self.limit {
+            TruncationLimit::Chars(max_chars) => match s.char_indices().nth(max_chars) {
+                Some((cut, _)) => cut,
+                None => return false,
+            },
+            TruncationLimit::Bytes(max_bytes) if s.len() > max_bytes => {
+                let mut cut = max_bytes;
+                while !s.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                cut
+            }
+            TruncationLimit::Bytes(_) => return false,
+        };
+        let removed = s.len() - cut;
+        s.truncate(cut);
+        s.push_str(&format!("...[truncated {} bytes]", removed));
+        true
+    }
+}
@@ -1,20 +1,136 @@
 use crate::models::run::Run;
-use crate::error::Result;
+use crate::error::{LangSmithError, Result};
+use crate::utils::serialization::object_or_messages;
+use serde_json::Value;
 
-/// Validates a Run before sending to LangSmith
+/// How validation treats run inputs that aren't a JSON object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonObjectInputs {
+    /// Wrap the value as `{"input": value}`, the same way the serialization layer does
+    #[default]
+    Wrap,
+    /// Reject the run
+    Reject,
+}
+
+/// Options controlling `validate_run_with`. Size and depth limits are off
+/// (`None`) by default.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOptions {
+    pub non_object_inputs: NonObjectInputs,
+    /// Largest allowed serialized size of the inputs, in bytes
+    pub max_input_bytes: Option<usize>,
+    /// Largest allowed serialized size of the outputs, in bytes
+    pub max_output_bytes: Option<usize>,
+    /// Deepest allowed nesting of objects/arrays in inputs and outputs
+    pub max_depth: Option<usize>,
+}
+
+impl ValidationOptions {
+    pub fn with_max_input_bytes(mut self, max: usize) -> Self {
+        self.max_input_bytes = Some(max);
+        self
+    }
+
+    pub fn with_max_output_bytes(mut self, max: usize) -> Self {
+        self.max_output_bytes = Some(max);
+        self
+    }
+
+    pub fn with_max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+}
+
+/// Validates a Run before sending to LangSmith. Non-object inputs are
+/// accepted since `normalize_run` wraps them.
 pub fn validate_run(run: &Run) -> Result<()> {
+    validate_run_with(run, &ValidationOptions::default())
+}
+
+/// Validates a Run before sending to LangSmith using the given options. With
+/// `NonObjectInputs::Wrap`, limits apply to the inputs as `normalize_run`
+/// wraps them.
+pub fn validate_run_with(run: &Run, options: &ValidationOptions) -> Result<()> {
     if run.name.is_empty() {
         return Err(crate::error::LangSmithError::Config(
             "Run name cannot be empty".to_string()
         ));
     }
 
+    let wrapped;
+    let inputs = if run.inputs.is_object() {
+        &run.inputs
+    } else {
+        match options.non_object_inputs {
+            NonObjectInputs::Wrap => {
+                wrapped = object_or_messages(run.inputs.clone(), "input");
+                &wrapped
+            }
+            NonObjectInputs::Reject => {
+                return Err(crate::error::LangSmithError::Config(
+                    "Run inputs must be an object".to_string()
+                ));
+            }
+        }
+    };
+
+    check_limits("inputs", inputs, options.max_input_bytes, options.max_depth)?;
+    if let Some(outputs) = &run.outputs {
+        check_limits("outputs", outputs, options.max_output_bytes, options.max_depth)?;
+    }
+
+    Ok(())
+}
+
+/// Wraps non-object inputs the way the serialization layer does (chat
+/// messages under `"messages"`, anything else under `"input"`), so a run
+/// built by hand is accepted by LangSmith
+pub fn normalize_run(run: &mut Run) {
     if !run.inputs.is_object() {
-        return Err(crate::error::LangSmithError::Config(
-            "Run inputs must be an object".to_string()
-        ));
+        run.inputs = object_or_messages(run.inputs.take(), "input");
+    }
+}
+
+fn check_limits(
+    field: &str,
+    value: &Value,
+    max_bytes: Option<usize>,
+    max_depth: Option<usize>,
+) -> Result<()> {
+    if let Some(max_bytes) = max_bytes {
+        let size = serde_json::to_vec(value)?.len();
+        if size > max_bytes {
+            return Err(LangSmithError::Config(format!(
+                "Run {} are {} bytes, exceeding max_{}_bytes ({})",
+                field,
+                size,
+                field.trim_end_matches('s'),
+                max_bytes
+            )));
+        }
+    }
+
+    if let Some(max_depth) = max_depth {
+        let depth = nesting_depth(value);
+        if depth > max_depth {
+            return Err(LangSmithError::Config(format!(
+                "Run {} are nested {} levels deep, exceeding max_depth ({})",
+                field, depth, max_depth
+            )));
+        }
     }
 
     Ok(())
 }
 
+/// Levels of object/array nesting; scalars are 0 and `{"a": 1}` is 1
+fn nesting_depth(value: &Value) -> usize {
+    let children: Box<dyn Iterator<Item = &Value>> = match value {
+        Value::Object(map) => Box::new(map.values()),
+        Value::Array(items) => Box::new(items.iter()),
+        _ => return 0,
+    };
+    1 + children.map(nesting_depth).max().unwrap_or(0)
+}
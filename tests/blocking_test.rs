@@ -0,0 +1,116 @@
+#![cfg(feature = "blocking")]
+
+use langsmith_rust::client::BlockingLangSmithClient;
+use langsmith_rust::config::Config;
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::models::attachment::Attachment;
+use langsmith_rust::models::run::{Run, RunType, RunUpdate};
+use serde_json::{json, Value};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn blocking_client(server: &MockServer) -> BlockingLangSmithClient {
+    let config = Config::builder()
+        .api_key("test-key")
+        .endpoint(server.uri())
+        .tracing_enabled(true)
+        .build()
+        .unwrap();
+    BlockingLangSmithClient::with_config(config)
+}
+
+#[test]
+fn test_blocking_client_posts_and_patches_runs() {
+    // The mock server needs a runtime; the client itself is used outside of it
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/runs"))
+            .and(header("x-api-key", "test-key"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+    let client = blocking_client(&server);
+
+    let mut run = Run::new("blocking".to_string(), RunType::Tool, json!({"q": "hi"}));
+    client.post_run(&run).unwrap();
+    run.end(json!({"answer": 42}));
+    client.patch_run(run.id, &RunUpdate::from(&run)).unwrap();
+
+    let requests = runtime.block_on(server.received_requests()).unwrap();
+    let post: Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(post["name"], "blocking");
+    assert_eq!(requests[1].url.path(), format!("/runs/{}", run.id));
+    let patch: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(patch["outputs"], json!({"answer": 42}));
+    runtime.block_on(server.verify());
+}
+
+#[test]
+fn test_blocking_client_surfaces_api_errors() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("bad run"))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    let run = Run::new("rejected".to_string(), RunType::Chain, json!({}));
+    match blocking_client(&server).post_run(&run) {
+        Err(LangSmithError::Api { status, body }) => {
+            assert_eq!(status, 400);
+            assert_eq!(body, "bad run");
+        }
+        other => panic!("expected API error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_blocking_client_ensures_project_and_posts_attachments() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/runs"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("session not found"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        server
+    });
+    let mut config = blocking_client(&server).config().clone();
+    config.ensure_project = true;
+    let client = BlockingLangSmithClient::with_config(config);
+
+    let mut run = Run::new("lazy".to_string(), RunType::Chain, json!({}));
+    run.session_name = Some("lazy-project".to_string());
+    client.post_run(&run).unwrap();
+
+    let photo = Attachment::new("photo.png", "image/png", b"PNG-bytes".to_vec());
+    client.post_run_multipart(&run, &[photo]).unwrap();
+
+    let requests = runtime.block_on(server.received_requests()).unwrap();
+    let paths: Vec<&str> = requests.iter().map(|r| r.url.path()).collect();
+    assert_eq!(paths, ["/runs", "/sessions", "/runs", "/runs/multipart"]);
+    let project: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(project["name"], "lazy-project");
+    let multipart = String::from_utf8_lossy(&requests[3].body);
+    assert!(multipart.contains(&format!("name=\"attachment.{}.photo.png\"", run.id)));
+    assert!(multipart.contains("PNG-bytes"));
+}
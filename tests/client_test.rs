@@ -0,0 +1,1214 @@
+use langsmith_rust::client::LangSmithClient;
+use langsmith_rust::config::Config;
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::factories::TracerFactory;
+use langsmith_rust::models::attachment::Attachment;
+use langsmith_rust::models::feedback::Feedback;
+use langsmith_rust::models::query::QueryRunsRequest;
+use langsmith_rust::models::run::{Run, RunType, RunUpdate};
+use langsmith_rust::tracing::batch::BatchScope;
+use langsmith_rust::tracing::context::with_current_trace;
+use langsmith_rust::tracing::decorator::trace_node_batched;
+use langsmith_rust::tracing::tracer::Tracer;
+use langsmith_rust::utils::redaction::KeyRedactor;
+use langsmith_rust::utils::timestamp::{self, TimeDelta};
+use langsmith_rust::utils::truncation::TruncationStrategy;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{any, body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::request_json;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn test_config() -> Config {
+    Config::builder()
+        .tracing_enabled(true)
+        .endpoint("http://127.0.0.1:1")
+        .api_key("test-key")
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_client_builds_with_custom_ca() {
+    let mut config = test_config();
+    config.ca_cert_path = Some(fixture("test_ca.pem"));
+
+    assert!(LangSmithClient::try_with_config(config).is_ok());
+}
+
+#[test]
+fn test_client_builds_with_client_identity() {
+    let mut config = test_config();
+    config.ca_cert_path = Some(fixture("test_ca.pem"));
+    config.client_cert_path = Some(fixture("test_ca.pem"));
+    config.client_key_path = Some(fixture("test_key.pem"));
+
+    assert!(LangSmithClient::try_with_config(config).is_ok());
+}
+
+#[test]
+fn test_client_missing_ca_file_is_config_error() {
+    let mut config = test_config();
+    config.ca_cert_path = Some(fixture("does_not_exist.pem"));
+
+    match LangSmithClient::try_with_config(config) {
+        Err(LangSmithError::Config(msg)) => assert!(msg.contains("CA certificate")),
+        other => panic!("expected config error, got {:?}", other.err()),
+    }
+}
+
+#[tokio::test]
+async fn test_with_config_falls_back_on_unloadable_tls_settings() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.ca_cert_path = Some(fixture("does_not_exist.pem"));
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("FallbackClient".to_string(), RunType::Chain, json!({}));
+    client.post_run(&run).await.unwrap();
+}
+
+#[test]
+fn test_client_cert_without_key_is_config_error() {
+    let mut config = test_config();
+    config.client_cert_path = Some(fixture("test_ca.pem"));
+
+    assert!(matches!(
+        LangSmithClient::try_with_config(config),
+        Err(LangSmithError::Config(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_post_run_retries_server_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.max_retries = 2;
+    config.retry_backoff = Duration::from_millis(10);
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    assert!(client.post_run(&run).await.is_err());
+}
+
+#[tokio::test]
+async fn test_post_run_does_not_retry_client_errors() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.max_retries = 3;
+    config.retry_backoff = Duration::from_millis(10);
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    assert!(client.post_run(&run).await.is_err());
+}
+
+#[tokio::test]
+async fn test_per_run_deadline_stops_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.max_retries = 10;
+    config.retry_backoff = Duration::from_millis(50);
+    config.per_run_deadline = Some(Duration::from_millis(200));
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    let started = Instant::now();
+    assert!(client.post_run(&run).await.is_err());
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let attempts = server.received_requests().await.unwrap().len();
+    assert!(attempts > 1, "expected at least one retry, got {}", attempts);
+    assert!(attempts < 11, "deadline should stop retries before max_retries");
+}
+
+#[tokio::test]
+async fn test_per_run_deadline_cancels_slow_attempt() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.per_run_deadline = Some(Duration::from_millis(200));
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    let started = Instant::now();
+    let result = client.post_run(&run).await;
+
+    assert!(matches!(result, Err(LangSmithError::Timeout(_))));
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_per_run_deadline_shared_by_post_and_patch() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.per_run_deadline = Some(Duration::from_millis(200));
+    let client = LangSmithClient::with_config(config);
+
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    assert!(client.post_run(&run).await.is_err());
+
+    // The post used up the run's budget, so its patch gives up without sending
+    run.end(json!({}));
+    let result = client.patch_run(run.id, &RunUpdate::from(&run)).await;
+    assert!(matches!(result, Err(LangSmithError::Timeout(_))));
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.method.as_str() == "POST"));
+
+    // Another run gets a budget of its own
+    let other = Run::new("Other".to_string(), RunType::Chain, json!({}));
+    assert!(client.patch_run(other.id, &RunUpdate::from(&other)).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_create_feedback_posts_body() {
+    let server = MockServer::start().await;
+    let run_id = uuid::Uuid::new_v4();
+    Mock::given(method("POST"))
+        .and(path("/feedback"))
+        .and(body_partial_json(json!({
+            "run_id": run_id.to_string(),
+            "key": "helpfulness",
+            "value": {"label": "good"},
+            "feedback_source_type": "api",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let feedback = Feedback::new(run_id, "helpfulness")
+        .with_value(json!({"label": "good"}))
+        .with_source_type("api");
+    client.create_feedback(&feedback).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_tracer_feedback_uses_run_id() {
+    let server = MockServer::start().await;
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let tracer = Tracer::new("Test", RunType::Chain, json!({})).with_client(client);
+
+    Mock::given(method("POST"))
+        .and(path("/feedback"))
+        .and(body_partial_json(json!({
+            "run_id": tracer.run_id().to_string(),
+            "key": "correctness",
+            "score": 1.0,
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    tracer.feedback("correctness", 1.0).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_create_dataset_and_example() {
+    let server = MockServer::start().await;
+    let dataset_id = uuid::Uuid::new_v4();
+    let example_id = uuid::Uuid::new_v4();
+    Mock::given(method("POST"))
+        .and(path("/datasets"))
+        .and(body_partial_json(json!({"name": "qa-pairs", "description": "From prod"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": dataset_id.to_string(),
+            "name": "qa-pairs",
+            "description": "From prod",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/examples"))
+        .and(body_partial_json(json!({
+            "dataset_id": dataset_id.to_string(),
+            "inputs": {"question": "What is Rust?"},
+            "outputs": {"answer": "A language"},
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": example_id.to_string(),
+            "dataset_id": dataset_id.to_string(),
+            "inputs": {"question": "What is Rust?"},
+            "outputs": {"answer": "A language"},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let dataset = client.create_dataset("qa-pairs", Some("From prod")).await.unwrap();
+    assert_eq!(dataset.id, dataset_id);
+    assert_eq!(dataset.description.as_deref(), Some("From prod"));
+
+    let example = client
+        .create_example(
+            dataset.id,
+            &json!({"question": "What is Rust?"}),
+            &json!({"answer": "A language"}),
+        )
+        .await
+        .unwrap();
+    assert_eq!(example.id, example_id);
+    assert_eq!(example.dataset_id, dataset_id);
+}
+
+#[tokio::test]
+async fn test_tracer_to_example_uses_run_io() {
+    let server = MockServer::start().await;
+    let dataset_id = uuid::Uuid::new_v4();
+    Mock::given(method("POST"))
+        .and(path("/examples"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "dataset_id": dataset_id.to_string(),
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let mut tracer = Tracer::new("Test", RunType::Chain, json!({"q": "hi", "api_key": "sk"}))
+        .with_client(client)
+        .with_redactor(Arc::new(KeyRedactor::new(["api_key"])));
+    tracer.end(json!({"a": "hello"}));
+
+    tracer.to_example(dataset_id).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        request_json(&requests[0]),
+        json!({
+            "dataset_id": dataset_id.to_string(),
+            "inputs": {"q": "hi", "api_key": "[REDACTED]"},
+            "outputs": {"a": "hello"},
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_query_runs_all_follows_cursors() {
+    let server = MockServer::start().await;
+    let project = uuid::Uuid::new_v4();
+    let runs: Vec<Run> = ["first", "second", "third"]
+        .iter()
+        .map(|name| Run::new(name.to_string(), RunType::Llm, json!({})))
+        .collect();
+
+    Mock::given(method("POST"))
+        .and(path("/runs/query"))
+        .and(body_partial_json(json!({"cursor": "page-2"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "runs": [runs[2]],
+            "cursors": {"next": null, "prev": "page-1"},
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/runs/query"))
+        .and(body_partial_json(json!({
+            "session": [project.to_string()],
+            "run_type": "llm",
+            "limit": 2,
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "runs": [runs[0], runs[1]],
+            "cursors": {"next": "page-2"},
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+    let query = QueryRunsRequest::new()
+        .with_session(project)
+        .with_run_type(RunType::Llm)
+        .with_time_range(timestamp::now() - TimeDelta::hours(1), timestamp::now())
+        .with_limit(2);
+
+    let page = client.query_runs_page(&query).await.unwrap();
+    assert_eq!(page.runs.len(), 2);
+    assert_eq!(page.next_cursor.as_deref(), Some("page-2"));
+
+    let all = client.query_runs_all(query).await.unwrap();
+    let names: Vec<&str> = all.iter().map(|run| run.name.as_str()).collect();
+    assert_eq!(names, ["first", "second", "third"]);
+
+    let requests = server.received_requests().await.unwrap();
+    let body = request_json(&requests[0]);
+    assert!(body["start_time"].is_string());
+    assert!(body["end_time"].is_string());
+    assert!(body.get("cursor").is_none());
+}
+
+#[tokio::test]
+async fn test_tracer_posts_run_once() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let mut tracer = Tracer::new("Test", RunType::Chain, json!({})).with_client(client);
+
+    tracer.post().await.unwrap();
+    assert!(tracer.is_posted());
+    tracer.post().await.unwrap();
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+    // force_post resends the same run
+    tracer.force_post().await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(request_json(&requests[0])["id"], request_json(&requests[1])["id"]);
+}
+
+#[tokio::test]
+async fn test_max_string_bytes_truncates_before_post() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.max_string_bytes = Some(100);
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let mut tracer = Tracer::new("Test", RunType::Chain, json!({"document": "x".repeat(5000)}))
+        .with_client(client);
+
+    tracer.post().await.unwrap();
+    tracer.end(json!({"answer": "y".repeat(300)}));
+    tracer.patch().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let post = request_json(&requests[0]);
+    assert_eq!(
+        post["inputs"]["document"],
+        json!(format!("{}...[truncated 4900 bytes]", "x".repeat(100)))
+    );
+    assert_eq!(post["extra"]["langsmith:truncated"], json!(true));
+    let patch = request_json(&requests[1]);
+    assert!(patch["outputs"]["answer"].as_str().unwrap().ends_with("[truncated 200 bytes]"));
+
+    // The tracer keeps the full values
+    assert_eq!(tracer.run().inputs["document"].as_str().unwrap().len(), 5000);
+}
+
+#[tokio::test]
+async fn test_run_with_attachment_is_posted_as_multipart() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs/multipart"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let png = vec![0x89, b'P', b'N', b'G', 1, 2, 3];
+    let mut tracer = Tracer::new("Vision", RunType::Llm, json!({"prompt": "Describe"}))
+        .with_client(client)
+        .with_attachment(Attachment::new("photo.png", "image/png", png));
+
+    tracer.post().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let request = &requests[0];
+    let content_type = request.headers.get("content-type").unwrap().to_str().unwrap();
+    assert!(content_type.starts_with("multipart/form-data; boundary="));
+    let boundary = content_type.split("boundary=").nth(1).unwrap();
+
+    let body = String::from_utf8_lossy(&request.body);
+    let parts: Vec<&str> = body
+        .split(&format!("--{}", boundary))
+        .filter(|part| part.contains("Content-Disposition"))
+        .collect();
+    assert_eq!(parts.len(), 2);
+
+    let run_id = tracer.run_id();
+    let run_part = parts[0];
+    assert!(run_part.contains(&format!("name=\"post.{}\"", run_id)));
+    assert!(run_part.contains("Content-Type: application/json"));
+    assert!(run_part.contains("\"prompt\":\"Describe\""));
+
+    let attachment_part = parts[1];
+    assert!(attachment_part.contains(&format!("name=\"attachment.{}.photo.png\"", run_id)));
+    assert!(attachment_part.contains("filename=\"photo.png\""));
+    assert!(attachment_part.contains("Content-Type: image/png"));
+    assert!(attachment_part.contains("PNG\u{1}\u{2}\u{3}"));
+}
+
+#[tokio::test]
+async fn test_not_found_response_is_api_error() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(404).set_body_string("run not found"))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+
+    let post_error = client.post_run(&run).await.unwrap_err();
+    match &post_error {
+        LangSmithError::Api { status, body } => {
+            assert_eq!(*status, 404);
+            assert_eq!(body, "run not found");
+        }
+        other => panic!("expected API error, got {:?}", other),
+    }
+    assert_eq!(post_error.to_string(), "HTTP 404: run not found");
+
+    let patch_error = client.patch_run(run.id, &RunUpdate::from(&run)).await.unwrap_err();
+    assert!(matches!(patch_error, LangSmithError::Api { status: 404, .. }));
+
+    // get_run keeps reporting a missing run as NotFound
+    assert!(matches!(client.get_run(run.id).await, Err(LangSmithError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_create_project_treats_conflict_as_success() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/sessions"))
+        .and(body_partial_json(json!({"name": "new-project", "description": "Per tenant"})))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/sessions"))
+        .and(body_partial_json(json!({"name": "existing-project"})))
+        .respond_with(ResponseTemplate::new(409).set_body_string("already exists"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    client.create_project("new-project", Some("Per tenant")).await.unwrap();
+    client.create_project("existing-project", None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_ensure_project_creates_missing_project_and_retries() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("session not found"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/sessions"))
+        .and(body_partial_json(json!({"name": "lazy-project"})))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.ensure_project = true;
+    let client = LangSmithClient::with_config(config);
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    run.session_name = Some("lazy-project".to_string());
+
+    client.post_run(&run).await.unwrap();
+
+    let paths: Vec<String> = server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.url.path().to_string())
+        .collect();
+    assert_eq!(paths, ["/runs", "/sessions", "/runs"]);
+}
+
+#[tokio::test]
+async fn test_upsert_posts_treats_conflict_as_posted() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(409).set_body_string("run already exists"))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+
+    let client = LangSmithClient::with_config(config.clone());
+    assert!(client.post_run(&run).await.is_err());
+
+    config.upsert_posts = true;
+    let client = LangSmithClient::with_config(config);
+    client.post_run(&run).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_run_round_trip() {
+    let server = MockServer::start().await;
+    let mut run = Run::new("Fetched".to_string(), RunType::Llm, json!({"prompt": "hi"}));
+    run.tags = vec!["prod".to_string()];
+    run.end(json!({"completion": "hello"}));
+
+    // The API includes fields we don't model and may return null collections
+    let mut body = serde_json::to_value(&run).unwrap();
+    body["status"] = json!("success");
+    body["child_run_ids"] = json!(null);
+    body["extra"] = json!(null);
+
+    Mock::given(method("GET"))
+        .and(path(format!("/runs/{}", run.id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let fetched = client.get_run(run.id).await.unwrap();
+    assert_eq!(fetched.id, run.id);
+    assert_eq!(fetched.name, "Fetched");
+    assert_eq!(fetched.run_type, RunType::Llm);
+    assert_eq!(fetched.inputs, json!({"prompt": "hi"}));
+    assert_eq!(fetched.outputs, Some(json!({"completion": "hello"})));
+    assert_eq!(fetched.tags, vec!["prod".to_string()]);
+    assert!(fetched.extra.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_run_not_found() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let result = client.get_run(uuid::Uuid::new_v4()).await;
+    assert!(matches!(result, Err(LangSmithError::NotFound(_))));
+}
+
+#[tokio::test]
+async fn test_request_timeout_surfaces_timeout_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.timeout = Duration::from_millis(200);
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    let started = Instant::now();
+    let result = client.post_run(&run).await;
+
+    assert!(matches!(result, Err(LangSmithError::Timeout(_))));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn test_sample_rate_zero_sends_nothing() {
+    let server = MockServer::start().await;
+    Mock::given(wiremock::matchers::any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.sample_rate = 0.0;
+    let client = Arc::new(LangSmithClient::with_config(config));
+
+    let mut root = Tracer::new("Root", RunType::Chain, json!({})).with_client(client);
+    assert!(!root.is_sampled());
+    root.post().await.unwrap();
+
+    // Children of an unsampled root are dropped too, including across contexts
+    let mut child = root.create_child("Child", RunType::Llm, json!({}));
+    child.post().await.unwrap();
+    child.end(json!({"output": "done"}));
+    child.patch().await.unwrap();
+
+    let ctx = root.child_context();
+    assert!(!ctx.sampled);
+    let mut remote = Tracer::new("Remote", RunType::Tool, json!({})).with_parent_context(&ctx);
+    assert!(!remote.is_sampled());
+    remote.post().await.unwrap();
+
+    root.end(json!({}));
+    root.patch().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_sample_rate_one_sends_runs() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.sample_rate = 1.0;
+    let client = Arc::new(LangSmithClient::with_config(config));
+
+    let mut root = Tracer::new("Root", RunType::Chain, json!({})).with_client(client);
+    assert!(root.is_sampled());
+    root.post().await.unwrap();
+    assert!(root.context().is_sampled());
+
+    let mut child = root.create_child("Child", RunType::Llm, json!({}));
+    child.post().await.unwrap();
+    child.end(json!({"output": "done"}));
+    child.patch().await.unwrap();
+
+    root.end(json!({}));
+    root.patch().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_redactor_scrubs_sent_inputs_and_outputs() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .and(body_partial_json(json!({"inputs": {"user": {"password": "[REDACTED]"}}})))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(body_partial_json(json!({"outputs": {"ssn": "[REDACTED]"}})))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let inputs = json!({"user": {"name": "Ada", "password": "hunter2"}});
+
+    let mut root = Tracer::new("Root", RunType::Chain, inputs.clone())
+        .with_client(client)
+        .with_redactor(Arc::new(KeyRedactor::new(["password", "ssn"])));
+    root.post().await.unwrap();
+
+    let mut child = root.create_child("Child", RunType::Tool, inputs.clone());
+    child.post().await.unwrap();
+    child.end(json!({"ssn": "123-45-6789"}));
+    child.patch().await.unwrap();
+
+    // Redaction only applies to what is sent
+    assert_eq!(child.run().inputs, inputs);
+}
+
+#[tokio::test]
+async fn test_seeded_outputs_kept_in_error_patch() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(body_partial_json(json!({
+            "outputs": {"route": "search"},
+            "error": "tool failed",
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+
+    let mut tracer = Tracer::new("Router", RunType::Chain, json!({}))
+        .with_client(client)
+        .with_outputs(json!({"route": "search"}));
+    tracer.set_error("tool failed");
+    tracer.patch().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_large_bodies_are_gzipped() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .and(header("content-encoding", "gzip"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let prompt = "long prompt ".repeat(200);
+    let run = Run::new("Large".to_string(), RunType::Llm, json!({"prompt": prompt}));
+    client.post_run(&run).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let body = request_json(&requests[0]);
+    assert_eq!(body["inputs"]["prompt"], json!(prompt));
+}
+
+#[tokio::test]
+async fn test_small_bodies_are_not_compressed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .and(body_partial_json(json!({"name": "Small"})))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Small".to_string(), RunType::Chain, json!({"q": "hi"}));
+    client.post_run(&run).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests[0].headers.get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_compression_can_be_disabled() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.compression_enabled = false;
+    let client = LangSmithClient::with_config(config);
+
+    let prompt = "long prompt ".repeat(200);
+    let run = Run::new("Large".to_string(), RunType::Llm, json!({"prompt": prompt}));
+    client.post_run(&run).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests[0].headers.get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_custom_http_client_is_used() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .and(header("user-agent", "my-agent/1.0"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let http_client = reqwest::Client::builder()
+        .user_agent("my-agent/1.0")
+        .build()
+        .unwrap();
+
+    let mut tracer = TracerFactory::create_with_http_client(
+        "Custom",
+        RunType::Chain,
+        json!({}),
+        config,
+        http_client,
+    );
+    tracer.post().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_batched_nodes_sent_in_one_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs/batch"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let batch = BatchScope::new().with_client(client);
+
+    for i in 0..10 {
+        let doubled = trace_node_batched(&batch, "double", RunType::Tool, i, |n| async move {
+            Ok(n * 2)
+        })
+        .await
+        .unwrap();
+        assert_eq!(doubled, i * 2);
+    }
+    assert_eq!(batch.len(), 10);
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    batch.flush().await.unwrap();
+    assert!(batch.is_empty());
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let body = request_json(&requests[0]);
+    let posted = body["post"].as_array().unwrap();
+    assert_eq!(posted.len(), 10);
+    assert_eq!(posted[3]["inputs"], json!({"input": 3}));
+    assert_eq!(posted[3]["outputs"], json!({"output": 6}));
+    assert!(posted[3]["end_time"].is_string());
+    assert_eq!(body["patch"], json!([]));
+}
+
+#[tokio::test]
+async fn test_batched_nodes_nest_under_current_trace() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs/batch"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let batch = BatchScope::new().with_client(client);
+    let root = Tracer::new("root", RunType::Chain, json!({}));
+
+    with_current_trace(root.child_context(), async {
+        trace_node_batched(&batch, "outer", RunType::Chain, json!({}), |_| async {
+            trace_node_batched(&batch, "inner", RunType::Tool, json!({}), |_| async { Ok(()) })
+                .await
+        })
+        .await
+    })
+    .await
+    .unwrap();
+    batch.flush().await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let body = request_json(&requests[0]);
+    let posted = body["post"].as_array().unwrap();
+    let named = |name: &str| posted.iter().find(|run| run["name"] == name).unwrap();
+    let (outer, inner) = (named("outer"), named("inner"));
+    assert_eq!(outer["parent_run_id"], json!(root.run_id()));
+    assert_eq!(outer["trace_id"], json!(root.run_id()));
+    assert_eq!(inner["parent_run_id"], outer["id"]);
+    assert_eq!(inner["trace_id"], json!(root.run_id()));
+}
+
+#[tokio::test]
+async fn test_dropped_batch_flushes_pending_runs() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs/batch"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = Arc::new(LangSmithClient::with_config(config));
+
+    {
+        let batch = BatchScope::new().with_client(client);
+        let result: langsmith_rust::Result<()> =
+            trace_node_batched(&batch, "failing", RunType::Tool, json!({}), |_| async {
+                Err(LangSmithError::Other("boom".to_string()))
+            })
+            .await;
+        assert!(result.is_err());
+    }
+
+    for _ in 0..50 {
+        if let Some(request) = server.received_requests().await.unwrap().first() {
+            let body = request_json(request);
+            assert_eq!(body["post"][0]["error"], "Other error: boom");
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("dropped batch was not flushed");
+}
+
+#[tokio::test]
+async fn test_stale_version_patch_is_conflict() {
+    let server = MockServer::start().await;
+    let run = Run::new("Annotated".to_string(), RunType::Chain, json!({}));
+    Mock::given(method("GET"))
+        .and(path(format!("/runs/{}", run.id)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .set_body_json(&run),
+        )
+        .mount(&server)
+        .await;
+    // The run has since moved on to v2
+    Mock::given(method("PATCH"))
+        .and(header("if-match", "\"v2\""))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(header("if-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(412))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let fetched = client.get_run_versioned(run.id).await.unwrap();
+    assert_eq!(fetched.run.id, run.id);
+    assert_eq!(fetched.version.as_deref(), Some("\"v1\""));
+
+    let updates = RunUpdate::from(&fetched.run);
+    let stale = client
+        .patch_run_if_match(run.id, &updates, fetched.version.as_deref().unwrap())
+        .await;
+    assert!(matches!(stale, Err(LangSmithError::Conflict(_))));
+
+    assert!(client.patch_run_if_match(run.id, &updates, "\"v2\"").await.is_ok());
+}
+
+#[tokio::test]
+async fn test_payload_too_large_is_typed_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(413))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Huge".to_string(), RunType::Llm, json!({"prompt": "x".repeat(5000)}));
+    let expected_size = serde_json::to_vec(&run).unwrap().len();
+    match client.post_run(&run).await {
+        Err(LangSmithError::PayloadTooLarge { size }) => assert_eq!(size, expected_size),
+        other => panic!("expected payload too large, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_oversized_run_truncated_and_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(413))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    config.truncation = Some(TruncationStrategy::new(100));
+    let client = LangSmithClient::with_config(config);
+
+    let run = Run::new("Huge".to_string(), RunType::Llm, json!({"prompt": "x".repeat(5000)}));
+    client.post_run(&run).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    let retried = request_json(&requests[1]);
+    let prompt = retried["inputs"]["prompt"].as_str().unwrap();
+    assert_eq!(prompt, format!("{}...[truncated 4900 bytes]", "x".repeat(100)));
+    assert_eq!(retried["extra"]["langsmith:truncated"], json!(true));
+}
+
+#[tokio::test]
+async fn test_post_run_validates_before_sending() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let run = Run::new(String::new(), RunType::Chain, json!({}));
+
+    let client = LangSmithClient::with_config(config.clone());
+    match client.post_run(&run).await {
+        Err(LangSmithError::Config(msg)) => assert!(msg.contains("name")),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    // Opting out sends the run as-is and leaves it to the server
+    config.validate_runs = false;
+    let client = LangSmithClient::with_config(config);
+    client.post_run(&run).await.unwrap();
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_batch_ingest_validates_and_normalizes_runs() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/runs/batch"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+    let valid = Run::new("Valid".to_string(), RunType::Chain, json!("hello"));
+    let invalid = Run::new(String::new(), RunType::Chain, json!({}));
+
+    match client.batch_ingest(std::slice::from_ref(&valid), &[invalid]).await {
+        Err(LangSmithError::Config(msg)) => assert!(msg.contains("name")),
+        other => panic!("expected validation error, got {:?}", other),
+    }
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    client.batch_ingest(&[valid], &[]).await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    let body = request_json(&requests[0]);
+    assert_eq!(body["post"][0]["inputs"], json!({"input": "hello"}));
+}
+
+#[tokio::test]
+async fn test_unprocessable_run_exposes_status_and_body() {
+    let server = MockServer::start().await;
+    let detail = json!({"detail": [{"loc": ["body", "run_type"], "msg": "invalid run type"}]});
+    Mock::given(method("POST"))
+        .and(path("/runs"))
+        .respond_with(ResponseTemplate::new(422).set_body_json(&detail))
+        .mount(&server)
+        .await;
+
+    let mut config = test_config();
+    config.endpoint = server.uri();
+    let client = LangSmithClient::with_config(config);
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+
+    let error = client.post_run(&run).await.unwrap_err();
+    let (status, body) = error.as_api().unwrap();
+    assert_eq!(status, 422);
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body, detail);
+    assert!(LangSmithError::TracingDisabled.as_api().is_none());
+    assert_eq!(error.variant_name(), "Api");
+    assert_eq!(LangSmithError::TracingDisabled.variant_name(), "TracingDisabled");
+}
@@ -0,0 +1,80 @@
+//! Shared helpers for integration tests that need a mock LangSmith server.
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+static SERVER: OnceCell<MockServer> = OnceCell::const_new();
+
+/// Starts a shared mock LangSmith server and points the global config at it.
+/// Tests tell their runs apart by using unique run names.
+pub async fn mock_server() -> &'static MockServer {
+    SERVER
+        .get_or_init(|| async {
+            let server = MockServer::start().await;
+            Mock::given(any())
+                .respond_with(ResponseTemplate::new(200))
+                .mount(&server)
+                .await;
+            std::env::set_var("LANGSMITH_TRACING", "true");
+            std::env::set_var("LANGSMITH_API_KEY", "test-key");
+            std::env::set_var("LANGSMITH_ENDPOINT", server.uri());
+            server
+        })
+        .await
+}
+
+/// Returns the POST body for the named run and the PATCH bodies sent for it.
+pub async fn recorded_run(server: &MockServer, name: &str) -> (Value, Vec<Value>) {
+    let requests = server.received_requests().await.unwrap();
+    let post = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/runs")
+        .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+        .find(|body| body["name"] == json!(name))
+        .unwrap_or_else(|| panic!("no run posted named {}", name));
+
+    let patch_path = format!("/runs/{}", post["id"].as_str().unwrap());
+    let patches = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "PATCH" && r.url.path() == patch_path)
+        .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+        .collect();
+
+    (post, patches)
+}
+
+/// Like `recorded_run`, but waits for the run to be patched first, for runs
+/// whose POST and PATCH are sent in the background.
+pub async fn ended_run(server: &MockServer, name: &str) -> (Value, Vec<Value>) {
+    for _ in 0..50 {
+        let requests = server.received_requests().await.unwrap();
+        let posted = requests.iter().find(|r| {
+            r.method.as_str() == "POST"
+                && r.url.path() == "/runs"
+                && serde_json::from_slice::<Value>(&r.body).unwrap()["name"] == json!(name)
+        });
+        if let Some(post) = posted {
+            let id = serde_json::from_slice::<Value>(&post.body).unwrap()["id"].clone();
+            let patch_path = format!("/runs/{}", id.as_str().unwrap());
+            if requests.iter().any(|r| r.method.as_str() == "PATCH" && r.url.path() == patch_path)
+            {
+                break;
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    recorded_run(server, name).await
+}
+
+/// Parses a request's JSON body, gunzipping it when it was compressed.
+pub fn request_json(request: &wiremock::Request) -> Value {
+    if request.headers.get("content-encoding").is_some() {
+        let decoder = flate2::read::GzDecoder::new(&request.body[..]);
+        serde_json::from_reader(decoder).unwrap()
+    } else {
+        serde_json::from_slice(&request.body).unwrap()
+    }
+}
@@ -0,0 +1,367 @@
+use langsmith_rust::config::Config;
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::models::run::{DottedOrderPrecision, RunType};
+use langsmith_rust::tracing::context::TraceContext;
+use langsmith_rust::tracing::tracer::Tracer;
+use langsmith_rust::utils::timestamp;
+use serde_json::json;
+use std::fs;
+use std::sync::Mutex;
+
+// Tests here change the working directory and environment, so they run one at a time
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Collects warnings logged by the crate
+struct CapturedLogs(Mutex<Vec<String>>);
+
+impl log::Log for CapturedLogs {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == "langsmith"
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) && record.level() == log::Level::Warn {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGS: CapturedLogs = CapturedLogs(Mutex::new(Vec::new()));
+
+#[test]
+fn test_dotenv_can_be_disabled() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let dir = std::env::temp_dir().join(format!("langsmith-dotenv-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join(".env"),
+        "LANGSMITH_API_KEY=from-dotenv\nLANGSMITH_PROJECT=dotenv-project\n",
+    )
+    .unwrap();
+    std::env::set_current_dir(&dir).unwrap();
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+    std::env::remove_var("LANGSMITH_PROJECT");
+
+    let strict = Config::from_env_strict().unwrap();
+    assert_eq!(strict.api_key, "from-env");
+    assert_eq!(strict.project, None);
+
+    std::env::set_var("LANGSMITH_DISABLE_DOTENV", "yes");
+    let disabled = Config::from_env().unwrap();
+    assert_eq!(disabled.project, None);
+
+    // Without the flag, .env fills in unset variables but doesn't override
+    std::env::remove_var("LANGSMITH_DISABLE_DOTENV");
+    let loaded = Config::from_env().unwrap();
+    assert_eq!(loaded.api_key, "from-env");
+    assert_eq!(loaded.project, Some("dotenv-project".to_string()));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_env_selects_project_from_map() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+    std::env::set_var("LANGSMITH_PROJECT", "default-project");
+    std::env::set_var(
+        "LANGSMITH_ENV_PROJECTS",
+        "dev=app-dev, staging=app-staging,broken,prod=app",
+    );
+
+    std::env::set_var("LANGSMITH_ENV", "staging");
+    let staging = Config::from_env_strict().unwrap();
+    assert_eq!(staging.environment.as_deref(), Some("staging"));
+    assert_eq!(staging.project.as_deref(), Some("app-staging"));
+    assert_eq!(staging.env_projects.len(), 3);
+
+    // Environments missing from the map fall back to LANGSMITH_PROJECT
+    std::env::set_var("LANGSMITH_ENV", "qa");
+    let qa = Config::from_env_strict().unwrap();
+    assert_eq!(qa.project.as_deref(), Some("default-project"));
+
+    std::env::remove_var("LANGSMITH_ENV");
+    let unset = Config::from_env_strict().unwrap();
+    assert_eq!(unset.project.as_deref(), Some("default-project"));
+
+    std::env::remove_var("LANGSMITH_ENV_PROJECTS");
+    std::env::remove_var("LANGSMITH_PROJECT");
+}
+
+#[test]
+fn test_builder_config_installed_globally() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let config = Config::builder()
+        .api_key("built-key")
+        .endpoint("https://langsmith.internal")
+        .project("built-project")
+        .tenant_id("tenant-1")
+        .tracing_enabled(true)
+        .build()
+        .unwrap();
+    assert_eq!(config.max_retries, 0);
+    assert_eq!(config.sample_rate, 1.0);
+
+    Config::set(config);
+
+    let global = Config::get().unwrap();
+    assert_eq!(global.api_key, "built-key");
+    assert_eq!(global.endpoint, "https://langsmith.internal");
+    assert_eq!(global.project.as_deref(), Some("built-project"));
+    assert_eq!(global.tenant_id.as_deref(), Some("tenant-1"));
+    assert!(Config::is_tracing_enabled());
+}
+
+#[test]
+fn test_builder_requires_api_key() {
+    assert!(Config::builder().tracing_enabled(true).build().is_err());
+}
+
+#[test]
+fn test_builder_rejects_nan_sample_rate() {
+    match Config::builder().api_key("key").sample_rate(f64::NAN).build() {
+        Err(LangSmithError::Config(msg)) => assert!(msg.contains("sample_rate")),
+        other => panic!("expected config error, got {:?}", other),
+    }
+    let clamped = Config::builder().api_key("key").sample_rate(2.0).build().unwrap();
+    assert_eq!(clamped.sample_rate, 1.0);
+}
+
+#[test]
+fn test_endpoint_is_validated() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+
+    std::env::set_var("LANGSMITH_ENDPOINT", "https://langsmith.example.com:8443/api");
+    let valid = Config::from_env_strict().unwrap();
+    assert_eq!(valid.endpoint, "https://langsmith.example.com:8443/api");
+
+    std::env::set_var("LANGSMITH_ENDPOINT", "https://api.smith.langchain.com/");
+    let normalized = Config::from_env_strict().unwrap();
+    assert_eq!(normalized.endpoint, "https://api.smith.langchain.com");
+
+    std::env::set_var("LANGSMITH_ENDPOINT", "api.smith.langchain.com");
+    match Config::from_env_strict() {
+        Err(LangSmithError::Config(msg)) => assert!(msg.contains("api.smith.langchain.com")),
+        other => panic!("expected config error, got {:?}", other),
+    }
+
+    std::env::set_var("LANGSMITH_ENDPOINT", "ftp://api.smith.langchain.com");
+    assert!(matches!(Config::from_env_strict(), Err(LangSmithError::Config(_))));
+
+    std::env::remove_var("LANGSMITH_ENDPOINT");
+    assert!(Config::builder().api_key("key").endpoint("localhost:1984").build().is_err());
+}
+
+#[test]
+fn test_from_file_with_env_overrides() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    std::env::set_var("LANGSMITH_DISABLE_DOTENV", "true");
+    for name in ["LANGSMITH_API_KEY", "LANGSMITH_ENDPOINT", "LANGSMITH_PROJECT", "LANGSMITH_ENV"] {
+        std::env::remove_var(name);
+    }
+    std::env::remove_var("LANGSMITH_ENV_PROJECTS");
+
+    let dir = std::env::temp_dir().join(format!("langsmith-file-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("langsmith.toml");
+    fs::write(
+        &path,
+        r#"
+tracing_enabled = true
+endpoint = "https://langsmith.example.com/"
+api_key = "from-file"
+project = "file-project"
+environment = "staging"
+timeout_ms = 2500
+sample_rate = 0.25
+
+[env_projects]
+staging = "app-staging"
+prod = "app,prod"
+"#,
+    )
+    .unwrap();
+
+    let config = Config::from_file(&path).unwrap();
+    assert!(config.tracing_enabled);
+    assert_eq!(config.endpoint, "https://langsmith.example.com");
+    assert_eq!(config.api_key, "from-file");
+    assert_eq!(config.project.as_deref(), Some("app-staging"));
+    // Table entries are used as written, not re-parsed as `env=project` pairs
+    assert_eq!(config.env_projects["prod"], "app,prod");
+    assert_eq!(config.timeout, std::time::Duration::from_millis(2500));
+    assert_eq!(config.sample_rate, 0.25);
+
+    // Environment variables win over the file
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+    std::env::set_var("LANGSMITH_ENV", "dev");
+    let overridden = Config::from_file(&path).unwrap();
+    assert_eq!(overridden.api_key, "from-env");
+    assert_eq!(overridden.project.as_deref(), Some("file-project"));
+
+    let json_path = dir.join("langsmith.json");
+    fs::write(&json_path, r#"{"api_key": "from-json", "endpoint": "localhost:1984"}"#).unwrap();
+    assert!(matches!(Config::from_file(&json_path), Err(LangSmithError::Config(_))));
+
+    fs::write(&path, "api_key = \"k\"\ntimeout = 5\n").unwrap();
+    match Config::from_file(&path) {
+        Err(LangSmithError::Config(msg)) => assert!(msg.contains("timeout")),
+        other => panic!("expected config error, got {:?}", other),
+    }
+
+    std::env::remove_var("LANGSMITH_ENV");
+    std::env::remove_var("LANGSMITH_DISABLE_DOTENV");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_tracing_flag_accepts_common_forms() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+
+    for (value, expected) in [
+        ("true", true),
+        ("TRUE", true),
+        ("1", true),
+        ("yes", true),
+        ("Yes", true),
+        ("on", true),
+        ("ON", true),
+        ("false", false),
+        ("0", false),
+        ("no", false),
+        ("off", false),
+        ("Off", false),
+    ] {
+        std::env::set_var("LANGSMITH_TRACING", value);
+        let config = Config::from_env_strict().unwrap();
+        assert_eq!(config.tracing_enabled, expected, "LANGSMITH_TRACING={}", value);
+    }
+
+    let _ = log::set_logger(&LOGS);
+    log::set_max_level(log::LevelFilter::Warn);
+    std::env::set_var("LANGSMITH_TRACING", "enabled");
+    let config = Config::from_env_strict().unwrap();
+    assert!(!config.tracing_enabled);
+    assert!(LOGS
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|msg| msg.contains("'enabled'") && msg.contains("LANGSMITH_TRACING")));
+
+    std::env::remove_var("LANGSMITH_TRACING");
+}
+
+#[test]
+fn test_dotted_order_precision_from_env() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+
+    std::env::remove_var("LANGSMITH_DOTTED_ORDER_PRECISION");
+    let config = Config::from_env_strict().unwrap();
+    assert_eq!(config.dotted_order_precision, DottedOrderPrecision::Micros);
+
+    std::env::set_var("LANGSMITH_DOTTED_ORDER_PRECISION", "Nanos");
+    let config = Config::from_env_strict().unwrap();
+    assert_eq!(config.dotted_order_precision, DottedOrderPrecision::Nanos);
+
+    std::env::set_var("LANGSMITH_DOTTED_ORDER_PRECISION", "millis");
+    let config = Config::from_env_strict().unwrap();
+    assert_eq!(config.dotted_order_precision, DottedOrderPrecision::Micros);
+
+    std::env::remove_var("LANGSMITH_DOTTED_ORDER_PRECISION");
+}
+
+#[test]
+fn test_project_id_from_env() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    std::env::set_var("LANGSMITH_API_KEY", "from-env");
+    let project_id = uuid::Uuid::new_v4();
+
+    std::env::set_var("LANGSMITH_PROJECT_ID", project_id.to_string());
+    let config = Config::from_env_strict().unwrap();
+    assert_eq!(config.project_id, Some(project_id));
+
+    log::set_logger(&LOGS).ok();
+    log::set_max_level(log::LevelFilter::Warn);
+    std::env::set_var("LANGSMITH_PROJECT_ID", "not-a-uuid");
+    assert!(Config::from_env_strict().unwrap().project_id.is_none());
+    assert!(LOGS
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|msg| msg.contains("'not-a-uuid'") && msg.contains("LANGSMITH_PROJECT_ID")));
+
+    std::env::remove_var("LANGSMITH_PROJECT_ID");
+}
+
+#[test]
+fn test_configured_project_id_dropped_for_project_overrides() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let project_id = uuid::Uuid::new_v4();
+    let config = Config::builder()
+        .api_key("built-key")
+        .project("default-project")
+        .project_id(project_id)
+        .build()
+        .unwrap();
+    Config::set(config);
+
+    let default = Tracer::new("Default", RunType::Chain, json!({}));
+    assert_eq!(default.session_name().map(String::as_str), Some("default-project"));
+    assert_eq!(default.session_id(), Some(&project_id.to_string()));
+    // Naming the default project again keeps its id
+    let same = Tracer::new("Same", RunType::Chain, json!({})).with_project("default-project");
+    assert_eq!(same.session_id(), Some(&project_id.to_string()));
+
+    let other = Tracer::new("Other", RunType::Chain, json!({})).with_project("other");
+    assert_eq!(other.session_name().map(String::as_str), Some("other"));
+    assert!(other.session_id().is_none());
+    assert!(other
+        .create_child("Child", RunType::Tool, json!({}))
+        .session_id()
+        .is_none());
+
+    let other_id = uuid::Uuid::new_v4();
+    let paired = Tracer::new("Paired", RunType::Chain, json!({}))
+        .with_project("other")
+        .with_project_id(other_id);
+    assert_eq!(paired.session_id(), Some(&other_id.to_string()));
+
+    // A context naming another project replaces the configured id too
+    let context = TraceContext::new(uuid::Uuid::new_v4()).with_session_name("remote".to_string());
+    let remote = Tracer::new("Remote", RunType::Tool, json!({})).with_parent_context(&context);
+    assert_eq!(remote.session_name().map(String::as_str), Some("remote"));
+    assert!(remote.session_id().is_none());
+}
+
+#[test]
+fn test_nanosecond_precision_kept_for_children() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let config = Config::builder()
+        .api_key("built-key")
+        .dotted_order_precision(DottedOrderPrecision::Nanos)
+        .build()
+        .unwrap();
+    Config::set(config);
+
+    let root = Tracer::new("Root", RunType::Chain, json!({}));
+    let first = root.create_child("First", RunType::Tool, json!({}));
+    let second = root.create_child("Second", RunType::Tool, json!({}));
+    let time_part = |tracer: &Tracer| {
+        let segment = tracer.dotted_order().unwrap().rsplit('.').next().unwrap();
+        segment.split_once('Z').unwrap().0.to_string()
+    };
+
+    // The first child's segment is its start time with all nine digits
+    let expected = timestamp::dotted_order_time_nanos(&first.run().start_time);
+    assert_eq!(time_part(&first), expected.trim_end_matches('Z'));
+    assert_eq!(time_part(&first).len(), "YYYYMMDDTHHMMSSfffffffff".len());
+    // Siblings created together are ordered by nanosecond ticks
+    assert!(time_part(&second) > time_part(&first));
+}
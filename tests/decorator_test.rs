@@ -0,0 +1,346 @@
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::models::metrics::Metrics;
+use langsmith_rust::models::run::RunType;
+use langsmith_rust::strategies::serialization_strategy::DefaultSerializationStrategy;
+use langsmith_rust::tracing::decorator::{
+    trace_future, trace_llm_node, trace_map, trace_map_concurrent, trace_node, trace_node_with,
+    TraceOptions,
+};
+use langsmith_rust::trace;
+use langsmith_rust::tracing::current_trace;
+use serde_json::json;
+use std::sync::Arc;
+
+mod common;
+use common::{mock_server, recorded_run};
+
+#[tokio::test]
+async fn test_trace_future_captures_inputs_and_output() {
+    let server = mock_server().await;
+
+    let fut = async { Ok::<_, LangSmithError>(42) };
+    let result = trace_future("trace_future_ok", RunType::Chain, json!({"x": 1}), fut).await;
+
+    assert_eq!(result.unwrap(), 42);
+    let (post, patches) = recorded_run(server, "trace_future_ok").await;
+    assert_eq!(post["inputs"], json!({"x": 1}));
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0]["outputs"], json!({"output": 42}));
+    assert!(patches[0]["end_time"].is_string());
+}
+
+#[tokio::test]
+async fn test_trace_future_records_error() {
+    let server = mock_server().await;
+
+    let fut = async { Err::<u32, _>(LangSmithError::Other("boom".to_string())) };
+    let result = trace_future("trace_future_err", RunType::Tool, "input", fut).await;
+
+    assert!(result.is_err());
+    let (post, patches) = recorded_run(server, "trace_future_err").await;
+    assert_eq!(post["inputs"], json!({"input": "input"}));
+    assert_eq!(patches[0]["error"], json!("Other error: boom"));
+}
+
+#[tokio::test]
+async fn test_trace_node_records_error_type() {
+    let server = mock_server().await;
+
+    let result = trace_node("trace_node_error_type", RunType::Tool, json!({}), |_| async {
+        Err::<u32, _>(LangSmithError::NotFound("tool".to_string()))
+    })
+    .await;
+
+    assert!(matches!(result, Err(LangSmithError::NotFound(_))));
+    let (_, patches) = recorded_run(server, "trace_node_error_type").await;
+    assert_eq!(patches[0]["error"], "Not found: tool");
+    assert_eq!(
+        patches[0]["extra"]["error_type"],
+        "langsmith_rust::error::LangSmithError::NotFound"
+    );
+    assert_eq!(patches[0]["extra"]["error_kind"], "other");
+    assert!(patches[0]["extra"].get("stacktrace").is_none());
+}
+
+#[tokio::test]
+async fn test_trace_node_with_tags_and_metadata() {
+    let server = mock_server().await;
+
+    let options = TraceOptions::new()
+        .with_tags(vec!["production".to_string(), "v2".to_string()])
+        .with_metadata("team", json!("search"));
+    let result = trace_node_with(
+        "trace_node_with_tags",
+        RunType::Chain,
+        options,
+        json!({"q": "rust"}),
+        |inputs| async move { Ok::<_, LangSmithError>(inputs["q"].clone()) },
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), json!("rust"));
+    let (post, _) = recorded_run(server, "trace_node_with_tags").await;
+    assert_eq!(post["tags"], json!(["production", "v2"]));
+    assert_eq!(post["extra"]["team"], json!("search"));
+}
+
+#[tokio::test]
+async fn test_trace_node_with_custom_serialization_keys() {
+    let server = mock_server().await;
+
+    let strategy =
+        DefaultSerializationStrategy::with_keys("query".to_string(), "answer".to_string());
+    let options = TraceOptions::new().with_serialization(Arc::new(strategy));
+    let result = trace_node_with(
+        "trace_node_custom_keys",
+        RunType::Tool,
+        options,
+        "rust".to_string(),
+        |query| async move { Ok::<_, LangSmithError>(query.len()) },
+    )
+    .await;
+
+    assert_eq!(result.unwrap(), 4);
+    let (post, patches) = recorded_run(server, "trace_node_custom_keys").await;
+    assert_eq!(post["inputs"], json!({"query": "rust"}));
+    assert_eq!(patches[0]["outputs"], json!({"answer": 4}));
+}
+
+#[tokio::test]
+async fn test_trace_llm_node_records_metrics() {
+    let server = mock_server().await;
+
+    let result = trace_llm_node("trace_llm_node_metrics", json!({"prompt": "hi"}), |_| async {
+        let metrics = Metrics::new().with_tokens(12, 30).with_costs(0.5, 0.25);
+        Ok::<_, LangSmithError>(("hello there".to_string(), metrics))
+    })
+    .await;
+
+    assert_eq!(result.unwrap(), "hello there");
+    let (post, patches) = recorded_run(server, "trace_llm_node_metrics").await;
+    assert_eq!(post["run_type"], "llm");
+    assert_eq!(patches[0]["outputs"], json!({"output": "hello there"}));
+    assert_eq!(patches[0]["prompt_tokens"], 12);
+    assert_eq!(patches[0]["completion_tokens"], 30);
+    assert_eq!(patches[0]["total_tokens"], 42);
+    assert_eq!(patches[0]["total_cost"], 0.75);
+}
+
+/// A value whose serialization always fails
+struct Unserializable(u32);
+
+impl serde::Serialize for Unserializable {
+    fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("not serializable"))
+    }
+}
+
+#[tokio::test]
+async fn test_trace_node_returns_output_that_fails_to_serialize() {
+    let server = mock_server().await;
+
+    let result = trace_node(
+        "trace_node_unserializable",
+        RunType::Chain,
+        json!({}),
+        |_| async { Ok::<_, LangSmithError>(Unserializable(7)) },
+    )
+    .await;
+
+    assert_eq!(result.unwrap().0, 7);
+    let (_, patches) = recorded_run(server, "trace_node_unserializable").await;
+    assert_eq!(patches[0]["outputs"], json!({}));
+    assert!(patches[0]["error"].is_null());
+    assert!(patches[0]["end_time"].is_string());
+}
+
+#[tokio::test]
+async fn test_trace_map_creates_child_per_item() {
+    let server = mock_server().await;
+
+    let results = trace_map("trace_map_ok", vec!["a", "b", "c"], |item| async move {
+        Ok::<_, LangSmithError>(item.to_uppercase())
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(results, vec!["A", "B", "C"]);
+    let (parent, parent_patches) = recorded_run(server, "trace_map_ok").await;
+    assert_eq!(parent["inputs"], json!({"count": 3}));
+    assert_eq!(parent_patches[0]["outputs"], json!({"results": ["A", "B", "C"]}));
+
+    for (index, item) in ["a", "b", "c"].iter().enumerate() {
+        let (child, patches) = recorded_run(server, &format!("trace_map_ok[{}]", index)).await;
+        assert_eq!(child["parent_run_id"], parent["id"]);
+        assert_eq!(child["trace_id"], parent["id"]);
+        assert_eq!(child["inputs"], json!({"index": index, "item": item}));
+        assert_eq!(patches[0]["outputs"], json!({"output": item.to_uppercase()}));
+    }
+}
+
+#[tokio::test]
+async fn test_trace_map_concurrent_reports_failure() {
+    let server = mock_server().await;
+
+    let result = trace_map_concurrent("trace_map_err", vec![1, 2, 3], 2, |n| async move {
+        if n == 2 {
+            Err(LangSmithError::Other("bad item".to_string()))
+        } else {
+            Ok(n * 10)
+        }
+    })
+    .await;
+
+    assert!(matches!(result, Err(LangSmithError::Other(_))));
+    let (_, parent_patches) = recorded_run(server, "trace_map_err").await;
+    assert_eq!(parent_patches[0]["error"], "Other error: bad item");
+    let (_, failed) = recorded_run(server, "trace_map_err[1]").await;
+    assert_eq!(failed[0]["error"], "Other error: bad item");
+    let (_, ok) = recorded_run(server, "trace_map_err[2]").await;
+    assert_eq!(ok[0]["outputs"], json!({"output": 30}));
+}
+
+#[tokio::test]
+async fn test_trace_map_ends_parent_when_serialization_fails() {
+    let server = mock_server().await;
+
+    let results = trace_map("trace_map_bad_output", vec![1, 2], |n| async move {
+        Ok::<_, LangSmithError>(Unserializable(n))
+    })
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 2);
+    let (_, patches) = recorded_run(server, "trace_map_bad_output").await;
+    assert!(patches[0]["end_time"].is_string());
+    assert!(patches[0]["error"].as_str().unwrap().contains("not serializable"));
+
+    let result = trace_map("trace_map_bad_item", vec![Unserializable(1)], |item| async move {
+        Ok::<_, LangSmithError>(item.0)
+    })
+    .await;
+    assert!(matches!(result, Err(LangSmithError::Serialization(_))));
+    let (_, patches) = recorded_run(server, "trace_map_bad_item").await;
+    assert!(patches[0]["end_time"].is_string());
+    assert!(patches[0]["error"].as_str().unwrap().contains("not serializable"));
+}
+
+#[tokio::test]
+async fn test_nested_trace_node_uses_current_trace_as_parent() {
+    let server = mock_server().await;
+
+    let result = trace_node("current_trace_outer", RunType::Chain, json!({}), |_| async {
+        trace_node("current_trace_inner", RunType::Tool, json!({}), |_| async {
+            Ok::<_, LangSmithError>(7)
+        })
+        .await
+    })
+    .await;
+
+    assert_eq!(result.unwrap(), 7);
+    let (outer, _) = recorded_run(server, "current_trace_outer").await;
+    let (inner, _) = recorded_run(server, "current_trace_inner").await;
+    assert!(outer["parent_run_id"].is_null());
+    assert_eq!(inner["parent_run_id"], outer["id"]);
+    assert_eq!(inner["trace_id"], outer["trace_id"]);
+    assert!(current_trace().is_none());
+}
+
+#[trace(run_type = "llm")]
+async fn macro_summarize(text: String, max_words: usize) -> langsmith_rust::Result<String> {
+    let words: Vec<&str> = text.split_whitespace().take(max_words).collect();
+    Ok(words.join(" "))
+}
+
+struct Retriever {
+    prefix: String,
+}
+
+impl Retriever {
+    #[trace(name = "macro_retrieve", run_type = "retriever")]
+    async fn retrieve(&self, query: &str) -> langsmith_rust::Result<Vec<String>> {
+        if query.is_empty() {
+            return Err(LangSmithError::Other("empty query".to_string()));
+        }
+        Ok(vec![format!("{}{}", self.prefix, query)])
+    }
+}
+
+#[tokio::test]
+async fn test_trace_macro_defaults_name_to_function() {
+    let server = mock_server().await;
+
+    let summary = macro_summarize("Rust is a systems language".to_string(), 3).await;
+
+    assert_eq!(summary.unwrap(), "Rust is a");
+    let (post, patches) = recorded_run(server, "macro_summarize").await;
+    assert_eq!(post["run_type"], "llm");
+    assert_eq!(post["inputs"], json!({"text": "Rust is a systems language", "max_words": 3}));
+    assert_eq!(patches[0]["outputs"], json!({"output": "Rust is a"}));
+}
+
+#[tokio::test]
+async fn test_trace_macro_on_method_skips_self() {
+    let server = mock_server().await;
+    let retriever = Retriever {
+        prefix: "doc:".to_string(),
+    };
+
+    assert_eq!(retriever.retrieve("rust").await.unwrap(), vec!["doc:rust"]);
+    assert!(retriever.retrieve("").await.is_err());
+
+    let requests = server.received_requests().await.unwrap();
+    let posts: Vec<serde_json::Value> = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/runs")
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .filter(|body: &serde_json::Value| body["name"] == "macro_retrieve")
+        .collect();
+    assert_eq!(posts.len(), 2);
+    assert_eq!(posts[0]["run_type"], "retriever");
+    assert_eq!(posts[0]["inputs"], json!({"query": "rust"}));
+
+    let (_, patches) = recorded_run(server, "macro_retrieve").await;
+    assert_eq!(patches[0]["outputs"], json!({"output": ["doc:rust"]}));
+}
+
+#[trace]
+async fn macro_with_inputs_arg(inputs: Vec<String>) -> langsmith_rust::Result<usize> {
+    Ok(inputs.len())
+}
+
+#[tokio::test]
+async fn test_trace_macro_argument_named_inputs() {
+    let server = mock_server().await;
+
+    let count = macro_with_inputs_arg(vec!["a".to_string(), "b".to_string()]).await;
+
+    assert_eq!(count.unwrap(), 2);
+    let (post, _) = recorded_run(server, "macro_with_inputs_arg").await;
+    assert_eq!(post["inputs"], json!({"inputs": ["a", "b"]}));
+}
+
+#[trace]
+async fn macro_with_unserializable_arg(
+    _handle: Unserializable,
+    label: &str,
+) -> langsmith_rust::Result<String> {
+    Ok(label.to_uppercase())
+}
+
+#[tokio::test]
+async fn test_trace_macro_runs_body_when_an_argument_fails_to_serialize() {
+    let server = mock_server().await;
+
+    let result = macro_with_unserializable_arg(Unserializable(7), "ok").await;
+
+    assert_eq!(result.unwrap(), "OK");
+    let (post, patches) = recorded_run(server, "macro_with_unserializable_arg").await;
+    assert_eq!(post["inputs"], json!({"_handle": "<unserializable>", "label": "ok"}));
+    assert_eq!(patches[0]["outputs"], json!({"output": "OK"}));
+}
+
+#[test]
+fn test_trace_macro_rejects_unsupported_functions() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/trace_*.rs");
+}
@@ -0,0 +1,113 @@
+use langsmith_rust::models::metrics::Metrics;
+use langsmith_rust::models::run::RunType;
+use langsmith_rust::observability::{LangSmithObserver, Observer};
+use langsmith_rust::tracing::decorator::trace_llm_node;
+use langsmith_rust::tracing::scope::RunScope;
+use serde::{Serialize, Serializer};
+use serde_json::json;
+
+/// Fails the test if anything tries to serialize it
+struct PanicsOnSerialize;
+
+impl Serialize for PanicsOnSerialize {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        panic!("inputs were serialized while tracing is disabled");
+    }
+}
+
+fn disable_tracing() {
+    std::env::set_var("LANGSMITH_TRACING", "false");
+    std::env::set_var("LANGSMITH_API_KEY", "test-key");
+}
+
+#[tokio::test]
+async fn test_disabled_scope_skips_serialization() {
+    disable_tracing();
+
+    let mut root = RunScope::root("root", RunType::Chain, PanicsOnSerialize).unwrap();
+    assert!(!root.is_enabled());
+    root.post_start().await.unwrap();
+
+    let mut child = root.child("child", RunType::Tool, PanicsOnSerialize).unwrap();
+    assert!(!child.is_enabled());
+    assert_eq!(child.tracer().parent_run_id(), Some(root.tracer().run_id()));
+    child.post_start().await.unwrap();
+    child.end_ok(PanicsOnSerialize).await.unwrap();
+
+    root.end_error("boom", Some(json!({}))).await.unwrap();
+}
+
+#[test]
+fn test_disabled_scope_children_stay_disabled() {
+    disable_tracing();
+
+    let root = RunScope::root_value("root", RunType::Chain, json!({"q": "hi"}));
+    assert!(!root.is_enabled());
+    // Placeholders are never sent, even if a context escapes to enabled code
+    assert!(!root.tracer().is_sampled());
+    let child = root.child_value("child", RunType::Llm, json!({}));
+    assert!(!child.is_enabled());
+    assert!(!child.tracer().child_context().sampled);
+}
+
+#[langsmith_rust::trace(run_type = "tool")]
+async fn traced_double(value: u32, _marker: PanicsOnSerialize) -> langsmith_rust::Result<u32> {
+    Ok(value * 2)
+}
+
+#[tokio::test]
+async fn test_trace_macro_runs_body_when_disabled() {
+    disable_tracing();
+
+    assert_eq!(traced_double(21, PanicsOnSerialize).await.unwrap(), 42);
+}
+
+#[tokio::test]
+async fn test_trace_llm_node_returns_output_when_disabled() {
+    disable_tracing();
+
+    let output = trace_llm_node("llm", PanicsOnSerialize, |_| async {
+        Ok::<_, langsmith_rust::LangSmithError>(("answer", Metrics::new().with_tokens(3, 4)))
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(output, "answer");
+}
+
+#[test]
+fn test_observer_pairs_start_and_end_into_one_run() {
+    disable_tracing();
+    let observer = LangSmithObserver::new();
+
+    let run_id = observer.start_run("retrieve", &json!({"query": "rust"}));
+    observer.on_node_start("summarize", &json!({"text": "..."}));
+    assert_eq!(observer.in_flight(), 2);
+
+    let run = observer
+        .end_run("retrieve", &json!({"docs": ["a", "b"]}), None)
+        .unwrap();
+    assert_eq!(run.id, run_id);
+    assert_eq!(run.name, "retrieve");
+    assert_eq!(run.inputs, json!({"query": "rust"}));
+    assert_eq!(run.outputs, Some(json!({"docs": ["a", "b"]})));
+    assert!(run.end_time.is_some());
+    assert!(observer.end_run("retrieve", &json!({}), None).is_none());
+
+    observer.on_node_error("summarize", "model unavailable");
+    assert_eq!(observer.in_flight(), 0);
+}
+
+#[test]
+fn test_observer_ends_nested_runs_of_same_node_last_first() {
+    disable_tracing();
+    let observer = LangSmithObserver::new();
+
+    let outer = observer.start_run("agent", &json!({"depth": 0}));
+    let inner = observer.start_run("agent", &json!({"depth": 1}));
+
+    let first = observer.end_run("agent", &json!({}), Some("too deep")).unwrap();
+    assert_eq!(first.id, inner);
+    assert_eq!(first.error.as_deref(), Some("too deep"));
+    assert_eq!(observer.end_run("agent", &json!({}), None).unwrap().id, outer);
+}
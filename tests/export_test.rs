@@ -0,0 +1,68 @@
+use langsmith_rust::export::{run_to_json, to_tree_json, write_jsonl, TimeFormat};
+use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::utils::timestamp::{unix_micros, Timestamp};
+use serde_json::{json, Value};
+
+fn unix_millis(timestamp: &Timestamp) -> i64 {
+    unix_micros(timestamp) / 1_000
+}
+
+fn ended_run(name: &str) -> Run {
+    let mut run = Run::new(name.to_string(), RunType::Chain, json!({"q": "hi"}));
+    run.end(json!({"a": "hello"}));
+    run
+}
+
+#[test]
+fn test_default_export_uses_rfc3339() {
+    let run = ended_run("Root");
+    let value = run_to_json(&run, TimeFormat::default()).unwrap();
+
+    let api_value = serde_json::to_value(&run).unwrap();
+    assert_eq!(value["start_time"], api_value["start_time"]);
+    assert!(value["start_time"].as_str().unwrap().ends_with('Z'));
+    assert!(value["end_time"].is_string());
+}
+
+#[test]
+fn test_jsonl_export_with_epoch_millis() {
+    let first = ended_run("First");
+    let second = Run::new("Second".to_string(), RunType::Tool, json!({}));
+
+    let mut buffer = Vec::new();
+    write_jsonl(&mut buffer, &[first.clone(), second.clone()], TimeFormat::EpochMillis).unwrap();
+
+    let lines: Vec<Value> = String::from_utf8(buffer)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["start_time"], json!(unix_millis(&first.start_time)));
+    assert_eq!(lines[0]["end_time"], json!(unix_millis(&first.end_time.unwrap())));
+    assert!(lines[1]["start_time"].is_i64());
+    assert!(lines[1].get("end_time").is_none());
+}
+
+#[test]
+fn test_tree_export_nests_children_with_epoch_millis() {
+    let mut root = ended_run("Root");
+    root.trace_id = Some(root.id);
+    let mut child = ended_run("Child");
+    child.parent_run_id = Some(root.id);
+    let mut grandchild = ended_run("Grandchild");
+    grandchild.parent_run_id = Some(child.id);
+
+    let tree = to_tree_json(&[grandchild, root, child], TimeFormat::EpochMillis).unwrap();
+
+    let roots = tree.as_array().unwrap();
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0]["name"], "Root");
+    assert!(roots[0]["start_time"].is_i64());
+
+    let child = &roots[0]["child_runs"][0];
+    assert_eq!(child["name"], "Child");
+    assert!(child["end_time"].is_i64());
+    assert_eq!(child["child_runs"][0]["name"], "Grandchild");
+    assert_eq!(child["child_runs"][0]["child_runs"], json!([]));
+}
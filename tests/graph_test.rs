@@ -0,0 +1,239 @@
+use langsmith_rust::client::{InMemorySink, LangSmithClient};
+use langsmith_rust::models::run::RunType;
+use langsmith_rust::tracing::graph::GraphTrace;
+use langsmith_rust::tracing::registry::TraceRegistry;
+use langsmith_rust::tracing::run_tree::RunTree;
+use langsmith_rust::tracing::tracer::Tracer;
+use serde_json::json;
+use std::sync::Arc;
+
+mod common;
+use common::{mock_server, recorded_run, request_json};
+
+#[tokio::test]
+async fn test_registry_shares_root_across_tasks() {
+    mock_server().await;
+
+    let graph = GraphTrace::start_root(json!({"path": "/chat"}), None)
+        .await
+        .unwrap();
+    let root_id = graph.root_tracer().run_id();
+    TraceRegistry::register("request-1", graph);
+
+    let handle = tokio::spawn(async move {
+        let graph = TraceRegistry::get("request-1").expect("root registered");
+        let step = graph
+            .start_node_iteration("handler", json!({}))
+            .await
+            .unwrap();
+        step.tracer().parent_run_id()
+    });
+
+    assert_eq!(handle.await.unwrap(), Some(root_id));
+    assert_eq!(
+        TraceRegistry::context("request-1").map(|c| c.trace_id),
+        Some(root_id)
+    );
+
+    let graph = TraceRegistry::remove("request-1").unwrap();
+    assert!(TraceRegistry::get("request-1").is_none());
+    let graph = Arc::try_unwrap(graph).ok().expect("no other handles");
+    graph.end_root(json!({"status": 200})).await.unwrap();
+}
+
+#[test]
+fn test_registry_missing_id() {
+    assert!(TraceRegistry::get("missing").is_none());
+    assert!(TraceRegistry::context("missing").is_none());
+}
+
+#[tokio::test]
+async fn test_numbered_iterations_suffix_repeated_nodes() {
+    mock_server().await;
+
+    let graph = GraphTrace::start_root(json!({}), None)
+        .await
+        .unwrap()
+        .with_numbered_iterations();
+
+    let mut names = Vec::new();
+    for _ in 0..3 {
+        let step = graph.start_node_iteration("chatbot", json!({})).await.unwrap();
+        assert_eq!(step.tracer().metadata().get("base_name"), Some(&json!("chatbot")));
+        names.push(step.tracer().name().to_string());
+    }
+    let tools = graph.start_node_iteration("tools", json!({})).await.unwrap();
+
+    assert_eq!(names, vec!["chatbot", "chatbot #2", "chatbot #3"]);
+    assert_eq!(tools.tracer().name(), "tools");
+}
+
+#[tokio::test]
+async fn test_builder_sets_root_name_and_run_type() {
+    let server = mock_server().await;
+
+    let graph = GraphTrace::builder()
+        .name("MyPipeline")
+        .run_type(RunType::Tool)
+        .inputs(json!({"query": "rust"}))
+        .thread_id(Some("thread-7".to_string()))
+        .start()
+        .await
+        .unwrap();
+    assert_eq!(graph.root_tracer().name(), "MyPipeline");
+    graph.end_root(json!({"done": true})).await.unwrap();
+
+    let (post, patches) = recorded_run(server, "MyPipeline").await;
+    assert_eq!(post["run_type"], "tool");
+    assert_eq!(post["inputs"], json!({"query": "rust"}));
+    assert_eq!(post["thread_id"], "thread-7");
+    assert_eq!(patches[0]["outputs"], json!({"done": true}));
+}
+
+#[tokio::test]
+async fn test_iterations_not_numbered_by_default() {
+    mock_server().await;
+
+    let graph = GraphTrace::start_root(json!({}), None).await.unwrap();
+    let first = graph.start_node_iteration("chatbot", json!({})).await.unwrap();
+    let second = graph.start_node_iteration("chatbot", json!({})).await.unwrap();
+
+    assert_eq!(first.tracer().name(), "chatbot");
+    assert_eq!(second.tracer().name(), "chatbot");
+    assert!(second.tracer().metadata().get("base_name").is_none());
+}
+
+#[tokio::test]
+async fn test_tool_scope_nests_child_runs() {
+    let server = mock_server().await;
+
+    let graph = GraphTrace::start_root(json!({}), None).await.unwrap();
+    let tools = graph.start_node_iteration("tools", json!({})).await.unwrap();
+    let tool = graph
+        .start_tool_call(&tools, "nested_summarizer", json!({"url": "https://example.com"}))
+        .await
+        .unwrap();
+    graph
+        .trace_llm_call(&tool, "NestedSummarizerLLM", json!({}), json!({"summary": "ok"}), None)
+        .await
+        .unwrap();
+    tool.end_ok(json!({"summary": "ok"})).await.unwrap();
+    tools.end_ok(json!({})).await.unwrap();
+    graph.end_root(json!({})).await.unwrap();
+
+    let (tool_post, tool_patches) = recorded_run(server, "tool/nested_summarizer").await;
+    let (llm_post, _) = recorded_run(server, "NestedSummarizerLLM").await;
+    assert_eq!(tool_post["run_type"], "tool");
+    assert_eq!(llm_post["parent_run_id"], tool_post["id"]);
+    assert_eq!(llm_post["trace_id"], tool_post["trace_id"]);
+    let tool_order = tool_post["dotted_order"].as_str().unwrap();
+    assert!(llm_post["dotted_order"].as_str().unwrap().starts_with(tool_order));
+    assert_eq!(tool_patches[0]["outputs"], json!({"summary": "ok"}));
+}
+
+#[tokio::test]
+async fn test_buffered_graph_sends_one_batch() {
+    let server = mock_server().await;
+    let marker = uuid::Uuid::new_v4().to_string();
+
+    let graph = GraphTrace::buffered(json!({"marker": marker}), None);
+    let step = graph.start_node_iteration("chatbot", json!({})).await.unwrap();
+    graph
+        .trace_llm_call(&step, "ChatOpenAI", json!({}), json!({"content": "hi"}), None)
+        .await
+        .unwrap();
+    graph
+        .trace_decision(&step, "should_continue", json!({}), json!({"next": "end"}))
+        .await
+        .unwrap();
+    step.end_ok(json!({})).await.unwrap();
+    graph.end_root(json!({"answer": 42})).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let ours = |body: &serde_json::Value| body.to_string().contains(&marker);
+    assert!(!requests.iter().any(|r| r.url.path() != "/runs/batch" && ours(&request_json(r))));
+    let batches: Vec<_> = requests
+        .iter()
+        .filter(|r| r.url.path() == "/runs/batch")
+        .map(request_json)
+        .filter(|body| ours(body))
+        .collect();
+    assert_eq!(batches.len(), 1);
+
+    let posted = batches[0]["post"].as_array().unwrap();
+    let names: Vec<_> = posted.iter().map(|run| run["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["Graph", "chatbot", "ChatOpenAI", "should_continue"]);
+    assert_eq!(posted[0]["outputs"], json!({"answer": 42}));
+    for child in &posted[1..] {
+        assert_eq!(child["trace_id"], posted[0]["id"]);
+        assert!(child["end_time"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn test_run_tree_exports_nested_trace() {
+    mock_server().await;
+    let tree = Arc::new(RunTree::new());
+
+    let graph = GraphTrace::start_root(json!({"question": "2+2?"}), None)
+        .await
+        .unwrap()
+        .with_run_tree(Arc::clone(&tree));
+    let chatbot = graph.start_node_iteration("chatbot", json!({})).await.unwrap();
+    graph
+        .trace_llm_call(&chatbot, "ChatOpenAI", json!({}), json!({"text": "4"}), None)
+        .await
+        .unwrap();
+    chatbot.end_ok(json!({"answer": "4"})).await.unwrap();
+    let tools = graph.start_node_iteration("tools", json!({})).await.unwrap();
+    tools.end_ok(json!({})).await.unwrap();
+    let root_id = graph.root_tracer().run_id();
+    graph.end_root(json!({"answer": "4"})).await.unwrap();
+
+    let roots = tree.to_json();
+    assert_eq!(roots.as_array().unwrap().len(), 1);
+    let root = &roots[0];
+    assert_eq!(root["name"], "Graph");
+    assert_eq!(root["id"], json!(root_id));
+    assert_eq!(root["outputs"], json!({"answer": "4"}));
+
+    let steps = root["child_runs"].as_array().unwrap();
+    let names: Vec<&str> = steps.iter().map(|s| s["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["chatbot", "tools"]);
+    for step in steps {
+        assert_eq!(step["parent_run_id"], root["id"]);
+    }
+    let llm = &steps[0]["child_runs"][0];
+    assert_eq!(llm["name"], "ChatOpenAI");
+    assert_eq!(llm["parent_run_id"], steps[0]["id"]);
+    assert_eq!(llm["child_runs"], json!([]));
+
+    // Every run was already traced live, so there is nothing left to send
+    let sink = InMemorySink::new();
+    tree.post_all(&LangSmithClient::in_memory(sink.clone())).await.unwrap();
+    assert!(sink.recorded_runs().is_empty());
+}
+
+#[tokio::test]
+async fn test_run_tree_posts_unsent_runs_once() {
+    let tree = RunTree::new();
+    let mut root = Tracer::new("offline_root", RunType::Chain, json!({}));
+    let mut child = root.create_child("offline_child", RunType::Tool, json!({}));
+    child.end(json!({}));
+    tree.add(&mut child);
+    root.end(json!({}));
+    tree.add(&mut root);
+    // A later copy of a run replaces the earlier one
+    root.add_tag("final".to_string());
+    tree.add(&mut root);
+    assert_eq!(tree.len(), 2);
+
+    let sink = InMemorySink::new();
+    let client = LangSmithClient::in_memory(sink.clone());
+    tree.post_all(&client).await.unwrap();
+    tree.post_all(&client).await.unwrap();
+
+    let posted: Vec<_> = sink.recorded_runs().iter().map(|run| run.name.clone()).collect();
+    assert_eq!(posted, ["offline_root", "offline_child"]);
+    assert!(sink.recorded_runs()[0].tags.contains(&"final".to_string()));
+}
@@ -0,0 +1,76 @@
+use langsmith_rust::client::{InMemorySink, LangSmithClient};
+use langsmith_rust::config::Config;
+use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::testing::{run_tree_shape, TreeCompareOptions};
+use langsmith_rust::tracing::GraphTrace;
+use serde_json::json;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_in_memory_sink_captures_graph_hierarchy() {
+    let sink = InMemorySink::new();
+    // Unroutable endpoint: any request that reached HTTP would fail
+    let config = Config::builder()
+        .endpoint("http://127.0.0.1:9")
+        .tracing_enabled(true)
+        .sink(Arc::new(sink.clone()))
+        .build()
+        .unwrap();
+    Config::set(config);
+
+    let graph = GraphTrace::start_root(json!({"question": "2+2?"}), None)
+        .await
+        .unwrap();
+    let chatbot = graph
+        .start_node_iteration("chatbot", json!({}))
+        .await
+        .unwrap();
+    graph
+        .trace_llm_call(&chatbot, "ChatOpenAI", json!({}), json!({"answer": "4"}), None)
+        .await
+        .unwrap();
+    chatbot.end_ok(json!({"answer": "4"})).await.unwrap();
+    graph.end_root(json!({"answer": "4"})).await.unwrap();
+
+    let runs = sink.recorded_runs();
+    assert_eq!(
+        run_tree_shape(&runs, &TreeCompareOptions::default()),
+        json!([{
+            "name": "Graph",
+            "run_type": "chain",
+            "children": [{
+                "name": "chatbot",
+                "run_type": "chain",
+                "children": [{"name": "ChatOpenAI", "run_type": "llm", "children": []}],
+            }],
+        }])
+    );
+
+    let patches = sink.recorded_patches();
+    let patched_ids: Vec<_> = patches.iter().map(|(id, _)| *id).collect();
+    let ids: Vec<_> = ["ChatOpenAI", "chatbot", "Graph"]
+        .iter()
+        .map(|name| runs.iter().find(|run| run.name == *name).unwrap().id)
+        .collect();
+    assert_eq!(patched_ids, ids);
+    assert_eq!(patches[2].1.outputs, Some(json!({"answer": "4"})));
+}
+
+#[tokio::test]
+async fn test_in_memory_client_records_without_sending() {
+    let sink = InMemorySink::new();
+    let client = LangSmithClient::in_memory(sink.clone());
+
+    let mut run = Run::new("in_memory".to_string(), RunType::Tool, json!({"q": 1}));
+    client.post_run(&run).await.unwrap();
+    run.end(json!({"a": 2}));
+    client.batch_ingest(&[], &[run.clone()]).await.unwrap();
+
+    assert_eq!(sink.recorded_runs()[0].id, run.id);
+    let (patched_id, update) = &sink.recorded_patches()[0];
+    assert_eq!(*patched_id, run.id);
+    assert_eq!(update.outputs, Some(json!({"a": 2})));
+
+    sink.clear();
+    assert!(sink.recorded_runs().is_empty());
+}
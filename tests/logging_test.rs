@@ -0,0 +1,86 @@
+use langsmith_rust::client::LangSmithClient;
+use langsmith_rust::config::Config;
+use langsmith_rust::error::{set_on_trace_error, LangSmithError};
+use langsmith_rust::models::run::RunType;
+use langsmith_rust::tracing::tracer::Tracer;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+struct CapturingLogger {
+    records: Mutex<Vec<(Level, String, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.records.lock().unwrap().push((
+            record.level(),
+            record.target().to_string(),
+            record.args().to_string(),
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+fn unreachable_config(endpoint: &str) -> Config {
+    Config::builder()
+        .tracing_enabled(true)
+        .endpoint(endpoint)
+        .api_key("test-key")
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_failed_post_logs_warning() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let client = Arc::new(LangSmithClient::with_config(unreachable_config(
+        "http://127.0.0.1:1",
+    )));
+    let mut tracer = Tracer::new("Test", RunType::Chain, json!({})).with_client(client);
+
+    // Tracing errors are swallowed, only logged
+    assert!(tracer.post().await.is_ok());
+
+    let records = LOGGER.records.lock().unwrap();
+    assert!(records.iter().any(|(level, target, message)| {
+        *level == Level::Warn
+            && target == "langsmith"
+            && message.starts_with("LangSmith tracing error:")
+    }));
+}
+
+#[tokio::test]
+async fn test_failed_post_invokes_error_callback() {
+    // The callback is global: count only the errors for this test's endpoint,
+    // not those of other tests running in parallel
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    set_on_trace_error(Box::new(|error| {
+        assert!(matches!(error, LangSmithError::Http(_)));
+        if error.to_string().contains("127.0.0.1:2/") {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+        }
+    }));
+
+    let client = Arc::new(LangSmithClient::with_config(unreachable_config(
+        "http://127.0.0.1:2",
+    )));
+    let mut tracer = Tracer::new("Test", RunType::Chain, json!({})).with_client(client);
+    tracer.post().await.unwrap();
+    tracer.end(json!({}));
+    tracer.patch().await.unwrap();
+
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,238 @@
+#![cfg(feature = "axum")]
+
+use axum::body::Body;
+use axum::routing::{get, post};
+use axum::{Extension, Router};
+use http::Request;
+use langsmith_rust::middleware::{CaptureConfig, LangSmithLayer};
+use langsmith_rust::models::run::RunType;
+use langsmith_rust::tracing::context::TraceContext;
+use langsmith_rust::tracing::decorator::trace_node;
+use langsmith_rust::tracing::tracer::Tracer;
+use serde_json::json;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower::ServiceExt;
+
+mod common;
+use common::{ended_run, mock_server, recorded_run};
+
+#[tokio::test]
+async fn test_layer_traces_request_as_root_run() {
+    let server = mock_server().await;
+
+    let app = Router::new()
+        .route(
+            "/hello",
+            get(|Extension(ctx): Extension<TraceContext>| async move {
+                ctx.parent_run_id.unwrap().to_string()
+            }),
+        )
+        .layer(LangSmithLayer::new());
+
+    let response = app
+        .oneshot(
+            Request::get("/hello")
+                .header("user-agent", "test-agent")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers().contains_key("langsmith-trace-id"));
+
+    let (post, patches) = ended_run(server, "GET /hello").await;
+    assert!(post.get("parent_run_id").is_none());
+    assert_eq!(post["inputs"]["method"], json!("GET"));
+    assert_eq!(post["inputs"]["path"], json!("/hello"));
+    assert_eq!(post["inputs"]["headers"]["user-agent"], json!("test-agent"));
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0]["outputs"], json!({"status": 200}));
+
+    // The handler saw the request run as its parent
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(post["id"], json!(String::from_utf8(body.to_vec()).unwrap()));
+}
+
+#[tokio::test]
+async fn test_handler_traces_nest_under_request_run() {
+    let server = mock_server().await;
+
+    let app = Router::new()
+        .route(
+            "/nested",
+            get(|| async {
+                trace_node("nested_handler_step", RunType::Tool, json!({}), |_| async {
+                    Ok::<_, langsmith_rust::LangSmithError>("ok")
+                })
+                .await
+                .unwrap()
+            }),
+        )
+        .layer(LangSmithLayer::new());
+
+    app.oneshot(Request::get("/nested").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let (request_run, _) = ended_run(server, "GET /nested").await;
+    let (step, _) = recorded_run(server, "nested_handler_step").await;
+    assert_eq!(step["parent_run_id"], request_run["id"]);
+    assert_eq!(step["trace_id"], request_run["trace_id"]);
+}
+
+#[tokio::test]
+async fn test_layer_continues_propagated_trace() {
+    let server = mock_server().await;
+    let upstream = Tracer::new("Upstream", RunType::Chain, json!({})).child_context();
+
+    let app = Router::new()
+        .route("/child", get(|| async { "ok" }))
+        .layer(LangSmithLayer::new());
+
+    let mut request = Request::get("/child");
+    for (name, value) in upstream.to_headers() {
+        request = request.header(name, value);
+    }
+    app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+
+    let (post, _) = ended_run(server, "GET /child").await;
+    assert_eq!(post["trace_id"], json!(upstream.trace_id));
+    assert_eq!(post["parent_run_id"], json!(upstream.parent_run_id));
+    let dotted_order = post["dotted_order"].as_str().unwrap();
+    assert!(dotted_order.starts_with(&format!("{}.", upstream.dotted_order.unwrap())));
+}
+
+#[tokio::test]
+async fn test_layer_captures_configured_fields_and_redacts_auth() {
+    let server = mock_server().await;
+
+    let capture = CaptureConfig::new()
+        .with_headers(vec![
+            "Authorization".to_string(),
+            "x-request-id".to_string(),
+        ])
+        .with_max_body_bytes(1024);
+    let app = Router::new()
+        .route("/echo", post(|body: String| async move { body }))
+        .layer(LangSmithLayer::new().with_capture(capture));
+
+    let response = app
+        .oneshot(
+            Request::post("/echo?lang=rust&page=2")
+                .header("authorization", "Bearer secret")
+                .header("x-request-id", "req-1")
+                .header("user-agent", "not-selected")
+                .body(Body::from(r#"{"question":"why"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // The handler still received the full body
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], br#"{"question":"why"}"#);
+
+    let (post, _) = ended_run(server, "POST /echo").await;
+    assert_eq!(
+        post["inputs"]["headers"],
+        json!({"authorization": "[REDACTED]", "x-request-id": "req-1"})
+    );
+    assert_eq!(post["inputs"]["query"], json!({"lang": "rust", "page": "2"}));
+    assert_eq!(post["inputs"]["body"], json!({"question": "why"}));
+}
+
+#[tokio::test]
+async fn test_layer_skips_body_over_limit() {
+    let server = mock_server().await;
+
+    let capture = CaptureConfig::new().with_max_body_bytes(5);
+    let app = Router::new()
+        .route("/upload", post(|body: String| async move { body.len().to_string() }))
+        .route("/stream", post(|body: String| async move { body.len().to_string() }))
+        .layer(LangSmithLayer::new().with_capture(capture));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::post("/upload")
+                .body(Body::from("0123456789"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"10");
+
+    let (post, _) = ended_run(server, "POST /upload").await;
+    assert!(post["inputs"].get("body").is_none());
+    assert!(post["inputs"].get("query").is_none());
+
+    // A streamed body of unknown length isn't buffered either
+    let chunks = ["012", "345"].map(Ok::<_, std::io::Error>);
+    let response = app
+        .oneshot(
+            Request::post("/stream")
+                .body(Body::from_stream(futures_util::stream::iter(chunks)))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(&body[..], b"6");
+    let (post, _) = ended_run(server, "POST /stream").await;
+    assert!(post["inputs"].get("body").is_none());
+}
+
+/// Claims a small known length, then fails to deliver it
+struct FailingBody;
+
+impl http_body::Body for FailingBody {
+    type Data = bytes::Bytes;
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        Poll::Ready(Some(Err(std::io::Error::other("connection reset"))))
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        http_body::SizeHint::with_exact(3)
+    }
+}
+
+#[tokio::test]
+async fn test_layer_fails_request_when_body_read_fails() {
+    let server = mock_server().await;
+
+    let capture = CaptureConfig::new().with_max_body_bytes(1024);
+    let app = Router::new()
+        .route("/broken", post(|body: String| async move { body }))
+        .layer(LangSmithLayer::new().with_capture(capture));
+
+    let response = app
+        .oneshot(Request::post("/broken").body(Body::new(FailingBody)).unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("connection reset"));
+    let (_, patches) = ended_run(server, "POST /broken").await;
+    assert!(patches[0]["error"].as_str().unwrap().contains("connection reset"));
+}
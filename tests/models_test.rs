@@ -1,4 +1,16 @@
-use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::models::dataset::{Dataset, Example};
+use langsmith_rust::models::feedback::Feedback;
+use langsmith_rust::models::messages::{
+    AIMessage, ChatMessage, ContentPart, FunctionMessage, HumanMessage, Message, MessageContent,
+    ToolCall,
+};
+use langsmith_rust::models::metrics::Metrics;
+use langsmith_rust::models::run::{
+    DottedOrderPrecision, Run, RunErrorKind, RunStatus, RunType, RunUpdate,
+};
+use langsmith_rust::utils::timestamp;
+use serde::Deserialize;
 use serde_json::json;
 
 #[test]
@@ -20,6 +32,34 @@ fn test_run_type_as_str() {
     assert_eq!(RunType::Custom("custom_type".to_string()).as_str(), "custom_type");
 }
 
+#[test]
+fn test_run_error_kind_serialization() {
+    let kinds = [
+        (RunErrorKind::Timeout, "timeout"),
+        (RunErrorKind::RateLimit, "rate_limit"),
+        (RunErrorKind::InvalidInput, "invalid_input"),
+        (RunErrorKind::ProviderError, "provider_error"),
+        (RunErrorKind::Other, "other"),
+    ];
+    for (kind, expected) in kinds {
+        assert_eq!(serde_json::to_value(kind).unwrap(), json!(expected));
+        assert_eq!(serde_json::from_value::<RunErrorKind>(json!(expected)).unwrap(), kind);
+    }
+}
+
+#[test]
+fn test_run_error_kind_from_client_errors() {
+    let api = |status| LangSmithError::Api { status, body: String::new() };
+    assert_eq!(RunErrorKind::from(&api(429)), RunErrorKind::RateLimit);
+    assert_eq!(RunErrorKind::from(&api(504)), RunErrorKind::Timeout);
+    assert_eq!(RunErrorKind::from(&api(422)), RunErrorKind::InvalidInput);
+    assert_eq!(RunErrorKind::from(&api(503)), RunErrorKind::ProviderError);
+    let timeout = LangSmithError::Timeout("POST /runs".to_string());
+    assert_eq!(RunErrorKind::from(&timeout), RunErrorKind::Timeout);
+    let other = LangSmithError::Other("boom".to_string());
+    assert_eq!(RunErrorKind::from(&other), RunErrorKind::Other);
+}
+
 #[test]
 fn test_run_generate_dotted_order() {
     let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
@@ -29,6 +69,28 @@ fn test_run_generate_dotted_order() {
     assert!(dotted_order.len() > 20);
 }
 
+#[test]
+fn test_dotted_order_precision_sets_fractional_digits() {
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    let id = run.id.to_string();
+
+    for (precision, digits) in [
+        (DottedOrderPrecision::Micros, 6),
+        (DottedOrderPrecision::Nanos, 9),
+    ] {
+        let dotted_order = run.generate_dotted_order_with(None, precision);
+        let time = dotted_order.strip_suffix(id.as_str()).unwrap();
+        // YYYYMMDDTHHMMSS, the fraction, then Z
+        assert_eq!(time.len(), 15 + digits + 1, "{:?}: {}", precision, time);
+        assert!(time.ends_with('Z'));
+        assert!(time[15..15 + digits].bytes().all(|b| b.is_ascii_digit()));
+    }
+    assert_eq!(
+        run.generate_dotted_order(None),
+        run.generate_dotted_order_with(None, DottedOrderPrecision::Micros)
+    );
+}
+
 #[test]
 fn test_run_generate_dotted_order_with_parent() {
     let parent = Run::new("Parent".to_string(), RunType::Chain, json!({}));
@@ -41,6 +103,101 @@ fn test_run_generate_dotted_order_with_parent() {
     assert!(child_dotted.contains('.'));
 }
 
+#[test]
+fn test_run_new_at_uses_given_start_time() {
+    let start = timestamp::parse_rfc3339("2024-09-19T17:16:48.521691Z").unwrap();
+    let run = Run::new_at("Imported".to_string(), RunType::Chain, json!({}), start);
+
+    assert_eq!(run.start_time, start);
+    assert_eq!(
+        run.generate_dotted_order(None),
+        format!("20240919T171648521691Z{}", run.id)
+    );
+}
+
+// Runs under both the `chrono` and the `time` backends; LangSmith must see the
+// same text from either
+#[test]
+fn test_timestamp_format_is_backend_independent() {
+    for (input, serialized, dotted) in [
+        ("2024-09-19T17:16:48.521691Z", "2024-09-19T17:16:48.521691Z", "20240919T171648521691Z"),
+        ("2024-09-19T17:16:48.5Z", "2024-09-19T17:16:48.500Z", "20240919T171648500000Z"),
+        ("2024-09-19T17:16:48Z", "2024-09-19T17:16:48Z", "20240919T171648000000Z"),
+        (
+            "2024-09-19T19:16:48.000000123+02:00",
+            "2024-09-19T17:16:48.000000123Z",
+            "20240919T171648000000Z",
+        ),
+    ] {
+        let start = timestamp::parse_rfc3339(input).unwrap();
+        let mut run = Run::new_at("Timed".to_string(), RunType::Chain, json!({}), start);
+        run.end_at(json!({}), start);
+
+        let value = serde_json::to_value(&run).unwrap();
+        assert_eq!(value["start_time"], serialized);
+        assert_eq!(value["end_time"], serialized);
+        assert_eq!(run.generate_dotted_order(None), format!("{}{}", dotted, run.id));
+
+        let parsed: Run = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.start_time, start);
+        assert_eq!(parsed.end_time, Some(start));
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Question {
+    query: String,
+    top_k: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Answer {
+    documents: Vec<String>,
+}
+
+#[test]
+fn test_run_inputs_and_outputs_as_types() {
+    let mut run = Run::new(
+        "Retrieve".to_string(),
+        RunType::Retriever,
+        json!({"query": "rust", "top_k": 3}),
+    );
+    assert_eq!(run.outputs_as::<Option<Answer>>().unwrap(), None);
+    run.end(json!({"documents": ["a", "b"]}));
+
+    let question: Question = run.inputs_as().unwrap();
+    assert_eq!(question, Question { query: "rust".to_string(), top_k: 3 });
+    let answer: Answer = run.outputs_as().unwrap();
+    assert_eq!(answer.documents, vec!["a", "b"]);
+}
+
+#[test]
+fn test_run_inputs_as_mismatched_type_errors() {
+    let run = Run::new("Retrieve".to_string(), RunType::Retriever, json!({"query": "rust"}));
+
+    let err = run.inputs_as::<Question>().unwrap_err();
+    assert!(err.to_string().contains("missing field `top_k`"));
+    assert!(run.outputs_as::<Answer>().is_err());
+}
+
+#[test]
+fn test_run_from_tool_call() {
+    let tool_call = ToolCall {
+        id: "call_abc123".to_string(),
+        name: "get_weather".to_string(),
+        args: json!({"city": "Lisbon", "unit": "celsius"}),
+    };
+    let run = Run::from_tool_call(&tool_call);
+
+    assert_eq!(run.name, "get_weather");
+    assert_eq!(run.run_type, RunType::Tool);
+    assert_eq!(
+        run.inputs,
+        json!({"name": "get_weather", "args": {"city": "Lisbon", "unit": "celsius"}})
+    );
+    assert_eq!(run.extra["tool_call_id"], json!("call_abc123"));
+}
+
 #[test]
 fn test_run_set_error() {
     let mut run = Run::new("Test".to_string(), RunType::Chain, json!({}));
@@ -71,3 +228,283 @@ fn test_run_update_from_run() {
     assert_eq!(update.error, Some("Error".to_string()));
 }
 
+#[test]
+fn test_feedback_serialization() {
+    let run_id = uuid::Uuid::new_v4();
+    let feedback = Feedback::new(run_id, "correctness")
+        .with_score(0.5)
+        .with_comment("close enough");
+
+    let value = serde_json::to_value(&feedback).unwrap();
+
+    assert_eq!(value["run_id"], json!(run_id.to_string()));
+    assert_eq!(value["key"], json!("correctness"));
+    assert_eq!(value["score"], json!(0.5));
+    assert_eq!(value["comment"], json!("close enough"));
+    assert!(value.get("value").is_none());
+    assert!(value.get("feedback_source_type").is_none());
+}
+
+#[test]
+fn test_dataset_deserializes_api_response() {
+    let id = uuid::Uuid::new_v4();
+    let dataset: Dataset = serde_json::from_value(json!({
+        "id": id.to_string(),
+        "name": "qa-pairs",
+        "description": null,
+        "created_at": "2024-09-19T17:16:48.521691Z",
+        "example_count": 0,
+    }))
+    .unwrap();
+
+    assert_eq!(dataset.id, id);
+    assert_eq!(dataset.name, "qa-pairs");
+    assert_eq!(dataset.description, None);
+    assert!(dataset.created_at.is_some());
+
+    let value = serde_json::to_value(&dataset).unwrap();
+    assert!(value.get("description").is_none());
+}
+
+#[test]
+fn test_example_round_trip() {
+    let example: Example = serde_json::from_value(json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "dataset_id": uuid::Uuid::new_v4().to_string(),
+        "inputs": {"question": "What is Rust?"},
+        "outputs": {"answer": "A language"},
+    }))
+    .unwrap();
+    assert_eq!(example.outputs, Some(json!({"answer": "A language"})));
+    assert_eq!(example.created_at, None);
+
+    let value = serde_json::to_value(&example).unwrap();
+    let parsed: Example = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.id, example.id);
+    assert_eq!(parsed.dataset_id, example.dataset_id);
+    assert_eq!(parsed.inputs, json!({"question": "What is Rust?"}));
+}
+
+#[test]
+fn test_run_type_serializes_as_flat_string() {
+    assert_eq!(serde_json::to_value(RunType::Chain).unwrap(), json!("chain"));
+    assert_eq!(serde_json::to_value(RunType::Llm).unwrap(), json!("llm"));
+    assert_eq!(
+        serde_json::to_value(RunType::Custom("agent".to_string())).unwrap(),
+        json!("agent")
+    );
+}
+
+#[test]
+fn test_run_type_round_trip() {
+    for run_type in [
+        RunType::Chain,
+        RunType::Llm,
+        RunType::Custom("agent".to_string()),
+    ] {
+        let json = serde_json::to_string(&run_type).unwrap();
+        let parsed: RunType = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, run_type);
+    }
+}
+
+#[test]
+fn test_run_with_custom_type_serializes_run_type_field() {
+    let run = Run::new("Agent".to_string(), RunType::Custom("agent".to_string()), json!({}));
+    let value = serde_json::to_value(&run).unwrap();
+
+    assert_eq!(value["run_type"], json!("agent"));
+}
+
+#[test]
+fn test_metrics_from_openai_usage() {
+    let response = json!({
+        "id": "chatcmpl-abc123",
+        "object": "chat.completion",
+        "model": "gpt-4o-mini",
+        "choices": [{"index": 0, "message": {"role": "assistant", "content": "Hi"}}],
+        "usage": {
+            "prompt_tokens": 19,
+            "completion_tokens": 10,
+            "total_tokens": 29,
+            "prompt_tokens_details": {"cached_tokens": 0}
+        }
+    });
+
+    let metrics = Metrics::from_openai_usage(&response);
+    assert_eq!(metrics.prompt_tokens, Some(19));
+    assert_eq!(metrics.completion_tokens, Some(10));
+    assert_eq!(metrics.total_tokens, Some(29));
+    assert!(metrics.total_cost.is_none());
+}
+
+#[test]
+fn test_metrics_from_anthropic_usage() {
+    let response = json!({
+        "id": "msg_01XFDUDYJgAACzvnptvVoYEL",
+        "type": "message",
+        "role": "assistant",
+        "content": [{"type": "text", "text": "Hi"}],
+        "model": "claude-3-5-sonnet-20241022",
+        "stop_reason": "end_turn",
+        "usage": {"input_tokens": 12, "output_tokens": 6}
+    });
+
+    let metrics = Metrics::from_anthropic_usage(&response);
+    assert_eq!(metrics.prompt_tokens, Some(12));
+    assert_eq!(metrics.completion_tokens, Some(6));
+    assert_eq!(metrics.total_tokens, Some(18));
+}
+
+#[test]
+fn test_metrics_from_usage_tolerates_missing_fields() {
+    let openai = Metrics::from_openai_usage(&json!({"usage": {"prompt_tokens": 5}}));
+    assert_eq!(openai.prompt_tokens, Some(5));
+    assert!(openai.completion_tokens.is_none());
+    assert!(openai.total_tokens.is_none());
+
+    let anthropic = Metrics::from_anthropic_usage(&json!({"usage": {"input_tokens": 5}}));
+    assert_eq!(anthropic.prompt_tokens, Some(5));
+    assert!(anthropic.total_tokens.is_none());
+
+    let empty = Metrics::from_openai_usage(&json!({"choices": []}));
+    assert!(empty.prompt_tokens.is_none());
+    assert!(Metrics::from_anthropic_usage(&json!("not an object")).prompt_tokens.is_none());
+}
+
+#[test]
+fn test_run_status_lifecycle_success() {
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    assert_eq!(run.status, Some(RunStatus::Pending));
+    assert_eq!(serde_json::to_value(&run).unwrap()["status"], "pending");
+
+    run.end(json!({"answer": 42}));
+    assert_eq!(run.status, Some(RunStatus::Success));
+    assert_eq!(serde_json::to_value(RunUpdate::from(&run)).unwrap()["status"], "success");
+}
+
+#[test]
+fn test_run_status_lifecycle_error() {
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    run.set_error("boom");
+    assert_eq!(run.status, Some(RunStatus::Error));
+
+    // Ending a failed run keeps it failed
+    run.end(json!({}));
+    assert_eq!(run.status, Some(RunStatus::Error));
+    assert_eq!(serde_json::to_value(RunUpdate::from(&run)).unwrap()["status"], "error");
+}
+
+#[test]
+fn test_run_status_optional_when_deserializing() {
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+    let mut value = serde_json::to_value(&run).unwrap();
+    value.as_object_mut().unwrap().remove("status");
+
+    let parsed: Run = serde_json::from_value(value).unwrap();
+    assert!(parsed.status.is_none());
+}
+
+#[test]
+fn test_function_message_serialization() {
+    let message = Message::Function(FunctionMessage {
+        name: "get_weather".to_string(),
+        content: "{\"temp\": 21}".to_string(),
+    });
+
+    let value = serde_json::to_value(&message).unwrap();
+    assert_eq!(
+        value,
+        json!({"role": "function", "name": "get_weather", "content": "{\"temp\": 21}"})
+    );
+
+    match serde_json::from_value::<Message>(value).unwrap() {
+        Message::Function(m) => assert_eq!(m.name, "get_weather"),
+        other => panic!("expected function message, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_chat_message_passes_role_through() {
+    let message = Message::Chat(ChatMessage {
+        role: "developer".to_string(),
+        content: "Answer in French.".to_string(),
+    });
+
+    let value = serde_json::to_value(&message).unwrap();
+    assert_eq!(value, json!({"role": "developer", "content": "Answer in French."}));
+
+    let parsed: Message = serde_json::from_value(json!({"role": "user", "content": "Hi"})).unwrap();
+    assert_eq!(parsed.role(), "user");
+    assert!(matches!(parsed, Message::Chat(_)));
+}
+
+#[test]
+fn test_tagged_messages_still_round_trip() {
+    let messages = vec![
+        Message::Human(HumanMessage { content: "Hi".into() }),
+        Message::AI(AIMessage { content: "Hello!".into(), tool_calls: vec![] }),
+    ];
+
+    let value = serde_json::to_value(&messages).unwrap();
+    assert_eq!(
+        value,
+        json!([{"role": "human", "content": "Hi"}, {"role": "ai", "content": "Hello!"}])
+    );
+
+    let parsed: Vec<Message> = serde_json::from_value(value).unwrap();
+    assert!(matches!(&parsed[0], Message::Human(m) if m.content == "Hi".into()));
+    assert!(matches!(&parsed[1], Message::AI(m) if m.content == "Hello!".into()));
+}
+
+#[test]
+fn test_message_without_role_is_rejected() {
+    assert!(serde_json::from_value::<Message>(json!({"content": "Hi"})).is_err());
+}
+
+#[test]
+fn test_text_message_content_round_trip() {
+    let message = Message::Human(HumanMessage { content: "What is Rust?".into() });
+
+    let value = serde_json::to_value(&message).unwrap();
+    assert_eq!(value, json!({"role": "human", "content": "What is Rust?"}));
+
+    let parsed: Message = serde_json::from_value(value).unwrap();
+    assert!(matches!(
+        &parsed,
+        Message::Human(m) if m.content == MessageContent::Text("What is Rust?".to_string())
+    ));
+}
+
+#[test]
+fn test_image_message_content_round_trip() {
+    let message = Message::Human(HumanMessage {
+        content: vec![
+            ContentPart::text("What is in this image?"),
+            ContentPart::image_url("https://example.com/cat.png"),
+        ]
+        .into(),
+    });
+
+    let value = serde_json::to_value(&message).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "role": "human",
+            "content": [
+                {"type": "text", "text": "What is in this image?"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+            ]
+        })
+    );
+
+    let parsed: Message = serde_json::from_value(value).unwrap();
+    assert_eq!(parsed.content(), "What is in this image?");
+    match parsed {
+        Message::Human(HumanMessage { content: MessageContent::Parts(parts) }) => {
+            assert_eq!(parts.len(), 2);
+            assert_eq!(parts[1], ContentPart::image_url("https://example.com/cat.png"));
+        }
+        other => panic!("expected multimodal human message, got {:?}", other),
+    }
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "otel")]
+
+use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::otel::{span_id, to_span_data, OtelExporter};
+use opentelemetry::trace::{SpanId, Status, TraceId};
+use opentelemetry::Value as OtelValue;
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SpanData};
+use serde_json::json;
+
+fn child_of(parent: &Run, name: &str, run_type: RunType) -> Run {
+    let mut run = Run::new(name.to_string(), run_type, json!({"parent": parent.name}));
+    run.parent_run_id = Some(parent.id);
+    run.trace_id = parent.trace_id;
+    run.dotted_order = Some(run.generate_dotted_order(parent.dotted_order.as_deref()));
+    run
+}
+
+fn run_tree() -> Vec<Run> {
+    let mut root = Run::new("agent".to_string(), RunType::Chain, json!({"question": "hi"}));
+    root.trace_id = Some(root.id);
+    root.dotted_order = Some(root.generate_dotted_order(None));
+
+    let mut llm = child_of(&root, "llm", RunType::Llm);
+    llm.prompt_tokens = Some(12);
+    llm.completion_tokens = Some(5);
+    llm.end(json!({"answer": "hello"}));
+
+    let mut tool = child_of(&llm, "tool", RunType::Tool);
+    tool.set_error("timed out");
+    tool.end(json!({}));
+
+    root.end(json!({"answer": "hello"}));
+    vec![root, llm, tool]
+}
+
+fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a OtelValue> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == key)
+        .map(|kv| &kv.value)
+}
+
+#[tokio::test]
+async fn test_exported_spans_follow_run_tree() {
+    let runs = run_tree();
+    let exporter = OtelExporter::with_exporter(InMemorySpanExporter::default());
+    exporter.export_all(&runs).await.unwrap();
+
+    let spans = exporter.exporter().get_finished_spans().unwrap();
+    let by_name = |name: &str| spans.iter().find(|s| s.name == name).unwrap();
+    let (root, llm, tool) = (by_name("agent"), by_name("llm"), by_name("tool"));
+
+    let trace_id = TraceId::from_bytes(*runs[0].id.as_bytes());
+    for span in [root, llm, tool] {
+        assert_eq!(span.span_context.trace_id(), trace_id);
+    }
+
+    assert_eq!(root.span_context.span_id(), span_id(runs[0].id));
+    assert_eq!(root.parent_span_id, SpanId::INVALID);
+    assert_eq!(llm.parent_span_id, root.span_context.span_id());
+    assert_eq!(tool.parent_span_id, llm.span_context.span_id());
+
+    assert_eq!(root.status, Status::Ok);
+    assert_eq!(tool.status, Status::error("timed out"));
+    assert!(root.end_time >= root.start_time);
+
+    assert_eq!(
+        attribute(llm, "gen_ai.usage.input_tokens"),
+        Some(&OtelValue::I64(12))
+    );
+    assert_eq!(
+        attribute(llm, "langsmith.run.type"),
+        Some(&OtelValue::from("llm"))
+    );
+    assert_eq!(
+        attribute(root, "langsmith.inputs"),
+        Some(&OtelValue::from(r#"{"question":"hi"}"#))
+    );
+}
+
+#[test]
+fn test_parent_comes_from_dotted_order() {
+    let runs = run_tree();
+    let mut tool = runs[2].clone();
+    // Ancestry in the dotted order wins over a missing parent_run_id
+    tool.parent_run_id = None;
+    tool.trace_id = None;
+
+    let span = to_span_data(&tool);
+    assert_eq!(span.parent_span_id, span_id(runs[1].id));
+    assert_eq!(
+        span.span_context.trace_id(),
+        TraceId::from_bytes(*runs[0].id.as_bytes())
+    );
+}
+
+#[test]
+fn test_unposted_run_is_its_own_root() {
+    let run = Run::new("standalone".to_string(), RunType::Chain, json!({}));
+    let span = to_span_data(&run);
+
+    assert_eq!(span.parent_span_id, SpanId::INVALID);
+    assert_eq!(span.span_context.trace_id(), TraceId::from_bytes(*run.id.as_bytes()));
+    assert_eq!(span.status, Status::Unset);
+}
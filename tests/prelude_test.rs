@@ -0,0 +1,27 @@
+use langsmith_rust::prelude::*;
+use serde_json::json;
+
+mod common;
+use common::{mock_server, recorded_run};
+
+#[tokio::test]
+async fn test_prelude_covers_common_tracing() {
+    let server = mock_server().await;
+
+    let graph: GraphTrace = GraphTrace::buffered(json!({}), None);
+    let root: &RunScope = graph.root_scope();
+    let context: TraceContext = root.tracer().child_context();
+    let tracer =
+        Tracer::new("prelude_child", RunType::Tool, json!({})).with_parent_context(&context);
+    assert_eq!(tracer.parent_run_id(), Some(root.tracer().run_id()));
+
+    let metrics = Metrics::new().with_tokens(3, 4);
+    let result: Result<u64> = trace_node("prelude_node", RunType::Chain, json!({}), |_| async {
+        Ok(metrics.total_tokens.unwrap_or_default())
+    })
+    .await;
+
+    assert_eq!(result.unwrap(), 7);
+    let (post, _) = recorded_run(server, "prelude_node").await;
+    assert_eq!(post["run_type"], "chain");
+}
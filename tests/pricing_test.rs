@@ -0,0 +1,42 @@
+use langsmith_rust::pricing::{estimate_cost, ModelPricing};
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.expect("cost set");
+    assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+}
+
+#[test]
+fn test_estimate_cost_known_model() {
+    let metrics = estimate_cost("gpt-4o", 1000, 500).unwrap();
+
+    assert_eq!(metrics.prompt_tokens, Some(1000));
+    assert_eq!(metrics.completion_tokens, Some(500));
+    assert_eq!(metrics.total_tokens, Some(1500));
+    assert_close(metrics.prompt_cost, 0.0025);
+    assert_close(metrics.completion_cost, 0.005);
+    assert_close(metrics.total_cost, 0.0075);
+}
+
+#[test]
+fn test_estimate_cost_matches_longest_prefix() {
+    let dated = estimate_cost("gpt-4o-2024-08-06", 1000, 0).unwrap();
+    assert_close(dated.prompt_cost, 0.0025);
+
+    let mini = estimate_cost("gpt-4o-mini-2024-07-18", 1000, 0).unwrap();
+    assert_close(mini.prompt_cost, 0.00015);
+}
+
+#[test]
+fn test_estimate_cost_unknown_model() {
+    assert!(estimate_cost("my-local-llama", 1000, 500).is_none());
+}
+
+#[test]
+fn test_register_custom_pricing() {
+    ModelPricing::register("acme-large", ModelPricing::new(0.002, 0.004));
+
+    let metrics = estimate_cost("acme-large", 2000, 1000).unwrap();
+    assert_close(metrics.prompt_cost, 0.004);
+    assert_close(metrics.completion_cost, 0.004);
+    assert_close(metrics.total_cost, 0.008);
+}
@@ -0,0 +1,101 @@
+use langsmith_rust::models::messages::{HumanMessage, Message};
+use langsmith_rust::models::run::RunType;
+use langsmith_rust::tracing::scope::RunScope;
+use langsmith_rust::tracing::tracer::Tracer;
+use serde_json::{json, Value};
+use std::time::Duration;
+use wiremock::MockServer;
+
+mod common;
+use common::{mock_server, recorded_run};
+
+/// Waits for the best-effort patch spawned by a dropped guard
+async fn wait_for_patch(server: &MockServer, name: &str) -> Value {
+    for _ in 0..50 {
+        let (_, patches) = recorded_run(server, name).await;
+        if let Some(patch) = patches.into_iter().next() {
+            return patch;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("no patch sent for {}", name);
+}
+
+#[tokio::test]
+async fn test_dropped_guard_patches_run() {
+    let server = mock_server().await;
+
+    {
+        let mut guard = RunScope::root_value("guard_dropped", RunType::Chain, json!({})).guard();
+        guard.post_start().await.unwrap();
+        // Early return: never ended
+    }
+
+    let patch = wait_for_patch(server, "guard_dropped").await;
+    assert!(patch["end_time"].is_string());
+    assert_eq!(patch["error"], "Run dropped before it was ended");
+}
+
+#[tokio::test]
+async fn test_dropped_tracer_guard_patches_run() {
+    let server = mock_server().await;
+
+    {
+        let mut guard = Tracer::new("tracer_guard_dropped", RunType::Chain, json!({})).into_guard();
+        guard.post().await.unwrap();
+    }
+
+    let patch = wait_for_patch(server, "tracer_guard_dropped").await;
+    assert!(patch["end_time"].is_string());
+    assert_eq!(patch["error"], "run dropped without completion");
+}
+
+#[tokio::test]
+async fn test_ended_tracer_guard_is_not_patched_again() {
+    let server = mock_server().await;
+
+    {
+        let mut guard = Tracer::new("tracer_guard_ended", RunType::Chain, json!({})).into_guard();
+        guard.post().await.unwrap();
+        guard.end(json!({"answer": 42}));
+        guard.patch().await.unwrap();
+    }
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let (_, patches) = recorded_run(server, "tracer_guard_ended").await;
+    assert_eq!(patches.len(), 1);
+    assert!(patches[0]["error"].is_null());
+}
+
+#[tokio::test]
+async fn test_guard_ended_explicitly_patches_once() {
+    let server = mock_server().await;
+
+    let mut guard = RunScope::root_value("guard_ended", RunType::Chain, json!({})).guard();
+    guard.post_start().await.unwrap();
+    guard.end_ok(json!({"answer": 42})).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let (_, patches) = recorded_run(server, "guard_ended").await;
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0]["outputs"], json!({"answer": 42}));
+    assert!(patches[0].get("error").is_none());
+}
+
+#[tokio::test]
+async fn test_scope_child_llm_posts_llm_run() {
+    let server = mock_server().await;
+
+    let mut root = RunScope::root_value("child_llm_root", RunType::Chain, json!({}));
+    root.post_start().await.unwrap();
+    let messages = vec![Message::Human(HumanMessage { content: "Hi".into() })];
+    let mut llm = root.child_llm("child_llm_call", "claude-haiku", &messages);
+    llm.post_start().await.unwrap();
+    llm.end_ok(json!({"text": "Hello"})).await.unwrap();
+
+    let (post, _) = recorded_run(server, "child_llm_call").await;
+    assert_eq!(post["run_type"], "llm");
+    assert_eq!(post["inputs"]["model"], "claude-haiku");
+    assert_eq!(post["inputs"]["messages"][0]["content"], "Hi");
+    assert_eq!(post["parent_run_id"], json!(root.tracer().run_id()));
+}
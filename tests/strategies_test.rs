@@ -1,5 +1,16 @@
 use langsmith_rust::strategies::serialization_strategy::{SerializationStrategy, DefaultSerializationStrategy};
+use langsmith_rust::client::LangSmithClient;
+use langsmith_rust::config::Config;
+use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::strategies::{AsyncTracingStrategy, NoOpTracingStrategy, TracingStrategy};
 use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Serializes the tests that install a global config
+static CONFIG_LOCK: Mutex<()> = Mutex::const_new(());
 
 #[test]
 fn test_serialization_strategy_wraps_primitive() {
@@ -38,3 +49,86 @@ fn test_serialization_strategy_custom_keys() {
     assert_eq!(output_value["result"], json!("result"));
 }
 
+/// Implements only the generic methods, like strategies written before
+/// `inputs_object`/`outputs_object` existed
+struct UppercaseKeys;
+
+impl SerializationStrategy for UppercaseKeys {
+    fn serialize_inputs<T: serde::Serialize>(
+        &self,
+        value: &T,
+    ) -> Result<serde_json::Value, serde_json::Error> {
+        Ok(json!({ "INPUT": serde_json::to_value(value)? }))
+    }
+
+    fn serialize_outputs<T: serde::Serialize>(
+        &self,
+        value: &T,
+    ) -> Result<serde_json::Value, serde_json::Error> {
+        Ok(json!({ "OUTPUT": serde_json::to_value(value)? }))
+    }
+}
+
+#[test]
+fn test_serialization_strategy_object_methods_have_defaults() {
+    let strategy = UppercaseKeys;
+    assert_eq!(strategy.serialize_inputs(&1).unwrap(), json!({"INPUT": 1}));
+
+    let strategy: Arc<dyn SerializationStrategy> = Arc::new(UppercaseKeys);
+    assert_eq!(strategy.inputs_object(json!("hi")), json!({"input": "hi"}));
+    assert_eq!(strategy.outputs_object(json!({"a": 1})), json!({"a": 1}));
+}
+
+#[tokio::test]
+async fn test_noop_tracing_strategy_never_errors() {
+    let _lock = CONFIG_LOCK.lock().await;
+    let strategy = NoOpTracingStrategy::new();
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+
+    // Neither setting is consulted; an unreachable endpoint proves nothing is sent
+    for enabled in [false, true] {
+        let config = Config::builder()
+            .api_key("key")
+            .endpoint("http://127.0.0.1:9")
+            .tracing_enabled(enabled)
+            .build()
+            .unwrap();
+        Config::set(config);
+
+        strategy.trace_start(&run).await.unwrap();
+        strategy.trace_end(&run).await.unwrap();
+        strategy.trace_error(&run, "boom").await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_async_tracing_strategy_reuses_injected_client() {
+    let _lock = CONFIG_LOCK.lock().await;
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let config = Config::builder()
+        .api_key("key")
+        .endpoint(server.uri())
+        .tracing_enabled(true)
+        .build()
+        .unwrap();
+    Config::set(config.clone());
+    let client = Arc::new(LangSmithClient::with_config(config));
+    let strategy = AsyncTracingStrategy::with_client(Arc::clone(&client));
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({}));
+
+    strategy.trace_start(&run).await.unwrap();
+    strategy.trace_end(&run).await.unwrap();
+    strategy.trace_error(&run, "boom").await.unwrap();
+
+    assert!(Arc::ptr_eq(&strategy.client().unwrap(), &client));
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+    // Without an injected client, one is built from the global config on first use
+    // and then shared
+    let lazy = AsyncTracingStrategy::new();
+    assert!(Arc::ptr_eq(&lazy.client().unwrap(), &lazy.client().unwrap()));
+}
@@ -0,0 +1,117 @@
+use langsmith_rust::models::run::{RunType, RunUpdate};
+use langsmith_rust::tracing::streaming::StreamingTracer;
+use langsmith_rust::tracing::tracer::Tracer;
+use langsmith_rust::utils::timestamp;
+use serde_json::json;
+
+fn word_count(chunk: &str) -> u64 {
+    chunk.split_whitespace().count() as u64
+}
+
+#[test]
+fn test_streaming_counts_tokens_per_chunk() {
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}));
+    let mut stream = StreamingTracer::new(tracer).with_tokenizer(word_count);
+
+    stream.push_chunk("Rust is ");
+    stream.push_chunk("a systems ");
+    assert_eq!(stream.completion_tokens(), Some(4));
+    stream.push_chunk("programming language");
+    stream.end();
+
+    assert_eq!(stream.output(), "Rust is a systems programming language");
+    let update = RunUpdate::from(stream.tracer().run());
+    assert_eq!(update.completion_tokens, Some(6));
+    assert_eq!(update.total_tokens, Some(6));
+    assert_eq!(
+        update.outputs,
+        Some(json!({"output": "Rust is a systems programming language"}))
+    );
+}
+
+#[test]
+fn test_streaming_adds_prompt_tokens_to_total() {
+    let mut tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}));
+    tracer.set_metrics(langsmith_rust::Metrics::new().with_tokens(10, 0));
+    let mut stream = StreamingTracer::new(tracer).with_tokenizer(word_count);
+
+    stream.push_chunk("one two three");
+    stream.end();
+
+    let run = stream.tracer().run();
+    assert_eq!(run.completion_tokens, Some(3));
+    assert_eq!(run.total_tokens, Some(13));
+}
+
+#[test]
+fn test_streaming_without_tokenizer_leaves_tokens_unset() {
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}));
+    let mut stream = StreamingTracer::new(tracer);
+
+    stream.push_chunk("hello");
+    stream.end();
+
+    assert_eq!(stream.completion_tokens(), None);
+    assert_eq!(stream.tracer().run().completion_tokens, None);
+}
+
+#[test]
+fn test_streaming_tracer_marks_run_as_streaming() {
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}))
+        .with_metadata("invocation_params", json!({"model": "gpt-4o"}));
+    let stream = StreamingTracer::new(tracer);
+
+    let serialized = serde_json::to_value(stream.tracer().run()).unwrap();
+    assert_eq!(
+        serialized["extra"]["invocation_params"],
+        json!({"model": "gpt-4o", "stream": true})
+    );
+}
+
+#[test]
+fn test_mark_streaming_on_plain_tracer() {
+    let mut tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}));
+    assert!(tracer.metadata().get("invocation_params").is_none());
+
+    tracer.mark_streaming();
+
+    let serialized = serde_json::to_value(tracer.run()).unwrap();
+    assert_eq!(serialized["extra"]["invocation_params"]["stream"], true);
+}
+
+#[test]
+fn test_streaming_records_first_token_time_once() {
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}));
+    let mut stream = StreamingTracer::new(tracer);
+    assert!(stream.first_token_time().is_none());
+
+    stream.push_chunk("Hello");
+    let first = stream.first_token_time().unwrap();
+    let recorded = stream.tracer().run().extra["first_token_time"].clone();
+    assert_eq!(recorded, json!(timestamp::to_rfc3339(&first)));
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    stream.push_chunk(", ");
+    stream.push_chunk("world");
+    assert_eq!(stream.first_token_time(), Some(first));
+    assert_eq!(stream.tracer().run().extra["first_token_time"], recorded);
+
+    stream.end();
+
+    let run = stream.tracer().run();
+    assert_eq!(run.outputs, Some(json!({"output": "Hello, world"})));
+    let ttft = run.extra["time_to_first_token_ms"].as_i64().unwrap();
+    assert!(ttft >= 0);
+    assert_eq!(Some(ttft), stream.time_to_first_token_ms());
+}
+
+#[test]
+fn test_streaming_without_chunks_has_no_first_token_time() {
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, json!({}));
+    let mut stream = StreamingTracer::new(tracer);
+    stream.end();
+
+    let run = stream.tracer().run();
+    assert!(!run.extra.contains_key("first_token_time"));
+    assert!(!run.extra.contains_key("time_to_first_token_ms"));
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "subscriber")]
+
+use langsmith_rust::client::LangSmithClient;
+use langsmith_rust::config::Config;
+use langsmith_rust::subscriber::LangSmithTracingLayer;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+use common::request_json;
+
+/// Installs the layer as the global subscriber, so the spans of its own HTTP
+/// requests (on runtime threads and spawned tasks) reach it too
+#[tokio::test]
+async fn test_global_layer_does_not_trace_its_own_requests() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let config = Config::builder()
+        .api_key("test-key")
+        .endpoint(server.uri())
+        .tracing_enabled(true)
+        .build()
+        .unwrap();
+    let layer = LangSmithTracingLayer::new()
+        .with_client(Arc::new(LangSmithClient::with_config(config)));
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer.clone()))
+        .unwrap();
+
+    drop(tracing::info_span!("app_span").entered());
+    layer.flush().await;
+    // Give any feedback loop time to show up
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    layer.flush().await;
+
+    let requests = server.received_requests().await.unwrap();
+    let posts: Vec<_> = requests
+        .iter()
+        .filter(|r| r.method.as_str() == "POST" && r.url.path() == "/runs")
+        .collect();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(request_json(posts[0])["name"], "app_span");
+    assert_eq!(requests.len(), 2);
+}
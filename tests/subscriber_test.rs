@@ -0,0 +1,96 @@
+#![cfg(feature = "subscriber")]
+
+use langsmith_rust::client::{InMemorySink, LangSmithClient};
+use langsmith_rust::config::Config;
+use langsmith_rust::subscriber::LangSmithTracingLayer;
+use serde_json::json;
+use std::sync::Arc;
+use tracing_subscriber::layer::SubscriberExt;
+
+mod common;
+use common::{mock_server, recorded_run};
+
+#[tokio::test]
+async fn test_nested_spans_become_run_tree() {
+    let server = mock_server().await;
+    let layer = LangSmithTracingLayer::new();
+    let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+    tracing::subscriber::with_default(subscriber, || {
+        let root = tracing::info_span!("span_agent", question = "What is Rust?");
+        let _root = root.enter();
+
+        let child =
+            tracing::info_span!("span_retriever", top_k = 3, answer = tracing::field::Empty);
+        {
+            let _child = child.enter();
+            child.record("answer", "A systems language");
+            let grandchild = tracing::debug_span!("span_embed", cached = true);
+            let _grandchild = grandchild.enter();
+        }
+        drop(child);
+
+        let failing = tracing::info_span!("span_tool");
+        let _failing = failing.enter();
+        tracing::error!("tool timed out");
+    });
+    layer.flush().await;
+
+    let (root, root_patches) = recorded_run(server, "span_agent").await;
+    let (child, child_patches) = recorded_run(server, "span_retriever").await;
+    let (grandchild, _) = recorded_run(server, "span_embed").await;
+    let (failing, failing_patches) = recorded_run(server, "span_tool").await;
+
+    assert_eq!(root["inputs"], json!({"question": "What is Rust?"}));
+    assert_eq!(root["run_type"], "chain");
+    assert_eq!(root["extra"]["target"], "subscriber_test");
+    assert!(root["parent_run_id"].is_null());
+    assert_eq!(root["trace_id"], root["id"]);
+
+    assert_eq!(child["inputs"], json!({"top_k": 3}));
+    assert_eq!(child["parent_run_id"], root["id"]);
+    assert_eq!(child["trace_id"], root["id"]);
+    assert_eq!(grandchild["parent_run_id"], child["id"]);
+    assert_eq!(grandchild["trace_id"], root["id"]);
+    assert_eq!(grandchild["extra"]["level"], "DEBUG");
+    assert_eq!(failing["parent_run_id"], root["id"]);
+
+    let child_dotted = child["dotted_order"].as_str().unwrap();
+    let root_dotted = root["dotted_order"].as_str().unwrap();
+    assert!(child_dotted.starts_with(root_dotted));
+
+    assert_eq!(child_patches.len(), 1);
+    assert_eq!(child_patches[0]["outputs"], json!({"answer": "A systems language"}));
+    assert!(child_patches[0]["end_time"].is_string());
+
+    assert_eq!(failing_patches.len(), 1);
+    assert_eq!(failing_patches[0]["error"], "tool timed out");
+
+    assert_eq!(root_patches.len(), 1);
+    assert!(root_patches[0]["end_time"].is_string());
+}
+
+#[tokio::test]
+async fn test_injected_client_config_decides_whether_spans_are_traced() {
+    let sink = InMemorySink::new();
+    let layer = LangSmithTracingLayer::new()
+        .with_client(Arc::new(LangSmithClient::in_memory(sink.clone())));
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer.clone()), || {
+        let _span = tracing::info_span!("span_injected_enabled").entered();
+    });
+    layer.flush().await;
+    assert_eq!(sink.recorded_runs().len(), 1);
+
+    let disabled = Config::builder()
+        .tracing_enabled(false)
+        .sink(Arc::new(sink.clone()))
+        .build()
+        .unwrap();
+    let layer = LangSmithTracingLayer::new()
+        .with_client(Arc::new(LangSmithClient::with_config(disabled)));
+    tracing::subscriber::with_default(tracing_subscriber::registry().with(layer.clone()), || {
+        let _span = tracing::info_span!("span_injected_disabled").entered();
+    });
+    layer.flush().await;
+    assert_eq!(sink.recorded_runs().len(), 1);
+}
@@ -0,0 +1,56 @@
+use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::testing::{assert_run_tree, assert_run_tree_with, TreeCompareOptions};
+use langsmith_rust::tracing::tracer::Tracer;
+use serde_json::json;
+
+/// Builds Graph -> (chatbot -> ChatOpenAI, tools) with fresh ids each call
+fn graph_runs(llm_output: &str) -> Vec<Run> {
+    let root = Tracer::new("Graph", RunType::Chain, json!({"q": "hi"}));
+    let chatbot = root.create_child("chatbot", RunType::Chain, json!({}));
+    let mut llm = chatbot.create_child("ChatOpenAI", RunType::Llm, json!({"prompt": "hi"}));
+    llm.end(json!({"completion": llm_output}));
+    let tools = root.create_child("tools", RunType::Chain, json!({}));
+
+    vec![
+        root.run().clone(),
+        chatbot.run().clone(),
+        llm.run().clone(),
+        tools.run().clone(),
+    ]
+}
+
+#[test]
+fn test_trees_differing_only_in_ids_are_equal() {
+    let actual = graph_runs("hello");
+    let expected = graph_runs("hello");
+    assert_ne!(actual[0].id, expected[0].id);
+
+    assert_run_tree(&actual, &expected);
+    assert_run_tree_with(&actual, &expected, &TreeCompareOptions::with_io());
+}
+
+#[test]
+fn test_outputs_ignored_unless_requested() {
+    assert_run_tree(&graph_runs("hello"), &graph_runs("goodbye"));
+}
+
+#[test]
+#[should_panic(expected = "run trees differ")]
+fn test_outputs_compared_when_requested() {
+    assert_run_tree_with(
+        &graph_runs("hello"),
+        &graph_runs("goodbye"),
+        &TreeCompareOptions::with_io(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "run trees differ")]
+fn test_trees_with_different_structure_fail() {
+    let actual = graph_runs("hello");
+    let mut expected = graph_runs("hello");
+    // Move the LLM run from under "chatbot" to under "tools"
+    expected[2].parent_run_id = Some(expected[3].id);
+
+    assert_run_tree(&actual, &expected);
+}
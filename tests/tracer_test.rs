@@ -1,7 +1,16 @@
-use langsmith_rust::models::run::RunType;
+use langsmith_rust::client::{InMemorySink, LangSmithClient};
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::models::messages::{
+    AIMessage, HumanMessage, Message, SystemMessage, ToolCall,
+};
+use langsmith_rust::models::metrics::Metrics;
+use langsmith_rust::models::run::{RunErrorKind, RunType, RunUpdate};
 use langsmith_rust::tracing::tracer::Tracer;
 use langsmith_rust::tracing::context::TraceContext;
+use langsmith_rust::tracing::shared::SharedTracer;
+use langsmith_rust::utils::timestamp::{self, TimeDelta};
 use serde_json::json;
+use std::sync::Arc;
 use uuid::Uuid;
 
 #[test]
@@ -20,6 +29,241 @@ fn test_tracer_with_thread_id() {
     assert_eq!(tracer.thread_id(), Some(&"thread-123".to_string()));
 }
 
+#[test]
+fn test_tracer_with_project_propagates_to_children() {
+    let root = Tracer::new("Root".to_string(), RunType::Chain, json!({}))
+        .with_project("override-project");
+    let child = root.create_child("Child".to_string(), RunType::Llm, json!({}));
+    let grandchild = child.create_child("Grandchild".to_string(), RunType::Tool, json!({}));
+    let remote = Tracer::new("Remote".to_string(), RunType::Chain, json!({}))
+        .with_parent_context(&root.child_context());
+
+    let serialized = serde_json::to_value(root.run()).unwrap();
+    assert_eq!(serialized["session_name"], "override-project");
+    for tracer in [&child, &grandchild, &remote] {
+        assert_eq!(tracer.session_name(), Some(&"override-project".to_string()));
+    }
+}
+
+#[test]
+fn test_tracer_timestamps_and_duration() {
+    let mut tracer = Tracer::new("Timed".to_string(), RunType::Chain, json!({}));
+    assert!(tracer.end_time().is_none());
+    assert!(tracer.duration().is_none());
+
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    tracer.end(json!({}));
+
+    let end_time = tracer.end_time().unwrap();
+    assert!(end_time >= tracer.start_time());
+    let duration = tracer.duration().unwrap();
+    assert!(duration >= TimeDelta::milliseconds(2));
+    assert_eq!(duration, end_time - tracer.start_time());
+}
+
+#[test]
+fn test_tracer_with_start_time_regenerates_dotted_order() {
+    let start = timestamp::parse_rfc3339("2024-09-19T17:16:48.521691Z").unwrap();
+    let root = Tracer::new("Imported".to_string(), RunType::Chain, json!({}))
+        .with_start_time(start);
+    let root_order = root.child_context().dotted_order.unwrap();
+    assert_eq!(root.start_time(), start);
+    assert_eq!(root_order, format!("20240919T171648521691Z{}", root.run_id()));
+
+    let child_start = start + TimeDelta::milliseconds(250);
+    let child = Tracer::new("Step".to_string(), RunType::Tool, json!({}))
+        .with_parent_context(&root.child_context())
+        .with_start_time(child_start);
+    assert_eq!(
+        child.dotted_order().unwrap(),
+        &format!("{}.20240919T171648771691Z{}", root_order, child.run_id())
+    );
+}
+
+#[test]
+fn test_tracer_end_at_sets_end_time_in_update() {
+    let start = timestamp::parse_rfc3339("2024-09-19T17:16:48Z").unwrap();
+    let end = start + TimeDelta::milliseconds(1500);
+    let mut tracer = Tracer::new("Replayed".to_string(), RunType::Llm, json!({}))
+        .with_start_time(start);
+    tracer.end_at(json!({"answer": 42}), end);
+
+    let update = RunUpdate::from(tracer.run());
+    assert_eq!(update.end_time, Some(end));
+    assert_eq!(update.outputs, Some(json!({"answer": 42})));
+    assert_eq!(tracer.duration(), Some(TimeDelta::milliseconds(1500)));
+}
+
+#[test]
+fn test_tracer_for_tool_call() {
+    let tool_call = ToolCall {
+        id: "call_1".to_string(),
+        name: "search".to_string(),
+        args: json!({"query": "rust"}),
+    };
+    let tracer = Tracer::for_tool_call(&tool_call);
+
+    assert_eq!(tracer.name(), "search");
+    assert_eq!(tracer.run_type(), &RunType::Tool);
+    assert_eq!(tracer.run().inputs, json!({"name": "search", "args": {"query": "rust"}}));
+    assert_eq!(tracer.metadata().get("tool_call_id"), Some(&json!("call_1")));
+}
+
+#[test]
+fn test_child_llm_records_model_and_messages() {
+    let parent = Tracer::new("Agent", RunType::Chain, json!({}));
+    let messages = vec![Message::Human(HumanMessage { content: "Hi".into() })];
+
+    let llm = parent.child_llm("ChatOpenAI", "gpt-4o-mini", &messages);
+
+    assert_eq!(llm.run_type(), &RunType::Llm);
+    assert_eq!(llm.parent_run_id(), Some(parent.run_id()));
+    assert_eq!(
+        llm.run().inputs,
+        json!({"messages": [{"role": "human", "content": "Hi"}], "model": "gpt-4o-mini"})
+    );
+}
+
+#[test]
+fn test_tracer_new_chat_serializes_messages() {
+    let messages = vec![
+        Message::System(SystemMessage { content: "Be brief".to_string() }),
+        Message::Human(HumanMessage { content: "What is Rust?".into() }),
+    ];
+    let mut tracer = Tracer::new_chat("ChatOpenAI", messages);
+
+    assert_eq!(tracer.run_type(), &RunType::Llm);
+    assert_eq!(
+        tracer.run().inputs,
+        json!({"messages": [
+            {"role": "system", "content": "Be brief"},
+            {"role": "human", "content": "What is Rust?"},
+        ]})
+    );
+
+    tracer.end_with_message(&AIMessage {
+        content: "A systems language.".into(),
+        tool_calls: vec![],
+    });
+    assert_eq!(
+        tracer.run().outputs,
+        Some(json!({"messages": [{"role": "ai", "content": "A systems language."}]}))
+    );
+}
+
+#[test]
+fn test_tracer_reference_example_serialized_only_when_set() {
+    let tracer = Tracer::new("Eval".to_string(), RunType::Chain, json!({}));
+    let value = serde_json::to_value(tracer.run()).unwrap();
+    assert!(value.get("reference_example_id").is_none());
+
+    let example_id = Uuid::new_v4();
+    let tracer = tracer.with_reference_example(example_id);
+    let value = serde_json::to_value(tracer.run()).unwrap();
+    assert_eq!(value["reference_example_id"], json!(example_id.to_string()));
+}
+
+#[test]
+fn test_tracer_merge_metadata_is_deep() {
+    let mut tracer = Tracer::new("Merged".to_string(), RunType::Chain, json!({}))
+        .with_metadata("team", json!("search"));
+
+    let first = json!({
+        "request": {"id": "req-1", "headers": {"user-agent": "curl"}},
+        "region": "eu",
+    });
+    let second = json!({
+        "request": {"headers": {"x-trace": "abc"}, "retries": 2},
+        "region": "us",
+    });
+    tracer.merge_metadata(first.as_object().unwrap().clone());
+    tracer.merge_metadata(second.as_object().unwrap().clone());
+
+    let metadata = tracer.metadata();
+    assert_eq!(metadata["team"], "search");
+    assert_eq!(metadata["region"], "us");
+    assert_eq!(
+        metadata["request"],
+        json!({
+            "id": "req-1",
+            "headers": {"user-agent": "curl", "x-trace": "abc"},
+            "retries": 2,
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_tracer_is_root_and_depth() {
+    let root = Tracer::new("Graph".to_string(), RunType::Chain, json!({}));
+    let parent = Tracer::new("Graph".to_string(), RunType::Chain, json!({}))
+        .with_parent_context(&root.child_context());
+    let child = parent.create_child("chatbot", RunType::Chain, json!({}));
+    let grandchild = child.create_child("ChatOpenAI", RunType::Llm, json!({}));
+
+    assert!(root.is_root());
+    assert_eq!(root.depth(), 0);
+    assert!(!parent.is_root());
+    assert_eq!(parent.depth(), 1);
+    assert!(!child.is_root());
+    assert_eq!(child.depth(), 2);
+    assert!(!grandchild.is_root());
+    assert_eq!(grandchild.depth(), 3);
+
+    // Children of a root that hasn't been posted yet
+    let client = Arc::new(LangSmithClient::in_memory(InMemorySink::new()));
+    let mut unposted =
+        Tracer::new("Graph".to_string(), RunType::Chain, json!({})).with_client(client);
+    let child = unposted.create_child("chatbot", RunType::Chain, json!({}));
+    let grandchild = child.create_child("ChatOpenAI", RunType::Llm, json!({}));
+    assert_eq!(unposted.depth(), 0);
+    assert_eq!(child.depth(), 1);
+    assert_eq!(grandchild.depth(), 2);
+
+    // Their dotted_order matches the one the root gets when it's sent
+    unposted.post().await.unwrap();
+    let root_order = unposted.dotted_order().unwrap();
+    assert!(child.dotted_order().unwrap().starts_with(&format!("{}.", root_order)));
+}
+
+#[test]
+fn test_tracer_set_tokens_and_cost_in_update() {
+    let mut tracer = Tracer::new("ChatOpenAI".to_string(), RunType::Llm, json!({}));
+    tracer.set_tokens(120, 30);
+    tracer.set_cost(0.0045);
+    tracer.end(json!({"answer": "hi"}));
+
+    let update = serde_json::to_value(RunUpdate::from(tracer.run())).unwrap();
+    assert_eq!(update["prompt_tokens"], 120);
+    assert_eq!(update["completion_tokens"], 30);
+    assert_eq!(update["total_tokens"], 150);
+    assert_eq!(update["total_cost"], 0.0045);
+    assert!(update.get("prompt_cost").is_none());
+}
+
+#[test]
+fn test_tracer_set_tokens_saturates_total() {
+    let mut tracer = Tracer::new("ChatOpenAI".to_string(), RunType::Llm, json!({}));
+    tracer.set_tokens(u64::MAX, 1);
+    assert_eq!(tracer.run().total_tokens, Some(u64::MAX));
+}
+
+#[test]
+fn test_tracer_set_error_details_carried_in_update() {
+    let mut tracer = Tracer::new("Failing".to_string(), RunType::Tool, json!({}));
+    tracer.set_error_details(
+        "std::io::Error",
+        "connection reset",
+        Some("at fetch (src/tools.rs:42)".to_string()),
+    );
+    tracer.end(json!({}));
+
+    let update = serde_json::to_value(RunUpdate::from(tracer.run())).unwrap();
+    assert_eq!(update["error"], "connection reset");
+    assert_eq!(update["status"], "error");
+    assert_eq!(update["extra"]["error_type"], "std::io::Error");
+    assert_eq!(update["extra"]["stacktrace"], "at fetch (src/tools.rs:42)");
+}
+
 #[test]
 fn test_tracer_create_child() {
     let parent = Tracer::new("Parent".to_string(), RunType::Chain, json!({}));
@@ -47,6 +291,106 @@ fn test_tracer_with_context() {
     assert_eq!(tracer.session_name(), Some(&"test-project".to_string()));
 }
 
+#[test]
+fn test_trace_context_header_round_trip() {
+    let root = Tracer::new("Root".to_string(), RunType::Chain, json!({}));
+    let context = root.child_context();
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in context.to_headers() {
+        headers.insert(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+            value.parse().unwrap(),
+        );
+    }
+    assert_eq!(headers["langsmith-trace-id"], context.trace_id.to_string().as_str());
+    assert!(headers.get("langsmith-sampled").is_none());
+
+    let received = TraceContext::from_headers(&headers).unwrap();
+    assert_eq!(received.trace_id, context.trace_id);
+    assert_eq!(received.parent_run_id, Some(root.run_id()));
+    assert_eq!(received.dotted_order, context.dotted_order);
+    assert!(received.is_sampled());
+
+    // A downstream run continues the same trace under the upstream run
+    let downstream = Tracer::new("Downstream".to_string(), RunType::Tool, json!({}))
+        .with_parent_context(&received);
+    assert_eq!(downstream.trace_id(), Some(context.trace_id));
+    assert_eq!(downstream.parent_run_id(), Some(root.run_id()));
+
+    let unsampled = TraceContext::new(context.trace_id).with_sampled(false).to_headers();
+    assert_eq!(unsampled["langsmith-sampled"], "false");
+    assert!(TraceContext::from_headers(&reqwest::header::HeaderMap::new()).is_none());
+}
+
+#[test]
+fn test_traceparent_round_trip() {
+    let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let context = TraceContext::from_traceparent(header).unwrap();
+
+    assert_eq!(context.trace_id, Uuid::parse_str("4bf92f3577b34da6a3ce929d0e0e4736").unwrap());
+    assert_eq!(context.parent_run_id, Some(Uuid::from_u128(0x00f067aa0ba902b7)));
+    assert!(context.is_sampled());
+    assert_eq!(context.to_traceparent(), header);
+
+    let unsampled = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00";
+    assert!(!TraceContext::from_traceparent(unsampled).unwrap().is_sampled());
+
+    // A LangSmith root run exports its own id as the span id
+    let root = Tracer::new("Root".to_string(), RunType::Chain, json!({}));
+    let traceparent = root.context().to_traceparent();
+    let parsed = TraceContext::from_traceparent(&traceparent).unwrap();
+    assert_eq!(parsed.trace_id, root.run_id());
+    assert_eq!(parsed.to_traceparent(), traceparent);
+}
+
+#[tokio::test]
+async fn test_traceparent_run_is_root_linking_remote_span() {
+    let sink = InMemorySink::new();
+    let client = Arc::new(LangSmithClient::in_memory(sink.clone()));
+    let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let context = TraceContext::from_traceparent(header).unwrap();
+
+    let mut run = Tracer::new("FromOtel", RunType::Chain, json!({}))
+        .with_client(client)
+        .with_parent_context(&context);
+    run.post().await.unwrap();
+
+    // No dotted_order to nest under, so the span is only linked
+    let posted = serde_json::to_value(&sink.recorded_runs()[0]).unwrap();
+    assert!(posted["parent_run_id"].is_null());
+    assert_eq!(posted["trace_id"], json!(context.trace_id));
+    let dotted_order = posted["dotted_order"].as_str().unwrap();
+    assert!(!dotted_order.contains('.'));
+    assert!(dotted_order.ends_with(&run.run_id().to_string()));
+    assert_eq!(posted["extra"]["otel_parent_span_id"], "00f067aa0ba902b7");
+}
+
+#[test]
+fn test_traceparent_rejects_malformed_headers() {
+    for header in [
+        "",
+        "garbage",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902bz-01",
+    ] {
+        assert!(
+            matches!(
+                TraceContext::from_traceparent(header),
+                Err(LangSmithError::InvalidTraceparent(_))
+            ),
+            "accepted {:?}",
+            header
+        );
+    }
+}
+
 #[test]
 fn test_tracer_context() {
     let tracer = Tracer::new("Test".to_string(), RunType::Chain, json!({}));
@@ -55,6 +399,30 @@ fn test_tracer_context() {
     assert!(context.trace_id == tracer.run_id() || tracer.trace_id() == Some(context.trace_id));
 }
 
+#[test]
+fn test_context_describes_run_and_child_context_its_children() {
+    let root = Tracer::new("Root".to_string(), RunType::Chain, json!({}));
+    let child = root.create_child("Child".to_string(), RunType::Tool, json!({}));
+
+    let own = child.context();
+    assert_eq!(own.parent_run_id, Some(root.run_id()));
+    assert_eq!(own.dotted_order.as_ref(), child.dotted_order());
+
+    let for_children = child.child_context();
+    assert_eq!(for_children.trace_id, own.trace_id);
+    assert_eq!(for_children.parent_run_id, Some(child.run_id()));
+    assert_eq!(for_children.dotted_order.as_ref(), child.dotted_order());
+
+    // with_context keeps the given position; with_parent_context nests under it
+    let same_place = Tracer::new("Retry".to_string(), RunType::Tool, json!({})).with_context(&own);
+    assert_eq!(same_place.parent_run_id(), Some(root.run_id()));
+    assert_eq!(same_place.dotted_order(), child.dotted_order());
+    let nested =
+        Tracer::new("Nested".to_string(), RunType::Llm, json!({})).with_parent_context(&own);
+    assert_eq!(nested.parent_run_id(), Some(root.run_id()));
+    assert!(nested.dotted_order().unwrap().starts_with(child.dotted_order().unwrap()));
+}
+
 #[test]
 fn test_tracer_run_id() {
     let tracer = Tracer::new("Test".to_string(), RunType::Chain, json!({}));
@@ -63,3 +431,360 @@ fn test_tracer_run_id() {
     assert_eq!(run_id, tracer.run_id());
 }
 
+
+#[test]
+fn test_tracer_set_metrics_carried_in_update() {
+    let mut tracer = Tracer::new("LLM".to_string(), RunType::Llm, json!({}));
+    tracer.set_metrics(Metrics::new().with_tokens(10, 5).with_costs(0.01, 0.02));
+    tracer.end(json!({"completion": "done"}));
+
+    let update = RunUpdate::from(tracer.run());
+    assert_eq!(update.prompt_tokens, Some(10));
+    assert_eq!(update.completion_tokens, Some(5));
+    assert_eq!(update.total_tokens, Some(15));
+    assert_eq!(update.prompt_cost, Some(0.01));
+    assert_eq!(update.completion_cost, Some(0.02));
+    assert_eq!(update.total_cost, Some(0.03));
+}
+
+#[test]
+fn test_tracer_tags_and_metadata() {
+    let mut tracer = Tracer::new("Test".to_string(), RunType::Chain, json!({}))
+        .with_tags(vec!["production".to_string()])
+        .with_metadata("version", json!("v2"));
+    tracer.add_tag("agent".to_string());
+
+    assert_eq!(tracer.tags(), &["production".to_string(), "agent".to_string()]);
+    assert_eq!(tracer.metadata().get("version"), Some(&json!("v2")));
+
+    let body = serde_json::to_value(tracer.run()).unwrap();
+    assert_eq!(body["tags"], json!(["production", "agent"]));
+    assert_eq!(body["extra"]["version"], json!("v2"));
+}
+
+#[test]
+fn test_tracer_child_inherits_tags_and_metadata() {
+    let parent = Tracer::new("Parent".to_string(), RunType::Chain, json!({}))
+        .with_tags(vec!["production".to_string()])
+        .with_metadata("env", json!("prod"));
+    let child = parent
+        .create_child("Child".to_string(), RunType::Llm, json!({}))
+        .with_metadata("model", json!("gpt-4o"));
+
+    let body = serde_json::to_value(child.run()).unwrap();
+    assert_eq!(body["tags"], json!(["production"]));
+    assert_eq!(body["extra"]["env"], json!("prod"));
+    assert_eq!(body["extra"]["model"], json!("gpt-4o"));
+    assert!(parent.metadata().get("model").is_none());
+}
+
+#[test]
+fn test_tracer_grandchild_inherits_metadata() {
+    let root = Tracer::new("Root".to_string(), RunType::Chain, json!({}))
+        .with_tags(vec!["production".to_string()])
+        .with_metadata("env", json!("prod"))
+        .with_metadata("version", json!("1.2.0"));
+    let mut child = root.create_child("Child".to_string(), RunType::Chain, json!({}));
+    child.add_tag("retrieval".to_string());
+    let grandchild = child
+        .create_child("Grandchild".to_string(), RunType::Tool, json!({}))
+        .with_metadata("tool", json!("search"));
+
+    let body = serde_json::to_value(grandchild.run()).unwrap();
+    assert_eq!(body["tags"], json!(["production", "retrieval"]));
+    assert_eq!(body["extra"]["env"], json!("prod"));
+    assert_eq!(body["extra"]["version"], json!("1.2.0"));
+    assert_eq!(body["extra"]["tool"], json!("search"));
+    assert_eq!(root.tags(), &["production".to_string()]);
+    assert!(child.metadata().get("tool").is_none());
+}
+
+#[test]
+fn test_tracer_seeded_outputs_merged_on_end() {
+    let mut tracer = Tracer::new("Router", RunType::Chain, json!({}))
+        .with_outputs(json!({"route": "search", "confidence": 0.5}));
+    assert_eq!(tracer.run().outputs, Some(json!({"route": "search", "confidence": 0.5})));
+
+    tracer.end(json!({"confidence": 0.9, "answer": "42"}));
+
+    assert_eq!(
+        tracer.run().outputs,
+        Some(json!({"route": "search", "confidence": 0.9, "answer": "42"}))
+    );
+}
+
+#[test]
+fn test_tracer_end_with_response_records_provider_ids() {
+    let response = json!({
+        "id": "chatcmpl-abc123",
+        "object": "chat.completion",
+        "model": "gpt-4o-2024-08-06",
+        "system_fingerprint": "fp_44709d6fcb",
+        "choices": [{"message": {"role": "assistant", "content": "Hi"}}]
+    });
+
+    let mut tracer = Tracer::new("LLM", RunType::Llm, json!({}));
+    tracer.end_with_response(json!({"completion": "Hi"}), &response);
+
+    assert_eq!(tracer.run().outputs, Some(json!({"completion": "Hi"})));
+    assert_eq!(
+        tracer.metadata().get("ls_response_metadata"),
+        Some(&json!({
+            "id": "chatcmpl-abc123",
+            "model": "gpt-4o-2024-08-06",
+            "system_fingerprint": "fp_44709d6fcb"
+        }))
+    );
+}
+
+#[test]
+fn test_tracer_response_metadata_skips_missing_fields() {
+    let mut tracer = Tracer::new("LLM", RunType::Llm, json!({}));
+    tracer.set_response_metadata(&json!({"id": "msg_01", "system_fingerprint": null}));
+    assert_eq!(
+        tracer.metadata().get("ls_response_metadata"),
+        Some(&json!({"id": "msg_01"}))
+    );
+
+    let mut tracer = Tracer::new("LLM", RunType::Llm, json!({}));
+    tracer.set_response_metadata(&json!({"choices": []}));
+    assert!(tracer.metadata().get("ls_response_metadata").is_none());
+}
+
+#[test]
+fn test_tracer_with_rendered_prompt() {
+    let inputs = json!({
+        "messages": [
+            {"role": "system", "content": "Be brief."},
+            {"role": "human", "content": "What is Rust?"}
+        ]
+    });
+
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, inputs.clone()).with_rendered_prompt();
+    assert_eq!(
+        tracer.metadata().get("rendered_prompt"),
+        Some(&json!("system: Be brief.\nhuman: What is Rust?"))
+    );
+
+    let custom = Tracer::new("ChatOpenAI", RunType::Llm, inputs).with_prompt_renderer(|messages| {
+        messages
+            .iter()
+            .map(|m| format!("<{}>{}</{}>", m.role(), m.content(), m.role()))
+            .collect()
+    });
+    assert_eq!(
+        custom.metadata().get("rendered_prompt"),
+        Some(&json!("<system>Be brief.</system><human>What is Rust?</human>"))
+    );
+}
+
+#[test]
+fn test_tracer_rendered_prompt_off_by_default() {
+    let inputs = json!({"messages": [{"role": "human", "content": "Hi"}]});
+    let tracer = Tracer::new("ChatOpenAI", RunType::Llm, inputs);
+    assert!(tracer.metadata().get("rendered_prompt").is_none());
+
+    // Inputs without messages are left alone
+    let tracer = Tracer::new("Tool", RunType::Tool, json!({"q": "hi"})).with_rendered_prompt();
+    assert!(tracer.metadata().get("rendered_prompt").is_none());
+}
+
+#[tokio::test]
+async fn test_shared_tracer_spawns_concurrent_children() {
+    let parent = SharedTracer::new(Tracer::new("Parent", RunType::Chain, json!({})));
+    let parent_id = parent.run_id();
+
+    let handles: Vec<_> = (0..3)
+        .map(|i| {
+            let parent = parent.clone();
+            tokio::spawn(async move {
+                let mut child =
+                    parent.create_child(format!("Child {}", i), RunType::Tool, json!({"i": i}));
+                child.end(json!({"done": i}));
+                child
+            })
+        })
+        .collect();
+
+    let mut children = Vec::new();
+    for handle in handles {
+        children.push(handle.await.unwrap());
+    }
+
+    let parent_dotted = parent.tracer().dotted_order().unwrap().clone();
+    for child in &children {
+        assert_eq!(child.trace_id(), Some(parent_id));
+        assert_eq!(child.parent_run_id(), Some(parent_id));
+        assert!(child.dotted_order().unwrap().starts_with(&parent_dotted));
+    }
+    assert_eq!(parent.child_context().parent_run_id, Some(parent_id));
+
+    let mut parent = parent.try_into_inner().ok().unwrap();
+    parent.end(json!({"children": children.len()}));
+    assert!(parent.run().end_time.is_some());
+}
+
+#[test]
+fn test_shared_tracer_into_inner_fails_while_shared() {
+    let parent = SharedTracer::from(Tracer::new("Parent", RunType::Chain, json!({})));
+    let clone = parent.clone();
+
+    let parent = parent.try_into_inner().err().unwrap();
+    drop(clone);
+    assert!(parent.try_into_inner().is_ok());
+}
+
+#[test]
+fn test_sibling_dotted_orders_sort_in_creation_order() {
+    let parent = Tracer::new("Parent", RunType::Chain, json!({}));
+    let children: Vec<Tracer> = (0..500)
+        .map(|i| parent.create_child(format!("Step {}", i), RunType::Tool, json!({})))
+        .collect();
+
+    let dotted: Vec<&String> = children.iter().map(|c| c.dotted_order().unwrap()).collect();
+    let mut sorted = dotted.clone();
+    sorted.sort();
+    assert_eq!(sorted, dotted);
+
+    // Start times are the real ones, so a child ended right away never has a
+    // negative duration; the tiebreak only moves the dotted_order segment
+    for pair in children.windows(2) {
+        assert!(pair[0].run().start_time <= pair[1].run().start_time);
+    }
+    let mut last = parent.create_child("Last", RunType::Tool, json!({}));
+    last.end(json!({}));
+    assert!(last.run().end_time.unwrap() >= last.run().start_time);
+
+    // Segments keep LangSmith's `{time}Z{id}` format, never before the start time
+    for child in &children {
+        let segment = child.dotted_order().unwrap().rsplit('.').next().unwrap();
+        let (time, id) = segment.split_once('Z').unwrap();
+        assert_eq!(id, child.run_id().to_string());
+        assert_eq!(time.len(), "YYYYMMDDTHHMMSSffffff".len());
+        let start = timestamp::dotted_order_time(&child.run().start_time);
+        assert!(time >= start.trim_end_matches('Z'));
+    }
+}
+
+#[tokio::test]
+async fn test_tracer_sends_previews_with_full_data() {
+    let sink = InMemorySink::new();
+    let client = Arc::new(LangSmithClient::in_memory(sink.clone()));
+    let document = "lorem ipsum ".repeat(100);
+    let mut tracer = Tracer::new("Summarize", RunType::Chain, json!({"document": document}))
+        .with_client(client)
+        .with_preview(20);
+
+    tracer.post().await.unwrap();
+    tracer.end(json!({"summary": "short"}));
+    tracer.patch().await.unwrap();
+
+    let posted = serde_json::to_value(&sink.recorded_runs()[0]).unwrap();
+    assert_eq!(posted["inputs"]["document"], json!(document));
+    assert_eq!(posted["inputs_preview"], r#"{"document":"lorem i...[truncated 1195 bytes]"#);
+    assert!(posted.get("outputs_preview").is_none());
+
+    let update = serde_json::to_value(&sink.recorded_patches()[0].1).unwrap();
+    assert_eq!(update["outputs"], json!({"summary": "short"}));
+    assert_eq!(update["outputs_preview"], r#"{"summary":"short"}"#);
+}
+
+#[tokio::test]
+async fn test_update_outputs_patches_merged_progress() {
+    let sink = InMemorySink::new();
+    let client = Arc::new(LangSmithClient::in_memory(sink.clone()));
+    let mut tracer = Tracer::new("Crawl", RunType::Chain, json!({})).with_client(client);
+
+    tracer.post().await.unwrap();
+    tracer
+        .update_outputs(json!({"progress": {"pages": 1}, "first": "a"}))
+        .await
+        .unwrap();
+    tracer
+        .update_outputs(json!({"progress": {"links": 5}}))
+        .await
+        .unwrap();
+    tracer.end(json!({"done": true}));
+    tracer.patch().await.unwrap();
+
+    let patches = sink.recorded_patches();
+    assert_eq!(patches.len(), 3);
+    assert_eq!(
+        patches[1].1.outputs,
+        Some(json!({"progress": {"pages": 1, "links": 5}, "first": "a"}))
+    );
+    assert!(patches[1].1.end_time.is_none());
+    assert_eq!(
+        patches[2].1.outputs,
+        Some(json!({"progress": {"pages": 1, "links": 5}, "first": "a", "done": true}))
+    );
+    assert_eq!(
+        tracer.run().outputs,
+        Some(json!({"progress": {"pages": 1, "links": 5}, "first": "a", "done": true}))
+    );
+}
+
+#[test]
+fn test_set_error_kind_records_category() {
+    let mut tracer = Tracer::new("Call model", RunType::Llm, json!({}));
+    tracer.set_error_kind(RunErrorKind::RateLimit, "429 Too Many Requests");
+
+    assert_eq!(tracer.run().error.as_deref(), Some("429 Too Many Requests"));
+    assert_eq!(tracer.run().extra["error_kind"], "rate_limit");
+
+    // The category and the error's type are kept side by side
+    tracer.set_error_details("reqwest::Error", "429 Too Many Requests", None);
+    assert_eq!(tracer.run().extra["error_kind"], "rate_limit");
+    assert_eq!(tracer.run().extra["error_type"], "reqwest::Error");
+}
+
+#[tokio::test]
+async fn test_fork_as_root_starts_linked_trace() {
+    let sink = InMemorySink::new();
+    let client = Arc::new(LangSmithClient::in_memory(sink.clone()));
+    let mut original = Tracer::new("Answer", RunType::Chain, json!({}))
+        .with_client(client)
+        .with_thread_id("thread-1".to_string())
+        .with_metadata("attempt", json!(1));
+    original.post().await.unwrap();
+    let child = original.create_child("Call model", RunType::Llm, json!({}));
+
+    let mut retry = child.fork_as_root("Answer (retry)", RunType::Chain, json!({}));
+    retry.post().await.unwrap();
+
+    assert!(retry.is_root());
+    assert_eq!(retry.depth(), 0);
+    assert_ne!(retry.run_id(), child.run_id());
+    assert_ne!(retry.trace_id(), original.trace_id());
+    assert_eq!(retry.trace_id(), Some(retry.run_id()));
+    assert_eq!(retry.metadata()["forked_from"], json!(child.run_id()));
+    assert!(retry.metadata().get("attempt").is_none());
+    assert_eq!(retry.thread_id().map(String::as_str), Some("thread-1"));
+    assert_eq!(sink.recorded_runs().len(), 2);
+}
+
+#[test]
+fn test_project_id_and_name_propagate_independently() {
+    let project_id = Uuid::new_v4();
+    let root = Tracer::new("Root", RunType::Chain, json!({}))
+        .with_project("named-project")
+        .with_project_id(project_id);
+    let child = root.create_child("Child", RunType::Tool, json!({}));
+    let remote =
+        Tracer::new("Remote", RunType::Tool, json!({})).with_parent_context(&root.child_context());
+
+    for tracer in [&root, &child, &remote] {
+        assert_eq!(tracer.session_name().map(String::as_str), Some("named-project"));
+        assert_eq!(tracer.session_id(), Some(&project_id.to_string()));
+    }
+    assert_eq!(root.context().session_id, Some(project_id));
+
+    // Either can be set without the other
+    let id_only = Tracer::new("Id only", RunType::Chain, json!({}))
+        .with_project_id(project_id)
+        .create_child("Child", RunType::Tool, json!({}));
+    assert_eq!(id_only.session_id(), Some(&project_id.to_string()));
+    let name_only = Tracer::new("Name only", RunType::Chain, json!({})).with_project("other");
+    assert!(name_only.session_id().is_none());
+    assert!(name_only.context().session_id.is_none());
+}
@@ -0,0 +1,8 @@
+use langsmith_rust::trace;
+
+#[trace]
+fn summarize(text: String) -> langsmith_rust::Result<String> {
+    Ok(text)
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use langsmith_rust::trace;
+
+#[trace]
+async fn summarize(text: String) -> String {
+    text
+}
+
+fn main() {}
@@ -0,0 +1,217 @@
+use langsmith_rust::error::LangSmithError;
+use langsmith_rust::models::messages::{HumanMessage, Message, SystemMessage};
+use langsmith_rust::models::run::{Run, RunType};
+use langsmith_rust::utils::redaction::{KeyRedactor, Redactor};
+use langsmith_rust::utils::serialization::{ensure_inputs_object, ensure_outputs_object};
+use langsmith_rust::utils::truncation::TruncationStrategy;
+use langsmith_rust::utils::validation::{
+    normalize_run, validate_run, validate_run_with, NonObjectInputs, ValidationOptions,
+};
+use serde_json::json;
+
+#[test]
+fn test_validate_run_accepts_object_inputs() {
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({"q": "hi"}));
+
+    assert!(validate_run(&run).is_ok());
+    assert_eq!(run.inputs, json!({"q": "hi"}));
+}
+
+#[test]
+fn test_validate_run_wraps_primitive_inputs() {
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!("hello"));
+
+    assert!(validate_run(&run).is_ok());
+    assert_eq!(run.inputs, json!("hello"));
+    normalize_run(&mut run);
+    assert_eq!(run.inputs, json!({"input": "hello"}));
+
+    let mut messages = Run::new("Chat".to_string(), RunType::Llm, json!([{"role": "user"}]));
+    normalize_run(&mut messages);
+    assert_eq!(messages.inputs, json!({"messages": [{"role": "user"}]}));
+}
+
+#[test]
+fn test_validate_run_rejects_primitive_inputs_when_configured() {
+    let run = Run::new("Test".to_string(), RunType::Chain, json!([1, 2]));
+    let options = ValidationOptions {
+        non_object_inputs: NonObjectInputs::Reject,
+        ..Default::default()
+    };
+
+    let result = validate_run_with(&run, &options);
+    assert!(matches!(result, Err(LangSmithError::Config(_))));
+}
+
+#[test]
+fn test_validate_run_rejects_empty_name() {
+    let run = Run::new(String::new(), RunType::Chain, json!({}));
+
+    assert!(matches!(validate_run(&run), Err(LangSmithError::Config(_))));
+}
+
+fn config_error(result: langsmith_rust::Result<()>) -> String {
+    match result {
+        Err(LangSmithError::Config(message)) => message,
+        other => panic!("expected config error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_validate_run_rejects_oversized_inputs() {
+    let run = Run::new("Test".to_string(), RunType::Chain, json!({"q": "x".repeat(200)}));
+    let options = ValidationOptions::default().with_max_input_bytes(100);
+
+    let message = config_error(validate_run_with(&run, &options));
+    assert!(message.contains("max_input_bytes (100)"), "{}", message);
+}
+
+#[test]
+fn test_validate_run_rejects_oversized_outputs() {
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({"q": "hi"}));
+    run.end(json!({"answer": "y".repeat(200)}));
+    let options = ValidationOptions::default()
+        .with_max_input_bytes(100)
+        .with_max_output_bytes(100);
+
+    let message = config_error(validate_run_with(&run, &options));
+    assert!(message.contains("max_output_bytes (100)"), "{}", message);
+}
+
+#[test]
+fn test_validate_run_rejects_deep_nesting() {
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({"a": {"b": [{"c": 1}]}}));
+    let options = ValidationOptions::default().with_max_depth(3);
+
+    let message = config_error(validate_run_with(&run, &options));
+    assert!(message.contains("inputs are nested 4 levels"), "{}", message);
+    assert!(message.contains("max_depth (3)"), "{}", message);
+
+    run.inputs = json!({"a": {"b": [1]}});
+    assert!(validate_run_with(&run, &options).is_ok());
+}
+
+#[test]
+fn test_validate_run_within_limits_passes() {
+    let inputs = json!({"messages": [{"role": "user"}]});
+    let mut run = Run::new("Test".to_string(), RunType::Llm, inputs);
+    run.end(json!({"output": "hello"}));
+    let options = ValidationOptions::default()
+        .with_max_input_bytes(1024)
+        .with_max_output_bytes(1024)
+        .with_max_depth(5);
+
+    assert!(validate_run_with(&run, &options).is_ok());
+}
+
+#[test]
+fn test_key_redactor_masks_nested_objects_and_arrays() {
+    let redactor = KeyRedactor::new(["password", "ssn"]);
+
+    let inputs = json!({
+        "user": {"name": "Ada", "ssn": "123-45-6789", "login": {"password": "hunter2"}},
+        "history": [
+            {"role": "user", "password": "secret"},
+            [{"ssn": "987-65-4321"}],
+            "plain text"
+        ],
+        "password": {"nested": "entire value is masked"}
+    });
+
+    assert_eq!(
+        redactor.redact_inputs(inputs),
+        json!({
+            "user": {"name": "Ada", "ssn": "[REDACTED]", "login": {"password": "[REDACTED]"}},
+            "history": [
+                {"role": "user", "password": "[REDACTED]"},
+                [{"ssn": "[REDACTED]"}],
+                "plain text"
+            ],
+            "password": "[REDACTED]"
+        })
+    );
+}
+
+#[test]
+fn test_key_redactor_leaves_other_values_untouched() {
+    let redactor = KeyRedactor::default().with_key("token");
+
+    assert_eq!(redactor.redact_outputs(json!("token")), json!("token"));
+    assert_eq!(
+        redactor.redact_outputs(json!({"answer": 42, "tokens": 10})),
+        json!({"answer": 42, "tokens": 10})
+    );
+}
+
+#[test]
+fn test_truncate_run_shortens_megabyte_string() {
+    let huge = "a".repeat(1024 * 1024);
+    let mut run = Run::new("Test".to_string(), RunType::Llm, json!({"document": huge, "n": 1}));
+    run.end(json!({"summary": "short", "chunks": ["b".repeat(2000)]}));
+
+    assert!(TruncationStrategy::bytes(1000).truncate_run_in_place(&mut run));
+
+    let expected = format!("{}...[truncated {} bytes]", "a".repeat(1000), 1024 * 1024 - 1000);
+    assert_eq!(run.inputs["document"], json!(expected));
+    assert_eq!(run.inputs["n"], json!(1));
+    let outputs = run.outputs.as_ref().unwrap();
+    assert_eq!(outputs["summary"], json!("short"));
+    assert!(outputs["chunks"][0].as_str().unwrap().ends_with("...[truncated 1000 bytes]"));
+    assert_eq!(run.extra["langsmith:truncated"], json!(true));
+}
+
+#[test]
+fn test_truncate_run_leaves_short_runs_alone() {
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({"q": "hi"}));
+
+    assert!(!TruncationStrategy::bytes(1000).truncate_run_in_place(&mut run));
+    assert_eq!(run.inputs, json!({"q": "hi"}));
+    assert!(!run.extra.contains_key("langsmith:truncated"));
+}
+
+#[test]
+fn test_truncate_run_cuts_on_char_boundary() {
+    // Each "é" is two bytes, so 5 bytes falls inside the third one
+    let mut run = Run::new("Test".to_string(), RunType::Chain, json!({"q": "éééé"}));
+
+    assert!(TruncationStrategy::bytes(5).truncate_run_in_place(&mut run));
+    assert_eq!(run.inputs["q"], json!("éé...[truncated 4 bytes]"));
+}
+
+#[test]
+fn test_truncation_char_limit_uses_same_marker() {
+    let strategy = TruncationStrategy::new(3);
+
+    assert_eq!(
+        strategy.truncate_value(json!({"q": ["éééé", "ok"]})),
+        json!({"q": ["ééé...[truncated 2 bytes]", "ok"]})
+    );
+}
+
+#[test]
+fn test_ensure_object_wraps_arrays_under_default_key() {
+    assert_eq!(ensure_inputs_object(vec![1, 2, 3]).unwrap(), json!({"input": [1, 2, 3]}));
+    assert_eq!(ensure_outputs_object("done").unwrap(), json!({"output": "done"}));
+    assert_eq!(ensure_inputs_object(Vec::<Message>::new()).unwrap(), json!({"input": []}));
+    assert_eq!(ensure_inputs_object(json!({"q": 1})).unwrap(), json!({"q": 1}));
+}
+
+#[test]
+fn test_ensure_object_wraps_message_lists_under_messages() {
+    let messages = vec![
+        Message::System(SystemMessage { content: "Be brief".to_string() }),
+        Message::Human(HumanMessage { content: "Hi".into() }),
+    ];
+
+    let inputs = ensure_inputs_object(&messages).unwrap();
+    assert_eq!(inputs["messages"][0]["role"], "system");
+    assert_eq!(inputs["messages"][1]["role"], "human");
+    assert!(inputs.get("input").is_none());
+
+    let outputs = ensure_outputs_object(json!([{"role": "ai", "content": "Hello"}])).unwrap();
+    assert_eq!(outputs, json!({"messages": [{"role": "ai", "content": "Hello"}]}));
+
+    // Only lists where every element carries a role count as messages
+    let mixed = ensure_inputs_object(json!([{"role": "human"}, {"text": "x"}])).unwrap();
+    assert!(mixed.get("input").is_some());
+}